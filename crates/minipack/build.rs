@@ -0,0 +1,44 @@
+use std::{
+  hash::{DefaultHasher, Hasher},
+  path::Path,
+};
+
+/// Sources that decide the content of the scan cache: the layout of its entries, the parsing and
+/// transforming of modules, which includes the preprocessor of the scanner, and the locked
+/// dependencies, oxc above all.
+const SCAN_INPUTS: [&str; 5] = [
+  "src/scan_stage",
+  "src/utils/parse_to_ecma_ast.rs",
+  "../minipack_common/src",
+  "../minipack_ecmascript/src",
+  "../../Cargo.lock",
+];
+
+/// Hash the sources of the scan stage into `MINIPACK_SCAN_FINGERPRINT`, which is part of the keys of
+/// the scan cache. Entries written by another build of minipack are never read, even if the version
+/// is the same.
+fn main() {
+  let mut hasher = DefaultHasher::new();
+  for input in SCAN_INPUTS {
+    println!("cargo:rerun-if-changed={input}");
+    hash_path(Path::new(input), &mut hasher);
+  }
+  println!("cargo:rustc-env=MINIPACK_SCAN_FINGERPRINT={:016x}", hasher.finish());
+}
+
+fn hash_path(path: &Path, hasher: &mut DefaultHasher) {
+  if path.is_dir() {
+    let mut entries = std::fs::read_dir(path)
+      .into_iter()
+      .flatten()
+      .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+      .collect::<Vec<_>>();
+    entries.sort();
+    for entry in entries {
+      hash_path(&entry, hasher);
+    }
+  } else if let Ok(content) = std::fs::read(path) {
+    hasher.write(path.to_string_lossy().as_bytes());
+    hasher.write(&content);
+  }
+}
@@ -12,7 +12,8 @@ async fn main() {
     input: Some(vec!["./entry.js".into()]),
     cwd: Some(root.normalize()),
     ..Default::default()
-  });
+  })
+  .unwrap();
 
   let _ = bundler.build(true).await;
 }
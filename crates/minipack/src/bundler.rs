@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
-use minipack_common::BundlerOptions;
+use minipack_common::{BundlerOptions, EntryPointKind, ModuleGraph, OutputAssetKind};
 use minipack_error::BuildResult;
-use minipack_fs::{FileSystem, OsFileSystem};
+use minipack_fs::OsFileSystem;
 use minipack_resolver::Resolver;
 
 use crate::{
@@ -10,6 +10,7 @@ use crate::{
   link_stage::{LinkStage, LinkStageOutput},
   scan_stage::{ScanStage, ScanStageOutput},
   types::{SharedOptions, SharedResolver, bundle_output::BundleOutput},
+  utils::write_output,
 };
 
 pub struct Bundler {
@@ -19,46 +20,76 @@ pub struct Bundler {
 }
 
 impl Bundler {
-  pub fn new(options: BundlerOptions) -> Self {
-    let options = crate::utils::normalize_bundler_options(options);
+  pub fn new(options: BundlerOptions) -> BuildResult<Self> {
+    Ok(Self::with_normalized_options(crate::utils::normalize_bundler_options(options)?))
+  }
+
+  pub(crate) fn with_normalized_options(options: SharedOptions) -> Self {
     let resolver = Arc::new(Resolver::new(options.platform, options.cwd.clone(), OsFileSystem));
     Bundler { fs: OsFileSystem, options, resolver }
   }
 
   pub async fn build(&mut self, is_write: bool) -> BuildResult<BundleOutput> {
-    if self.options.input.is_empty() {
+    if self.options.input.is_empty() && self.options.stdin.is_none() {
       return Err(anyhow::anyhow!("You must supply `options.input`."))?;
     }
 
     let scan_stage_output = self.scan().await?;
     let link_stage_output = self.link(scan_stage_output).await;
-    let generate_stage_output = self.generate(link_stage_output).await?;
-
-    if is_write {
-      let dist = self.options.cwd.join(&self.options.dir);
-      self.fs.create_dir_all(&dist).map_err(|err| {
-        anyhow::anyhow!("Couldn't create output directory: {:?}", dist).context(err)
-      })?;
-      for chunk in &generate_stage_output.assets {
-        let filename = dist.join(&chunk.filename);
-        if let Some(p) = filename.parent() {
-          if !self.fs.exists(p) {
-            self.fs.create_dir_all(p).unwrap();
-          }
-        }
-        self
-          .fs
-          .write(&filename, chunk.content.as_bytes())
-          .map_err(|err| anyhow::anyhow!("Failed to write file {filename:?}").context(err))?;
+    let mut generate_stage_output = self.generate(link_stage_output).await?;
+
+    if self.options.stdout {
+      let chunks = generate_stage_output
+        .assets
+        .iter()
+        .filter(|asset| !matches!(asset.kind, OutputAssetKind::Asset))
+        .count();
+      if chunks != 1 {
+        return Err(anyhow::anyhow!(
+          "Invalid value for `options.stdout` - the build produces {chunks} chunks but only a single chunk can be printed."
+        ))?;
       }
     }
 
+    if is_write && !self.options.stdout {
+      generate_stage_output.write_report =
+        Some(write_output(&self.fs, &self.options, &generate_stage_output.assets)?);
+    }
+
     Ok(generate_stage_output)
   }
 
+  /// Builds the module graph without generating any output, with the chunk graph if `with_chunks`.
+  pub async fn graph(&mut self, with_chunks: bool) -> BuildResult<ModuleGraph> {
+    if self.options.input.is_empty() && self.options.stdin.is_none() {
+      return Err(anyhow::anyhow!("You must supply `options.input`."))?;
+    }
+
+    let scan_stage_output = self.scan().await?;
+    let link_stage_output = self.link(scan_stage_output).await;
+    GenerateStage::new(self.fs, link_stage_output, self.options.clone())
+      .collect_module_graph(with_chunks)
+      .await
+  }
+
   #[inline]
   async fn scan(&self) -> BuildResult<ScanStageOutput> {
-    ScanStage::new(self.fs, self.options.clone(), self.resolver.clone()).scan().await
+    let scan_stage_output =
+      ScanStage::new(self.fs, self.options.clone(), self.resolver.clone()).scan().await?;
+
+    // An html entry may bring several scripts, so the resolved entries are counted, not the inputs.
+    let entries_count = scan_stage_output
+      .entry_points
+      .iter()
+      .filter(|entry| entry.kind == EntryPointKind::UserDefined)
+      .count();
+    if self.options.inline_dynamic_imports && entries_count > 1 {
+      return Err(anyhow::anyhow!(
+        "Invalid value for `options.inline_dynamic_imports` - multiple inputs are not supported when inlining dynamic imports."
+      ))?;
+    }
+
+    Ok(scan_stage_output)
   }
 
   #[inline]
@@ -68,9 +99,35 @@ impl Bundler {
 
   #[inline]
   async fn generate(&self, link_stage_output: LinkStageOutput) -> BuildResult<BundleOutput> {
-    GenerateStage::new(link_stage_output, self.options.clone()).generate().await
+    GenerateStage::new(self.fs, link_stage_output, self.options.clone()).generate().await
   }
 }
 
 #[test]
 fn test_rust_syntax_errors() {}
+
+#[tokio::test]
+async fn test_stdout_needs_a_single_chunk() {
+  use minipack_common::InputItem;
+  use std::path::Path;
+
+  let build = |inline_dynamic_imports: bool| async move {
+    let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/preload");
+    let mut bundler = Bundler::new(BundlerOptions {
+      cwd: Some(cwd),
+      input: Some(vec![InputItem { name: None, import: "./main.js".to_string() }]),
+      stdout: Some(true),
+      inline_dynamic_imports: Some(inline_dynamic_imports),
+      ..BundlerOptions::default()
+    })
+    .unwrap();
+    bundler.build(true).await
+  };
+
+  let Err(errors) = build(false).await else { panic!("two chunks can't be printed") };
+  assert!(errors[0].to_string().contains("only a single chunk can be printed"));
+
+  let output = build(true).await.unwrap();
+  assert!(output.write_report.is_none());
+  assert_eq!(output.assets.len(), 1);
+}
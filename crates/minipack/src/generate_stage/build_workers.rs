@@ -0,0 +1,137 @@
+use std::{path::Path, sync::Arc};
+
+use minipack_common::{
+  EsModuleFlag, ImportRecordMeta, InputItem, Module, ModuleIdx, NormalizedBundlerOptions,
+  OutputAsset, OutputAssetKind, OutputExports, OutputFormat, RuntimeOptions,
+};
+use minipack_error::BuildResult;
+use minipack_utils::path_ext::PathExt;
+use rustc_hash::FxHashMap;
+
+use crate::bundler::Bundler;
+
+use super::GenerateStage;
+
+#[derive(Default)]
+pub struct WorkerOutputs {
+  /// Filenames of the entry chunks of workers, relative to the output directory.
+  pub filenames: FxHashMap<ModuleIdx, String>,
+  pub assets: Vec<OutputAsset>,
+}
+
+impl GenerateStage {
+  /// Workers run in a global scope of their own, so each of them is bundled by a separate build
+  /// with its own chunk graph instead of sharing chunks with the entries that spawn it.
+  pub async fn build_workers(&mut self) -> BuildResult<WorkerOutputs> {
+    let mut outputs = WorkerOutputs::default();
+
+    for entry in &self.link_stage_output.worker_entries {
+      let module_table = &self.link_stage_output.module_table;
+      let id = module_table[entry.idx].id();
+
+      // Classic workers can't use `import` statements, neither could they load `cjs` chunks.
+      let is_module_worker = module_table.iter().filter_map(Module::as_normal).all(|module| {
+        module.import_records.iter().all(|rec| {
+          !rec.kind.is_worker()
+            || rec.state != entry.idx
+            || rec.meta.contains(ImportRecordMeta::IS_MODULE_WORKER)
+        })
+      });
+      let format = if is_module_worker && self.options.format.is_esm() {
+        OutputFormat::Esm
+      } else {
+        OutputFormat::Iife
+      };
+
+      // Only the options that shape the code of the worker are inherited, the ones of the files
+      // emitted next to the entries, like the manifest, belong to the main build.
+      let options = NormalizedBundlerOptions {
+        cwd: self.options.cwd.clone(),
+        input: vec![InputItem {
+          name: Some(Path::new(id).representative_file_name().into_owned()),
+          import: id.to_string(),
+        }],
+        stdin: None,
+        platform: self.options.platform,
+        dir: self.options.dir.clone(),
+        file: None,
+        empty_out_dir: false,
+        stdout: false,
+        format,
+        entry_filenames: self.options.chunk_filenames.clone(),
+        chunk_filenames: self.options.chunk_filenames.clone(),
+        inline_dynamic_imports: self.options.inline_dynamic_imports
+          || matches!(format, OutputFormat::Iife),
+        strict_execution_order: self.options.strict_execution_order,
+        manifest: None,
+        integrity: None,
+        legal_comments: self.options.legal_comments,
+        exports: OutputExports::default(),
+        es_module: EsModuleFlag::default(),
+        interop: self.options.interop,
+        dts: false,
+        size_limits: vec![],
+        // Scripts can't import the external runtime, and the main build emits it already.
+        runtime: match (&self.options.runtime, format) {
+          (RuntimeOptions::External { specifier, .. }, OutputFormat::Esm) => {
+            RuntimeOptions::External { specifier: specifier.clone(), filename: None }
+          }
+          _ => RuntimeOptions::Inline,
+        },
+        keep_names: self.options.keep_names,
+        generated_code: self.options.generated_code,
+        hoist_transitive_imports: self.options.hoist_transitive_imports,
+        // Classic workers are scripts, the main build already warns that the option is ignored.
+        preload_dynamic_deps: self.options.preload_dynamic_deps && format.is_esm(),
+        minify: self.options.minify,
+        // The cache evicts the entries a build didn't use, i.e. the ones of the main build.
+        cache_dir: None,
+        unused_code: None,
+        hmr: false,
+        hmr_reused_modules: vec![],
+        ci: self.options.ci,
+      };
+
+      let output =
+        Box::pin(Bundler::with_normalized_options(Arc::new(options)).build(false)).await?;
+      let filename = output
+        .assets
+        .iter()
+        .find(|asset| matches!(asset.kind, OutputAssetKind::Entry))
+        .map(|asset| asset.filename.clone())
+        .expect("worker build should emit an entry chunk");
+
+      outputs.filenames.insert(entry.idx, filename);
+      outputs.assets.extend(output.assets);
+      self.link_stage_output.warnings.extend(output.warnings);
+    }
+
+    Ok(outputs)
+  }
+}
+
+#[tokio::test]
+async fn test_worker_is_emitted_as_its_own_chunk() {
+  use minipack_common::BundlerOptions;
+
+  let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/worker");
+  let mut bundler = Bundler::new(BundlerOptions {
+    cwd: Some(cwd),
+    input: Some(vec![InputItem { name: None, import: "./main.js".to_string() }]),
+    // Workers aren't dynamic imports, they are neither inlined nor preloaded.
+    inline_dynamic_imports: Some(true),
+    preload_dynamic_deps: Some(true),
+    ..BundlerOptions::default()
+  })
+  .unwrap();
+  let output = bundler.build(false).await.unwrap();
+
+  let main = output.assets.iter().find(|asset| asset.filename == "main.js").unwrap();
+  let worker = output.assets.iter().find(|asset| asset.filename.starts_with("worker-")).unwrap();
+  assert!(matches!(worker.kind, OutputAssetKind::Entry));
+  assert!(worker.content.contains("self.onmessage"));
+  assert!(worker.content.contains("function reply(message)"));
+  assert!(main.content.contains(&format!("new URL(\"./{}\", import.meta.url)", worker.filename)));
+  assert!(!main.content.contains("self.onmessage"));
+  assert!(!main.content.contains("__preload"));
+}
@@ -0,0 +1,90 @@
+use minipack_common::{OutputAsset, SizeCompression};
+use minipack_error::BuildResult;
+use minipack_utils::{
+  compressed_size::{brotli_size, gzip_size},
+  rayon::{IntoParallelRefIterator, ParallelIterator},
+};
+
+use super::GenerateStage;
+
+impl GenerateStage {
+  /// Check the assets against `size_limits`. Exceeded limits are errors with `ci`, so nothing is
+  /// written, and warnings otherwise.
+  pub fn check_size_limits(
+    &self,
+    assets: &[OutputAsset],
+    warnings: &mut Vec<anyhow::Error>,
+  ) -> BuildResult<()> {
+    let exceeded = self
+      .options
+      .size_limits
+      .par_iter()
+      .flat_map_iter(|limit| {
+        assets
+          .iter()
+          .filter(|asset| glob_match::glob_match(&limit.pattern, &asset.filename))
+          .filter_map(move |asset| {
+            let content = asset.content.as_bytes();
+            let (size, label) = match limit.compression {
+              SizeCompression::None => (content.len(), ""),
+              SizeCompression::Gzip => (gzip_size(content), "gzipped "),
+              SizeCompression::Brotli => (brotli_size(content), "brotli-compressed "),
+            };
+            (size > limit.max_size).then(|| {
+              anyhow::anyhow!(
+                "{} exceeds the size limit of {:?} - {label}size is {size} bytes, the limit is {} bytes.",
+                asset.filename,
+                limit.pattern,
+                limit.max_size,
+              )
+            })
+          })
+      })
+      .collect::<Vec<_>>();
+
+    if self.options.ci && !exceeded.is_empty() {
+      return Err(exceeded)?;
+    }
+    warnings.extend(exceeded);
+    Ok(())
+  }
+}
+
+#[tokio::test]
+async fn test_size_limits() {
+  use std::path::Path;
+
+  use minipack_common::{BundlerOptions, InputItem, SizeLimit};
+
+  let build = |ci: bool| async move {
+    let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/preload");
+    let mut bundler = crate::Bundler::new(BundlerOptions {
+      cwd: Some(cwd),
+      input: Some(vec![InputItem { name: None, import: "./main.js".to_string() }]),
+      size_limits: Some(vec![
+        SizeLimit {
+          pattern: "main.js".to_string(),
+          max_size: 10,
+          compression: SizeCompression::Gzip,
+        },
+        SizeLimit { pattern: "*.css".to_string(), max_size: 0, compression: SizeCompression::None },
+      ]),
+      ci: Some(ci),
+      ..BundlerOptions::default()
+    })
+    .unwrap();
+    bundler.build(false).await
+  };
+
+  let output = build(false).await.unwrap();
+  assert_eq!(output.warnings.len(), 1);
+  let warning = output.warnings[0].to_string();
+  assert!(warning.starts_with(r#"main.js exceeds the size limit of "main.js" - gzipped size is "#));
+  assert!(warning.ends_with("the limit is 10 bytes."));
+
+  let Err(errors) = build(true).await else {
+    panic!("exceeded limits should fail the build in ci")
+  };
+  assert_eq!(errors.len(), 1);
+  assert_eq!(errors[0].to_string(), warning);
+}
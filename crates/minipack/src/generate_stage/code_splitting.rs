@@ -76,6 +76,12 @@ impl GenerateStage {
       if module_to_assigned[normal_module.idx] {
         continue;
       }
+      // The helpers of the external runtime are imported by each chunk that uses them.
+      if self.options.runtime.is_external()
+        && normal_module.idx == self.link_stage_output.runtime_module.idx
+      {
+        continue;
+      }
 
       module_to_assigned[normal_module.idx] = true;
 
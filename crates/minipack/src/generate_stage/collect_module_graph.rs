@@ -0,0 +1,99 @@
+use minipack_common::{
+  ChunkGraphNode, EntryPointKind, Module, ModuleGraph, ModuleGraphEdge, ModuleGraphEdgeKind,
+  ModuleGraphNode, RUNTIME_MODULE_ID,
+};
+use minipack_error::BuildResult;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use super::GenerateStage;
+
+impl GenerateStage {
+  /// Collect the module graph, and the chunk graph if `with_chunks`, without rendering any chunk.
+  pub async fn collect_module_graph(&mut self, with_chunks: bool) -> BuildResult<ModuleGraph> {
+    if !self.link_stage_output.errors.is_empty() {
+      return Err(std::mem::take(&mut self.link_stage_output.errors))?;
+    }
+
+    let module_table = &self.link_stage_output.module_table;
+    let entries = self
+      .link_stage_output
+      .entry_points
+      .iter()
+      .filter(|entry| matches!(entry.kind, EntryPointKind::UserDefined))
+      .map(|entry| entry.idx)
+      .collect::<FxHashSet<_>>();
+
+    let mut graph = ModuleGraph::default();
+    for module in module_table.iter().filter(|module| module.id() != RUNTIME_MODULE_ID) {
+      graph.modules.push(ModuleGraphNode {
+        id: module.stable_id().to_string(),
+        is_entry: entries.contains(&module.idx()),
+        is_external: module.is_external(),
+      });
+
+      let Module::Normal(module) = module else {
+        continue;
+      };
+      let mut seen = FxHashSet::default();
+      for rec in &module.import_records {
+        let importee = &module_table[rec.state];
+        if importee.id() == RUNTIME_MODULE_ID {
+          continue;
+        }
+        let kind = if rec.kind.is_static() {
+          ModuleGraphEdgeKind::Static
+        } else {
+          ModuleGraphEdgeKind::Dynamic
+        };
+        if seen.insert((rec.state, kind)) {
+          graph.edges.push(ModuleGraphEdge {
+            from: module.stable_id.to_string(),
+            to: importee.stable_id().to_string(),
+            kind,
+          });
+        }
+      }
+    }
+    graph.modules.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+
+    if with_chunks {
+      let mut chunk_graph = self.generate_chunks().await;
+      self.compute_cross_chunk_links(&mut chunk_graph);
+      self.generate_chunk_name_and_preliminary_filenames(&mut chunk_graph).await?;
+
+      let module_table = &self.link_stage_output.module_table;
+      let indices = chunk_graph
+        .sorted_chunk_idx_vec
+        .iter()
+        .enumerate()
+        .map(|(index, chunk_id)| (*chunk_id, index))
+        .collect::<FxHashMap<_, _>>();
+      let chunks = chunk_graph
+        .sorted_chunk_idx_vec
+        .iter()
+        .map(|chunk_id| {
+          let chunk = &chunk_graph.chunk_table[*chunk_id];
+          ChunkGraphNode {
+            name: chunk.name.as_deref().unwrap_or_default().to_string(),
+            modules: chunk
+              .modules
+              .iter()
+              .map(|idx| &module_table[*idx])
+              .filter(|module| module.id() != RUNTIME_MODULE_ID)
+              .map(|module| module.stable_id().to_string())
+              .collect(),
+            imports: chunk.cross_chunk_imports.iter().map(|id| indices[id]).collect(),
+            dynamic_imports: chunk
+              .cross_chunk_dynamic_imports
+              .iter()
+              .map(|id| indices[id])
+              .collect(),
+          }
+        })
+        .collect();
+      graph.chunks = Some(chunks);
+    }
+
+    Ok(graph)
+  }
+}
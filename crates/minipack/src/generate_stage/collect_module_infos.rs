@@ -0,0 +1,48 @@
+use minipack_common::{Module, ModuleIdx, ModuleInfo, RUNTIME_MODULE_ID};
+
+use super::GenerateStage;
+
+impl GenerateStage {
+  /// Collect a snapshot of the module graph for tools built on top of the bundler, e.g. the dev server.
+  pub fn collect_module_infos(&self) -> Vec<ModuleInfo> {
+    let module_table = &self.link_stage_output.module_table;
+
+    module_table
+      .iter()
+      .filter_map(Module::as_normal)
+      .filter(|module| module.id.as_ref() != RUNTIME_MODULE_ID)
+      .map(|module| {
+        let id_of = |idx: ModuleIdx| module_table[idx].id().to_string();
+        let (static_records, dynamic_records): (Vec<_>, Vec<_>) = module
+          .import_records
+          .iter()
+          .filter(|rec| !rec.kind.is_worker())
+          .partition(|rec| rec.kind.is_static());
+        ModuleInfo {
+          id: module.id.to_string(),
+          stable_id: module.stable_id.clone(),
+          is_entry: module.is_user_defined_entry,
+          exports: self.link_stage_output.metadata[module.idx]
+            .sorted_resolved_exports
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+          imported_ids: static_records.into_iter().map(|rec| id_of(rec.state)).collect(),
+          dynamically_imported_ids: dynamic_records
+            .into_iter()
+            .map(|rec| id_of(rec.state))
+            .collect(),
+          hmr_self_accepting: module.meta.is_hmr_self_accepting(),
+          hmr_accepted_deps: module
+            .hmr_accepted_deps
+            .iter()
+            .map(|rec_idx| {
+              let rec = &module.import_records[*rec_idx];
+              (rec.specifier.to_string(), id_of(rec.state))
+            })
+            .collect(),
+        }
+      })
+      .collect()
+  }
+}
@@ -0,0 +1,175 @@
+use std::path::{Path, PathBuf};
+
+use minipack_common::{
+  Module, RUNTIME_MODULE_ID, SymbolOrMemberExprRef, SymbolRef, UnusedCodeOptions, UnusedCodeReport,
+  UnusedExports,
+};
+use minipack_fs::FileSystem;
+use rustc_hash::FxHashSet;
+use sugar_path::SugarPath;
+
+use super::GenerateStage;
+
+impl GenerateStage {
+  /// Collect the code that tree shaking found unused. An export is unused if no other module
+  /// imports it, even if the module uses it itself. Exports of entries are the interface of their
+  /// chunks, they are always used.
+  pub fn collect_unused_code(&self, options: &UnusedCodeOptions) -> UnusedCodeReport {
+    let module_table = &self.link_stage_output.module_table;
+    let symbol_ref_db = &self.link_stage_output.symbol_ref_db;
+    // Same as tree shaking, symbols aliased to a namespace property are used via the namespace.
+    let canonical_ref_for = |symbol_ref: SymbolRef| {
+      let canonical_ref = symbol_ref_db.canonical_ref_for(symbol_ref);
+      symbol_ref_db
+        .get(canonical_ref)
+        .namespace_alias
+        .as_ref()
+        .map_or(canonical_ref, |alias| alias.namespace_ref)
+    };
+
+    // Symbols of other modules referenced by the included statements of a module, which is how
+    // imports are used. A namespace object in there uses every export of its module.
+    let mut imported_refs = FxHashSet::default();
+    for module in module_table.iter().filter_map(Module::as_normal) {
+      let resolved_map = &self.link_stage_output.metadata[module.idx].resolved_member_expr_refs;
+      for stmt_info in module.stmt_infos.iter().filter(|stmt_info| stmt_info.is_included) {
+        for reference_ref in &stmt_info.referenced_symbols {
+          let symbol_ref = match reference_ref {
+            SymbolOrMemberExprRef::Symbol(symbol_ref) => Some(*symbol_ref),
+            SymbolOrMemberExprRef::MemberExpr(member_expr) => {
+              member_expr.resolved_symbol_ref(resolved_map)
+            }
+          };
+          if let Some(canonical_ref) = symbol_ref.map(canonical_ref_for) {
+            if canonical_ref.owner != module.idx {
+              imported_refs.insert(canonical_ref);
+            }
+          }
+        }
+      }
+    }
+    let entries =
+      self.link_stage_output.entry_points.iter().map(|entry| entry.idx).collect::<FxHashSet<_>>();
+
+    // Modules of workers are tree shaken by the worker builds, they are only reached here.
+    let mut worker_modules = FxHashSet::default();
+    let mut stack =
+      self.link_stage_output.worker_entries.iter().map(|entry| entry.idx).collect::<Vec<_>>();
+    while let Some(idx) = stack.pop() {
+      if let Some(module) = module_table[idx].as_normal() {
+        if worker_modules.insert(idx) {
+          stack.extend(module.import_records.iter().map(|rec| rec.state));
+        }
+      }
+    }
+
+    let mut report = UnusedCodeReport::default();
+    for module in module_table.iter().filter_map(Module::as_normal) {
+      if module.id.as_ref() == RUNTIME_MODULE_ID {
+        continue;
+      }
+      if !module.meta.is_included() && worker_modules.contains(&module.idx) {
+        continue;
+      }
+      if !module.meta.is_included() || !module.stmt_infos.iter().any(|info| info.is_included) {
+        report.unused_modules.push(module.stable_id.clone());
+        continue;
+      }
+      if entries.contains(&module.idx) || imported_refs.contains(&module.namespace_object_ref) {
+        continue;
+      }
+      let mut names = module
+        .named_exports
+        .iter()
+        .filter(|(_, local_export)| {
+          !imported_refs.contains(&canonical_ref_for(local_export.referenced))
+        })
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<_>>();
+      if !names.is_empty() {
+        names.sort_unstable();
+        report.unused_exports.push(UnusedExports { module: module.stable_id.clone(), names });
+      }
+    }
+    report.unused_exports.sort_unstable_by(|a, b| a.module.cmp(&b.module));
+    report.unused_modules.sort_unstable();
+
+    if let Some(source_root) = &options.source_root {
+      let reached =
+        module_table.iter().map(|module| Path::new(module.id())).collect::<FxHashSet<_>>();
+      let out_dir = self.options.cwd.join(&self.options.dir).normalize();
+      let mut files = vec![];
+      collect_source_files(
+        &self.fs,
+        &self.options.cwd.join(source_root).normalize(),
+        &out_dir,
+        &mut files,
+      );
+      report.unreachable_files = files
+        .into_iter()
+        .filter(|file| !reached.contains(file.as_path()))
+        .map(|file| file.relative(&self.options.cwd).to_slash_lossy().into_owned())
+        .collect();
+      report.unreachable_files.sort_unstable();
+    }
+
+    report
+  }
+}
+
+/// Recursively collect the files that could be loaded as modules, skipping `node_modules`, hidden
+/// directories and the output directory.
+fn collect_source_files(fs: &dyn FileSystem, dir: &Path, out_dir: &Path, files: &mut Vec<PathBuf>) {
+  let Ok(entries) = fs.read_dir(dir) else {
+    return;
+  };
+  for path in entries {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+      continue;
+    };
+    let Ok(metadata) = fs.metadata(&path) else {
+      continue;
+    };
+    if metadata.is_dir() {
+      if name != "node_modules" && !name.starts_with('.') && path != out_dir {
+        collect_source_files(fs, &path, out_dir, files);
+      }
+    } else if is_source_file(name) {
+      files.push(path);
+    }
+  }
+}
+
+fn is_source_file(name: &str) -> bool {
+  let is_declaration = [".d.ts", ".d.mts", ".d.cts"].iter().any(|ext| name.ends_with(ext));
+  !is_declaration
+    && Path::new(name)
+      .extension()
+      .is_some_and(|ext| matches!(ext.to_str(), Some("js" | "mjs" | "cjs" | "ts" | "mts" | "cts")))
+}
+
+#[tokio::test]
+async fn test_unused_exports_used_by_the_module_itself() {
+  use minipack_common::{BundlerOptions, InputItem};
+
+  let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/unused_code");
+  let mut bundler = crate::Bundler::new(BundlerOptions {
+    cwd: Some(cwd),
+    input: Some(vec![InputItem { name: None, import: "./main.js".to_string() }]),
+    unused_code: Some(UnusedCodeOptions { source_root: Some(".".to_string()), filename: None }),
+    ..BundlerOptions::default()
+  })
+  .unwrap();
+  let output = bundler.build(false).await.unwrap();
+  let report = output.unused_code.unwrap();
+
+  // `helper` is included because `format` calls it, but no other module imports it.
+  let names = |module: &str| {
+    report.unused_exports.iter().find(|exports| exports.module == module).map(|e| e.names.clone())
+  };
+  assert_eq!(names("lib.js"), Some(vec!["helper".to_string(), "unused".to_string()]));
+  assert_eq!(names("main.js"), None);
+  assert_eq!(names("shapes.js"), None);
+  assert_eq!(report.unused_modules, Vec::<String>::new());
+  assert_eq!(report.unreachable_files, vec!["dead.js".to_string()]);
+}
@@ -24,6 +24,7 @@ type IndexChunkDependedSymbols = IndexVec<ChunkIdx, FxIndexSet<SymbolRef>>;
 type IndexChunkImportsFromExternalModules =
   IndexVec<ChunkIdx, FxHashMap<ModuleIdx, Vec<NamedImport>>>;
 type IndexChunkExportedSymbols = IndexVec<ChunkIdx, FxHashSet<SymbolRef>>;
+type IndexChunkImportsFromRuntime = IndexVec<ChunkIdx, Vec<SymbolRef>>;
 type IndexCrossChunkImports = IndexVec<ChunkIdx, FxHashSet<ChunkIdx>>;
 type IndexCrossChunkDynamicImports = IndexVec<ChunkIdx, FxIndexSet<ChunkIdx>>;
 type IndexImportsFromOtherChunks =
@@ -40,6 +41,7 @@ impl GenerateStage {
     let mut cross_chunk_imports = index_vec![FxHashSet::default(); chunk_graph.chunk_table.len()];
     let mut cross_chunk_dynamic_imports =
       index_vec![FxIndexSet::default(); chunk_graph.chunk_table.len()];
+    let mut imports_from_runtime = index_vec![Vec::new(); chunk_graph.chunk_table.len()];
 
     self.collect_depended_symbols(
       chunk_graph,
@@ -54,8 +56,13 @@ impl GenerateStage {
       &mut chunk_exported_symbols,
       &mut cross_chunk_imports,
       &mut imports_from_other_chunks,
+      &mut imports_from_runtime,
     );
 
+    if self.options.hoist_transitive_imports && self.options.format.is_esm() {
+      Self::hoist_transitive_imports(&mut cross_chunk_imports, &mut imports_from_other_chunks);
+    }
+
     self.deconflict_exported_names(
       chunk_graph,
       &chunk_exported_symbols,
@@ -79,6 +86,9 @@ impl GenerateStage {
       })
       .collect::<Vec<_>>();
 
+    // Chunks are imported in the execution order of their modules. Chunks with top-level await run
+    // concurrently with the ones imported after them, their modules are wrapped then and awaited
+    // in order by `init_foo()`, see `LinkStage::wrap_modules`.
     let sorted_imports_from_other_chunks = imports_from_other_chunks
       .into_iter()
       .collect_vec()
@@ -111,6 +121,7 @@ impl GenerateStage {
       sorted_imports_from_external_modules,
       sorted_cross_chunk_imports,
       cross_chunk_dynamic_imports,
+      imports_from_runtime,
     ))
     .par_bridge()
     .for_each(
@@ -120,12 +131,14 @@ impl GenerateStage {
         imports_from_external_modules,
         cross_chunk_imports,
         cross_chunk_dynamic_imports,
+        imports_from_runtime,
       )| {
         chunk.imports_from_other_chunks = sorted_imports_from_other_chunks;
         chunk.imports_from_external_modules = imports_from_external_modules;
         chunk.cross_chunk_imports = cross_chunk_imports;
         chunk.cross_chunk_dynamic_imports =
           cross_chunk_dynamic_imports.into_iter().collect::<Vec<_>>();
+        chunk.imports_from_runtime = imports_from_runtime;
       },
     );
   }
@@ -171,8 +184,15 @@ impl GenerateStage {
                   return;
                 }
                 if rec.kind.is_dynamic() {
-                  let importee_chunk = chunk_graph.module_to_chunk[importee.idx].unwrap();
-                  cross_chunk_dynamic_imports.insert(importee_chunk);
+                  // `import()` loads the entry chunk of the importee, which might only be a facade of the
+                  // chunk holding the module. Inlined dynamic imports have no entry chunk of their own.
+                  if let Some(importee_chunk) =
+                    chunk_graph.entry_module_to_chunk.get(&importee.idx).copied()
+                  {
+                    if importee_chunk != chunk_idx {
+                      cross_chunk_dynamic_imports.insert(importee_chunk);
+                    }
+                  }
                 }
               }
             })
@@ -188,6 +208,18 @@ impl GenerateStage {
               imports_from_external_modules.entry(importee.idx).or_default();
             });
 
+          // Wrapped modules are initialized by `init_foo()` where they are imported.
+          module
+            .import_records
+            .iter()
+            .filter(|rec| rec.kind.is_static())
+            .filter_map(|rec| self.link_stage_output.module_table[rec.state].as_normal())
+            .filter(|importee| importee.meta.is_included())
+            .filter_map(|importee| self.link_stage_output.metadata[importee.idx].wrapper_ref)
+            .for_each(|wrapper_ref| {
+              depended_symbols.insert(wrapper_ref);
+            });
+
           module.named_imports.iter().for_each(|(_, import)| {
             let rec = &module.import_records[import.record_id];
             if let Module::External(importee) = &self.link_stage_output.module_table[rec.state] {
@@ -243,6 +275,11 @@ impl GenerateStage {
             depended_symbols.insert(canonical_ref);
           }
 
+          // The entry chunk runs the wrapped entry module by `init_foo()`.
+          if let Some(wrapper_ref) = entry_meta.wrapper_ref {
+            depended_symbols.insert(wrapper_ref);
+          }
+
           if matches!(self.options.format, OutputFormat::Cjs) {
             depended_symbols
               .insert(self.link_stage_output.runtime_module.resolve_symbol("__toCommonJS"));
@@ -270,7 +307,9 @@ impl GenerateStage {
     chunk_exported_symbols: &mut IndexChunkExportedSymbols,
     cross_chunk_imports: &mut IndexCrossChunkImports,
     imports_from_other_chunks: &mut IndexImportsFromOtherChunks,
+    imports_from_runtime: &mut IndexChunkImportsFromRuntime,
   ) {
+    let runtime_idx = self.link_stage_output.runtime_module.idx;
     chunk_graph.chunk_table.iter_enumerated().for_each(|(chunk_id, chunk)| {
       let chunk_meta_imports = &chunk_depended_symbols[chunk_id];
       for import_ref in chunk_meta_imports.iter().copied() {
@@ -281,6 +320,10 @@ impl GenerateStage {
         if self.link_stage_output.module_table[import_ref.owner].is_external() {
           continue;
         }
+        if self.options.runtime.is_external() && import_ref.owner == runtime_idx {
+          imports_from_runtime[chunk_id].push(import_ref);
+          continue;
+        }
         let import_symbol = self.link_stage_output.symbol_ref_db.get(import_ref);
         let importee_chunk_id = import_symbol.chunk_id.unwrap_or_else(|| {
           let symbol_owner = &self.link_stage_output.module_table[import_ref.owner];
@@ -299,6 +342,9 @@ impl GenerateStage {
         }
       }
 
+      imports_from_runtime[chunk_id]
+        .sort_by_cached_key(|symbol_ref| symbol_ref.name(&self.link_stage_output.symbol_ref_db));
+
       // If this is an entry point, make sure we import all chunks belonging to this entry point,
       // even if there are no imports. We need to make sure these chunks are evaluated for their side effects too.
       if let ChunkKind::EntryPoint { bit: importer_chunk_bit, .. } = &chunk.kind {
@@ -319,6 +365,36 @@ impl GenerateStage {
     });
   }
 
+  /// Make each chunk import the chunks it depends on transitively, so that they are fetched along
+  /// with its direct imports instead of only after those are loaded.
+  fn hoist_transitive_imports(
+    cross_chunk_imports: &mut IndexCrossChunkImports,
+    imports_from_other_chunks: &mut IndexImportsFromOtherChunks,
+  ) {
+    let transitive_imports = cross_chunk_imports
+      .iter_enumerated()
+      .map(|(chunk_id, direct_imports)| {
+        let mut visited = FxHashSet::default();
+        let mut stack = direct_imports.iter().copied().collect_vec();
+        while let Some(importee_chunk_id) = stack.pop() {
+          if importee_chunk_id != chunk_id && visited.insert(importee_chunk_id) {
+            stack.extend(cross_chunk_imports[importee_chunk_id].iter().copied());
+          }
+        }
+        visited
+      })
+      .collect_vec();
+
+    for (chunk_id, transitive_imports) in cross_chunk_imports.indices().zip(transitive_imports) {
+      for importee_chunk_id in transitive_imports {
+        // Chunks without imported symbols are rendered as side-effect imports, i.e. `import './c.js'`.
+        if cross_chunk_imports[chunk_id].insert(importee_chunk_id) {
+          imports_from_other_chunks[chunk_id].entry(importee_chunk_id).or_default();
+        }
+      }
+    }
+  }
+
   fn deconflict_exported_names(
     &mut self,
     chunk_graph: &mut ChunkGraph,
@@ -373,3 +449,40 @@ impl GenerateStage {
     }
   }
 }
+
+#[tokio::test]
+async fn test_hoist_transitive_imports() {
+  use std::path::Path;
+
+  use minipack_common::{BundlerOptions, InputItem};
+
+  let build = |hoist_transitive_imports: bool| async move {
+    let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/hoist_imports");
+    let input = ["entry-a.js", "entry-b.js", "entry-c.js", "entry-d.js"]
+      .map(|import| InputItem { name: None, import: concat_string!("./", import) });
+    let mut bundler = crate::Bundler::new(BundlerOptions {
+      cwd: Some(cwd),
+      input: Some(input.to_vec()),
+      hoist_transitive_imports: Some(hoist_transitive_imports),
+      ..BundlerOptions::default()
+    })
+    .unwrap();
+    let assets = bundler.build(false).await.unwrap().assets;
+    ["b-", "c-", "d-"].map(|name| {
+      let asset = assets.iter().find(|asset| asset.filename.starts_with(name)).unwrap();
+      (asset.filename.clone(), asset.content.clone())
+    })
+  };
+
+  // Entry chunks import every chunk they depend on already, but the common chunk of `b.js` only
+  // imports the one of `c.js`, which imports the one of `d.js`.
+  let [(_, b), (c_filename, c), (d_filename, _)] = build(false).await;
+  assert!(c.starts_with(&format!("import {{ d }} from \"./{d_filename}\";")));
+  assert!(b.starts_with(&format!("import {{ c }} from \"./{c_filename}\";")));
+  assert!(!b.contains(&d_filename));
+
+  let [(_, b), (c_filename, _), (d_filename, _)] = build(true).await;
+  assert!(
+    b.starts_with(&format!("import \"./{d_filename}\";\nimport {{ c }} from \"./{c_filename}\";"))
+  );
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+use minipack_common::{
+  AssetIdx, ChunkIdx, ChunkKind, IntegrityAlgorithm, ModuleIdx, OutputAsset, OutputAssetKind,
+};
+use minipack_utils::concat_string;
+use oxc_index::IndexVec;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::graph::ChunkGraph;
+
+use super::GenerateStage;
+
+/// A record of the build manifest, which is modeled after Vite's `manifest.json`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestChunk<'a> {
+  file: &'a str,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  src: Option<&'a str>,
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  is_entry: bool,
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  is_dynamic_entry: bool,
+  /// Keys of the records that are statically imported by this chunk.
+  imports: Vec<&'a str>,
+  /// Keys of the records that are dynamically imported by this chunk.
+  dynamic_imports: Vec<&'a str>,
+  /// Stylesheets loaded along with this chunk by the html entries.
+  css: Vec<&'a str>,
+  /// Other files this chunk relies on, i.e. the workers it spawns and its linked legal comments.
+  assets: Vec<&'a str>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  integrity: Option<String>,
+}
+
+/// The emitted files the records of the manifest are made of.
+pub struct ManifestFiles<'a> {
+  /// Maps the chunks to their finalized asset in `assets`.
+  pub chunk_assets: &'a IndexVec<ChunkIdx, Option<AssetIdx>>,
+  pub assets: &'a [OutputAsset],
+  pub stylesheets: &'a FxHashMap<ChunkIdx, Vec<String>>,
+  pub worker_filenames: &'a FxHashMap<ModuleIdx, String>,
+}
+
+impl GenerateStage {
+  /// Generate the build manifest. Entry chunks are keyed by the stable id of their entry module, and
+  /// common chunks are keyed by `_` + their filename since they have no source file of their own.
+  pub fn generate_manifest(
+    &self,
+    filename: &str,
+    chunk_graph: &ChunkGraph,
+    files: &ManifestFiles,
+  ) -> OutputAsset {
+    let module_table = &self.link_stage_output.module_table;
+    let asset_of = |chunk_idx: ChunkIdx| {
+      let asset_idx = files.chunk_assets[chunk_idx].expect("chunk should be rendered to an asset");
+      &files.assets[asset_idx.index()]
+    };
+    let legal_filenames = files
+      .assets
+      .iter()
+      .filter(|asset| asset.filename.ends_with(".LEGAL.txt"))
+      .map(|asset| asset.filename.as_str())
+      .collect::<FxHashSet<_>>();
+
+    let keys = chunk_graph
+      .chunk_table
+      .iter_enumerated()
+      .map(|(chunk_idx, chunk)| match chunk.kind {
+        ChunkKind::EntryPoint { module, .. } => module_table[module].stable_id().to_string(),
+        ChunkKind::Common => format!("_{}", asset_of(chunk_idx).filename),
+      })
+      .collect::<IndexVec<ChunkIdx, _>>();
+
+    let manifest = chunk_graph
+      .chunk_table
+      .iter_enumerated()
+      .map(|(chunk_idx, chunk)| {
+        let asset = asset_of(chunk_idx);
+        let (src, is_entry, is_dynamic_entry) = match chunk.kind {
+          ChunkKind::EntryPoint { module, is_user_defined, .. } => {
+            (Some(module_table[module].stable_id()), is_user_defined, !is_user_defined)
+          }
+          ChunkKind::Common => (None, false, false),
+        };
+        let worker_filenames =
+          chunk.modules.iter().filter_map(|idx| module_table[*idx].as_normal());
+        let worker_filenames = worker_filenames
+          .flat_map(|module| &module.import_records)
+          .filter(|rec| rec.kind.is_worker())
+          .filter_map(|rec| files.worker_filenames.get(&rec.state))
+          .map(String::as_str);
+        let legal_filename = concat_string!(asset.filename, ".LEGAL.txt");
+        let legal_filename = legal_filenames.get(legal_filename.as_str()).copied();
+        let record = ManifestChunk {
+          file: &asset.filename,
+          src,
+          is_entry,
+          is_dynamic_entry,
+          imports: chunk.cross_chunk_imports.iter().map(|idx| keys[*idx].as_str()).collect(),
+          dynamic_imports: chunk
+            .cross_chunk_dynamic_imports
+            .iter()
+            .map(|idx| keys[*idx].as_str())
+            .collect(),
+          css: files
+            .stylesheets
+            .get(&chunk_idx)
+            .map(|filenames| filenames.iter().map(String::as_str).collect())
+            .unwrap_or_default(),
+          assets: worker_filenames.chain(legal_filename).unique().collect(),
+          integrity: self
+            .options
+            .integrity
+            .map(|algorithm| compute_integrity(algorithm, asset.content.as_bytes())),
+        };
+        (keys[chunk_idx].as_str(), record)
+      })
+      .collect::<BTreeMap<_, _>>();
+
+    let content = serde_json::to_string_pretty(&manifest).expect("manifest should be serializable");
+    OutputAsset { filename: filename.to_string(), content, kind: OutputAssetKind::Asset }
+  }
+}
+
+/// Compute a subresource integrity value, e.g. `sha384-<base64 digest>`.
+fn compute_integrity(algorithm: IntegrityAlgorithm, content: &[u8]) -> String {
+  let digest = match algorithm {
+    IntegrityAlgorithm::Sha256 => Sha256::digest(content).to_vec(),
+    IntegrityAlgorithm::Sha384 => Sha384::digest(content).to_vec(),
+    IntegrityAlgorithm::Sha512 => Sha512::digest(content).to_vec(),
+  };
+  format!("{algorithm}-{}", base64_simd::STANDARD.encode_to_string(digest))
+}
+
+#[test]
+fn test_compute_integrity() {
+  assert_eq!(
+    compute_integrity(IntegrityAlgorithm::Sha256, b"alert('Hello, world.');"),
+    "sha256-qznLcsROx4GACP2dm0UCKCzCG+HiZ1guq6ZZDob/Tng="
+  );
+}
+
+#[tokio::test]
+async fn test_manifest_lists_the_files_of_chunks() {
+  use minipack_common::{BundlerOptions, InputItem, LegalComments};
+  use std::path::Path;
+
+  let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/manifest");
+  let mut bundler = crate::Bundler::new(BundlerOptions {
+    cwd: Some(cwd),
+    input: Some(vec![InputItem { name: None, import: "./index.html".to_string() }]),
+    manifest: Some("manifest.json".to_string()),
+    legal_comments: Some(LegalComments::Linked),
+    ..BundlerOptions::default()
+  })
+  .unwrap();
+  let output = bundler.build(false).await.unwrap();
+
+  let filename = |prefix: &str| {
+    output.assets.iter().find(|asset| asset.filename.starts_with(prefix)).unwrap().filename.clone()
+  };
+  let manifest = output.assets.iter().find(|asset| asset.filename == "manifest.json").unwrap();
+  let manifest = serde_json::from_str::<serde_json::Value>(&manifest.content).unwrap();
+
+  let main = &manifest["main.js"];
+  assert_eq!(main["file"], "main.js");
+  assert_eq!(main["css"], serde_json::json!([filename("style-")]));
+  assert_eq!(main["assets"], serde_json::json!([filename("worker-"), "main.js.LEGAL.txt"]));
+  assert_eq!(main["dynamicImports"], serde_json::json!(["lazy.js"]));
+
+  let lazy = &manifest["lazy.js"];
+  assert_eq!(lazy["css"], serde_json::json!([]));
+  assert_eq!(lazy["assets"], serde_json::json!([]));
+}
@@ -1,12 +1,17 @@
 use std::sync::Arc;
 
-use minipack_common::{InstantiatedChunk, ModuleIdx, OutputFormat, Source};
+use minipack_common::{
+  ChunkKind, InstantiatedChunk, ModuleIdx, OutputAssetKind, OutputFormat, Source,
+};
 use minipack_error::BuildResult;
-use minipack_utils::rayon::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use minipack_utils::{
+  indexmap::FxIndexSet,
+  rayon::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator},
+};
 
 use crate::types::generator::{GenerateContext, GenerateOutput};
 
-use super::formats::{cjs::render_cjs, esm::render_esm};
+use super::formats::{cjs::render_cjs, esm::render_esm, iife::render_iife};
 
 pub struct RenderedModuleSource {
   pub module_idx: ModuleIdx,
@@ -22,14 +27,19 @@ pub struct EcmaGenerator;
 
 impl EcmaGenerator {
   pub async fn instantiate_chunk(ctx: &mut GenerateContext<'_>) -> BuildResult<GenerateOutput> {
-    let module_id_to_codegen_ret = std::mem::take(&mut ctx.module_id_to_codegen_ret);
+    let mut module_id_to_codegen_ret = std::mem::take(&mut ctx.module_id_to_codegen_ret);
+    let legal_comments = module_id_to_codegen_ret
+      .iter_mut()
+      .flatten()
+      .flat_map(|codegen_ret| std::mem::take(&mut codegen_ret.legal_comments))
+      .collect::<FxIndexSet<_>>();
     let rendered_module = ctx
       .chunk
       .modules
       .par_iter()
       .zip(module_id_to_codegen_ret)
-      .filter_map(|(id, code)| {
-        ctx.link_stage_output.module_table[*id].as_normal().map(|m| (m, code.unwrap()))
+      .filter_map(|(id, codegen_ret)| {
+        ctx.link_stage_output.module_table[*id].as_normal().map(|m| (m, codegen_ret.unwrap().code))
       })
       .map(|(m, code)| {
         RenderedModuleSource::new(
@@ -42,6 +52,7 @@ impl EcmaGenerator {
     let source_joiner = match ctx.options.format {
       OutputFormat::Esm => render_esm(ctx, &rendered_module),
       OutputFormat::Cjs => render_cjs(ctx, &rendered_module)?,
+      OutputFormat::Iife => render_iife(ctx, &rendered_module)?,
     };
 
     let content = source_joiner.join();
@@ -49,7 +60,16 @@ impl EcmaGenerator {
       ctx.chunk.preliminary_filename.clone().expect("should have preliminary filename");
 
     Ok(GenerateOutput {
-      chunks: vec![InstantiatedChunk { content, preliminary_filename }],
+      chunks: vec![InstantiatedChunk {
+        chunk_idx: ctx.chunk_idx,
+        content,
+        preliminary_filename,
+        legal_comments: legal_comments.into_iter().collect(),
+        kind: match ctx.chunk.kind {
+          ChunkKind::EntryPoint { .. } => OutputAssetKind::Entry,
+          ChunkKind::Common => OutputAssetKind::Common,
+        },
+      }],
       warnings: std::mem::take(&mut ctx.warnings),
     })
   }
@@ -1,10 +1,14 @@
-use minipack_common::SourceJoiner;
+use minipack_common::{Interop, RuntimeOptions, SourceJoiner};
 use minipack_error::BuildResult;
 use minipack_utils::concat_string;
 
 use crate::{
-  generate_stage::generators::ecmascript::RenderedModuleSource, types::generator::GenerateContext,
-  utils::chunk::render_chunk_exports::render_chunk_exports,
+  generate_stage::generators::ecmascript::RenderedModuleSource,
+  types::generator::GenerateContext,
+  utils::chunk::{
+    render_chunk_exports::{determine_export_mode, render_chunk_exports},
+    render_wrapped_entry_init::render_wrapped_entry_init,
+  },
 };
 
 #[inline]
@@ -38,9 +42,14 @@ fn render_modules_with_peek_runtime_module_at_first<'a>(
 }
 
 pub fn render_cjs<'code>(
-  ctx: &GenerateContext<'_>,
+  ctx: &mut GenerateContext<'_>,
   module_sources: &'code [RenderedModuleSource],
 ) -> BuildResult<SourceJoiner<'code>> {
+  let export_mode = match ctx.chunk.entry_module(&ctx.link_stage_output.module_table) {
+    Some(entry_module) => Some(determine_export_mode(ctx, entry_module)?),
+    None => None,
+  };
+
   let mut source_joiner = SourceJoiner::default();
   let mut modules = ctx.renderable_ecma_modules().peekable();
 
@@ -57,7 +66,11 @@ pub fn render_cjs<'code>(
     render_cjs_chunk_imports(ctx),
   );
 
-  if let Some(exports) = render_chunk_exports(ctx) {
+  if let Some(init) = render_wrapped_entry_init(ctx) {
+    source_joiner.append_source(init);
+  }
+
+  if let Some(exports) = render_chunk_exports(ctx, export_mode) {
     source_joiner.append_source(exports);
   }
 
@@ -68,6 +81,24 @@ pub fn render_cjs<'code>(
 fn render_cjs_chunk_imports(ctx: &GenerateContext<'_>) -> String {
   let mut s = String::new();
 
+  // The helpers are required first, since `__toESM(require(...))` relies on them.
+  if let RuntimeOptions::External { specifier, .. } = &ctx.options.runtime {
+    let bindings = ctx
+      .runtime_imports()
+      .map(|(imported, local)| {
+        if imported == local { imported.to_string() } else { concat_string!(imported, ": ", local) }
+      })
+      .collect::<Vec<_>>();
+    if !bindings.is_empty() {
+      s.push_str(ctx.options.generated_code.binding_keyword());
+      s.push_str(" { ");
+      s.push_str(&bindings.join(", "));
+      s.push_str(" } = require(\"");
+      s.push_str(specifier);
+      s.push_str("\");\n");
+    }
+  }
+
   // render imports from other chunks
   ctx.chunk.imports_from_other_chunks.iter().for_each(|(exporter_id, items)| {
     let chunk = &ctx.chunk_graph.chunk_table[*exporter_id];
@@ -75,7 +106,8 @@ fn render_cjs_chunk_imports(ctx: &GenerateContext<'_>) -> String {
     if items.is_empty() {
       s.push_str(&require_path);
     } else {
-      s.push_str("const ");
+      s.push_str(ctx.options.generated_code.binding_keyword());
+      s.push(' ');
       s.push_str(&ctx.chunk.require_binding_names_for_other_chunks[exporter_id]);
       s.push_str(" = ");
       s.push_str(&require_path);
@@ -91,20 +123,26 @@ fn render_cjs_chunk_imports(ctx: &GenerateContext<'_>) -> String {
     let require_path_str = concat_string!("require(\"", &importee.name, "\")");
 
     if ctx.link_stage_output.used_symbol_refs.contains(&importee.namespace_ref) {
-      let to_esm_fn_name = ctx.finalized_string_pattern_for_symbol_ref(
-        ctx.link_stage_output.runtime_module.resolve_symbol("__toESM"),
-        ctx.chunk_idx,
-        &ctx.chunk.canonical_names,
-      );
-
       let external_module_symbol_name = &ctx.chunk.canonical_names[&importee.namespace_ref];
-      s.push_str("const ");
+      s.push_str(ctx.options.generated_code.binding_keyword());
+      s.push(' ');
       s.push_str(external_module_symbol_name);
       s.push_str(" = ");
-      s.push_str(&to_esm_fn_name);
-      s.push('(');
-      s.push_str(&require_path_str);
-      s.push_str(");\n");
+      if matches!(ctx.options.interop, Interop::EsModule) {
+        s.push_str(&require_path_str);
+      } else {
+        let to_esm_fn_name = ctx.finalized_string_pattern_for_symbol_ref(
+          ctx.link_stage_output.runtime_module.resolve_symbol("__toESM"),
+          ctx.chunk_idx,
+          &ctx.chunk.canonical_names,
+        );
+        s.push_str(&to_esm_fn_name);
+        s.push('(');
+        s.push_str(&require_path_str);
+        // `isNodeMode` of `__toESM` ignores `__esModule` of the module.
+        s.push_str(if matches!(ctx.options.interop, Interop::Node) { ", 1)" } else { ")" });
+      }
+      s.push_str(";\n");
     } else if importee.side_effects.has_side_effects() {
       s.push_str(&require_path_str);
       s.push_str(";\n");
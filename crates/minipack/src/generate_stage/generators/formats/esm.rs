@@ -1,11 +1,15 @@
 use arcstr::ArcStr;
 use itertools::Itertools;
-use minipack_common::{SourceJoiner, Specifier};
+use minipack_common::{RuntimeOptions, SourceJoiner, Specifier};
 use minipack_utils::{concat_string, ecmascript::to_module_import_export_name};
 
 use crate::{
-  generate_stage::generators::ecmascript::RenderedModuleSource, types::generator::GenerateContext,
-  utils::chunk::render_chunk_exports::render_chunk_exports,
+  generate_stage::generators::ecmascript::RenderedModuleSource,
+  types::generator::GenerateContext,
+  utils::chunk::{
+    render_chunk_exports::render_chunk_exports,
+    render_wrapped_entry_init::render_wrapped_entry_init,
+  },
 };
 
 pub fn render_esm<'code>(
@@ -38,7 +42,11 @@ pub fn render_esm<'code>(
     },
   );
 
-  if let Some(exports) = render_chunk_exports(ctx) {
+  if let Some(init) = render_wrapped_entry_init(ctx) {
+    source_joiner.append_source(init);
+  }
+
+  if let Some(exports) = render_chunk_exports(ctx, None) {
     if !exports.is_empty() {
       source_joiner.append_source(exports);
     }
@@ -50,6 +58,22 @@ pub fn render_esm<'code>(
 fn render_esm_chunk_imports(ctx: &GenerateContext<'_>) -> String {
   let mut s = String::new();
 
+  if let RuntimeOptions::External { specifier, .. } = &ctx.options.runtime {
+    let specifiers = ctx
+      .runtime_imports()
+      .map(|(imported, local)| {
+        if imported == local {
+          imported.to_string()
+        } else {
+          concat_string!(imported, " as ", local)
+        }
+      })
+      .collect::<Vec<_>>();
+    if !specifiers.is_empty() {
+      s.push_str(&create_import_declaration(specifiers, &[], &specifier.into()));
+    }
+  }
+
   ctx.chunk.imports_from_other_chunks.iter().for_each(|(exporter_id, items)| {
     let importee_chunk = &ctx.chunk_graph.chunk_table[*exporter_id];
     let mut default_alias = vec![];
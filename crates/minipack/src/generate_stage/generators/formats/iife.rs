@@ -0,0 +1,77 @@
+use minipack_common::{ChunkKind, SourceJoiner};
+use minipack_error::BuildResult;
+
+use crate::{
+  generate_stage::generators::ecmascript::RenderedModuleSource,
+  types::generator::GenerateContext,
+  utils::chunk::{
+    render_chunk_exports::get_export_items, render_wrapped_entry_init::render_wrapped_entry_init,
+  },
+};
+
+/// IIFE chunks are plain scripts, so they can't load other chunks or external modules.
+pub fn render_iife<'code>(
+  ctx: &mut GenerateContext<'_>,
+  module_sources: &'code [RenderedModuleSource],
+) -> BuildResult<SourceJoiner<'code>> {
+  let module_table = &ctx.link_stage_output.module_table;
+
+  if !matches!(ctx.chunk.kind, ChunkKind::EntryPoint { is_user_defined: true, .. })
+    || !ctx.chunk.imports_from_other_chunks.is_empty()
+  {
+    Err(anyhow::anyhow!(
+      r#"Invalid value "iife" for option "format" - code splitting is not supported, use `inline_dynamic_imports` to bundle dynamic imports into the entry chunk."#
+    ))?;
+  }
+
+  let external =
+    ctx.chunk.modules.iter().filter_map(|idx| module_table[*idx].as_normal()).find_map(|module| {
+      module.import_records.iter().find_map(|rec| {
+        (!rec.kind.is_worker()).then(|| module_table[rec.state].as_external()).flatten()
+      })
+    });
+  if let Some(external) = external {
+    Err(anyhow::anyhow!(
+      r#"Invalid value "iife" for option "format" - external module "{}" can't be imported by a script."#,
+      external.name
+    ))?;
+  }
+
+  if !ctx.chunk.imports_from_runtime.is_empty() {
+    Err(anyhow::anyhow!(
+      r#"Invalid value "iife" for option "format" - the external runtime can't be imported by a script, use the inline runtime instead."#
+    ))?;
+  }
+
+  if let Some(entry_module) = ctx.chunk.entry_module(module_table) {
+    if !get_export_items(ctx.chunk, ctx.link_stage_output).is_empty() {
+      ctx.warnings.push(anyhow::anyhow!(
+        r#"Entry module "{}" has exports, which are not exposed by the "iife" output."#,
+        entry_module.stable_id
+      ));
+    }
+  }
+
+  let mut source_joiner = SourceJoiner::default();
+  source_joiner.append_source(if ctx.options.generated_code.arrow_functions {
+    "(() => {\n\"use strict\";"
+  } else {
+    "(function() {\n\"use strict\";"
+  });
+
+  module_sources.iter().for_each(|RenderedModuleSource { sources, .. }| {
+    if let Some(emitted_sources) = sources {
+      for source in emitted_sources.as_ref() {
+        source_joiner.append_source(source);
+      }
+    }
+  });
+
+  if let Some(init) = render_wrapped_entry_init(ctx) {
+    source_joiner.append_source(init);
+  }
+
+  source_joiner.append_source("})();");
+
+  Ok(source_joiner)
+}
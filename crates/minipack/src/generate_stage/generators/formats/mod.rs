@@ -1,2 +1,3 @@
 pub mod cjs;
 pub mod esm;
+pub mod iife;
@@ -1,7 +1,16 @@
+mod build_workers;
+mod check_size_limits;
 mod code_splitting;
+mod collect_module_graph;
+mod collect_module_infos;
+mod collect_unused_code;
 mod compute_cross_chunk_links;
 mod generate_chunk_name_and_preliminary_filenames;
+mod generate_manifest;
 mod render_chunk_to_assets;
+mod render_dts;
+mod render_html_entries;
+mod render_standalone_runtime;
 mod scope_hoisting;
 
 pub mod generators;
@@ -9,8 +18,10 @@ pub mod generators;
 use minipack_common::Module;
 use minipack_ecmascript::AstSnippet;
 use minipack_error::BuildResult;
+use minipack_fs::OsFileSystem;
 use minipack_utils::rayon::{IntoParallelRefMutIterator, ParallelIterator};
 use oxc::ast_visit::VisitMut;
+use oxc_index::IndexVec;
 use rustc_hash::FxHashSet;
 
 use crate::{
@@ -21,13 +32,14 @@ use crate::{
 use super::link_stage::LinkStageOutput;
 
 pub struct GenerateStage {
+  fs: OsFileSystem,
   options: SharedOptions,
   link_stage_output: LinkStageOutput,
 }
 
 impl GenerateStage {
-  pub fn new(link_stage_output: LinkStageOutput, options: SharedOptions) -> Self {
-    Self { link_stage_output, options }
+  pub fn new(fs: OsFileSystem, link_stage_output: LinkStageOutput, options: SharedOptions) -> Self {
+    Self { fs, link_stage_output, options }
   }
 
   pub async fn generate(&mut self) -> BuildResult<BundleOutput> {
@@ -35,8 +47,17 @@ impl GenerateStage {
       return Err(std::mem::take(&mut self.link_stage_output.errors))?;
     }
 
+    let workers = self.build_workers().await?;
+
     let mut chunk_graph = self.generate_chunks().await;
 
+    if self.options.file.is_some() && chunk_graph.chunk_table.len() > 1 {
+      return Err(anyhow::anyhow!(
+        "Invalid value for `options.file` - the build produces {} chunks but `options.file` can only hold one. Use `options.dir` instead, or `options.inline_dynamic_imports` to keep dynamic imports in the entry chunk.",
+        chunk_graph.chunk_table.len()
+      ))?;
+    }
+
     self.compute_cross_chunk_links(&mut chunk_graph);
 
     let chunk_id_to_name =
@@ -51,6 +72,18 @@ impl GenerateStage {
       );
     });
 
+    let static_chunk_imports = if self.options.preloads_dynamic_deps() {
+      chunk_graph.static_chunk_imports()
+    } else {
+      if self.options.preload_dynamic_deps {
+        self.link_stage_output.warnings.push(anyhow::anyhow!(
+          r#"Option "preload_dynamic_deps" is ignored with the "{}" output format - only "esm" chunks are preloaded."#,
+          self.options.format
+        ));
+      }
+      IndexVec::default()
+    };
+
     self.link_stage_output.ecma_ast.par_iter_mut().for_each(|(ast, owner)| {
       let Module::Normal(module) = &self.link_stage_output.module_table[*owner] else {
         return;
@@ -60,7 +93,10 @@ impl GenerateStage {
         return;
       }
 
-      let chunk_id = chunk_graph.module_to_chunk[module.idx].unwrap();
+      // The external runtime belongs to no chunk.
+      let Some(chunk_id) = chunk_graph.module_to_chunk[module.idx] else {
+        return;
+      };
       let linking_info = &self.link_stage_output.metadata[module.idx];
       let canonical_names = &chunk_graph.chunk_table[chunk_id].canonical_names;
       ast.program.with_mut(|fields| {
@@ -75,11 +111,14 @@ impl GenerateStage {
             chunk_id,
             symbol_ref_db: &self.link_stage_output.symbol_ref_db,
             linking_info,
+            metadata: &self.link_stage_output.metadata,
             module,
             modules: &self.link_stage_output.module_table,
             runtime: &self.link_stage_output.runtime_module,
             chunk_graph: &chunk_graph,
             options: &self.options,
+            worker_filenames: &workers.filenames,
+            static_chunk_imports: &static_chunk_imports,
           },
           namespace_alias_symbol_id: FxHashSet::default(),
         };
@@ -87,6 +126,6 @@ impl GenerateStage {
       });
     });
 
-    self.render_chunk_to_assets(&mut chunk_graph).await
+    self.render_chunk_to_assets(&mut chunk_graph, workers).await
   }
 }
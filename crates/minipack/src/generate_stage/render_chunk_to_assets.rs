@@ -1,17 +1,29 @@
 use futures::future::try_join_all;
+use minipack_common::{LegalComments, OutputAsset, OutputAssetKind, RuntimeOptions};
 use minipack_ecmascript::EcmaCompiler;
 use minipack_error::BuildResult;
-use minipack_utils::rayon::{
-  IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator,
-};
-use oxc_index::IndexVec;
+use minipack_utils::concat_string;
+use minipack_utils::rayon::{IntoParallelRefMutIterator, ParallelIterator};
+use oxc::codegen::LegalComment;
+use oxc_index::{IndexVec, index_vec};
+use rustc_hash::FxHashMap;
 
-use super::generators::ecmascript::EcmaGenerator;
+use super::{
+  build_workers::WorkerOutputs, generate_manifest::ManifestFiles,
+  generators::ecmascript::EcmaGenerator,
+};
 
 use crate::{
   graph::ChunkGraph,
-  types::{IndexInstantiatedChunks, bundle_output::BundleOutput, generator::GenerateContext},
-  utils::chunk::finalize_chunks::finalize_assets,
+  types::{
+    IndexInstantiatedChunks,
+    bundle_output::BundleOutput,
+    generator::{GenerateContext, ModuleCodegenOutput},
+  },
+  utils::{
+    chunk::{finalize_chunks::finalize_assets, render_legal_comments::render_legal_comments},
+    minifier_options,
+  },
 };
 
 use super::GenerateStage;
@@ -20,25 +32,90 @@ impl GenerateStage {
   pub async fn render_chunk_to_assets(
     &mut self,
     chunk_graph: &mut ChunkGraph,
+    workers: WorkerOutputs,
   ) -> BuildResult<BundleOutput> {
     let mut warnings = std::mem::take(&mut self.link_stage_output.warnings);
-    let mut assets = finalize_assets(self.instantiate_chunks(chunk_graph, &mut warnings).await?);
+    let chunk_index_to_codegen_rets = self.create_chunk_to_codegen_ret_map(chunk_graph);
+    let mut instantiated_chunks =
+      self.instantiate_chunks(chunk_graph, chunk_index_to_codegen_rets, &mut warnings).await?;
+
+    let legal_txts = render_legal_comments(&mut instantiated_chunks, self.options.legal_comments);
+    let mut chunk_assets = index_vec![None; chunk_graph.chunk_table.len()];
+    for (asset_idx, chunk) in instantiated_chunks.iter_enumerated() {
+      chunk_assets[chunk.chunk_idx] = Some(asset_idx);
+    }
+    let mut assets = finalize_assets(instantiated_chunks);
+
+    let legal_assets = assets
+      .iter()
+      .zip(legal_txts)
+      .filter_map(|(asset, legal_txt)| {
+        Some(OutputAsset {
+          filename: concat_string!(asset.filename, ".LEGAL.txt"),
+          content: legal_txt?,
+          kind: OutputAssetKind::Asset,
+        })
+      })
+      .collect::<Vec<_>>();
+    assets.extend(legal_assets);
+    assets.extend(workers.assets);
+
+    if let RuntimeOptions::External { filename: Some(filename), .. } = &self.options.runtime {
+      assets.push(self.render_standalone_runtime(filename));
+    }
 
-    if self.options.minify {
-      assets.par_iter_mut().for_each(|asset| {
-        asset.content = EcmaCompiler::minify(&asset.content);
-      });
+    if self.options.dts {
+      let dts_assets = self.render_dts(chunk_graph)?;
+      assets.extend(dts_assets);
     }
 
-    Ok(BundleOutput { assets, warnings })
+    let html_outputs = self.render_html_entries(chunk_graph, &chunk_assets, &assets)?;
+
+    if let Some(filename) = &self.options.manifest {
+      let manifest = self.generate_manifest(
+        filename,
+        chunk_graph,
+        &ManifestFiles {
+          chunk_assets: &chunk_assets,
+          assets: &assets,
+          stylesheets: &html_outputs.stylesheets,
+          worker_filenames: &workers.filenames,
+        },
+      );
+      assets.push(manifest);
+    }
+
+    assets.extend(html_outputs.assets);
+
+    let unused_code = self.options.unused_code.as_ref().map(|options| {
+      let report = self.collect_unused_code(options);
+      if let Some(filename) = &options.filename {
+        assets.push(OutputAsset {
+          filename: filename.clone(),
+          content: serde_json::to_string_pretty(&report).expect("report should be serializable"),
+          kind: OutputAssetKind::Asset,
+        });
+      }
+      report
+    });
+
+    self.check_size_limits(&assets, &mut warnings)?;
+
+    Ok(BundleOutput {
+      assets,
+      warnings,
+      modules: self.collect_module_infos(),
+      unused_code,
+      write_report: None,
+    })
   }
 
   async fn instantiate_chunks(
     &self,
     chunk_graph: &ChunkGraph,
+    chunk_index_to_codegen_rets: Vec<Vec<Option<ModuleCodegenOutput>>>,
     warnings: &mut Vec<anyhow::Error>,
   ) -> BuildResult<IndexInstantiatedChunks> {
-    let chunk_index_to_codegen_rets = self.create_chunk_to_codegen_ret_map(chunk_graph);
     let mut index_preliminary_assets = IndexVec::with_capacity(chunk_graph.chunk_table.len());
 
     let tasks =
@@ -73,24 +150,50 @@ impl GenerateStage {
   ///   [Some(ecma1_codegen), Some(ecma2_codegen), None],
   ///   [Some(ecma3_codegen), None],
   /// ]
-  fn create_chunk_to_codegen_ret_map(&self, chunk_graph: &ChunkGraph) -> Vec<Vec<Option<String>>> {
+  /// With `minify`, the minifier runs on the AST of each module before it's printed, so the content
+  /// hashes are computed from the minified code.
+  fn create_chunk_to_codegen_ret_map(
+    &mut self,
+    chunk_graph: &ChunkGraph,
+  ) -> Vec<Vec<Option<ModuleCodegenOutput>>> {
+    let legal_comments = match self.options.legal_comments {
+      LegalComments::None => LegalComment::None,
+      LegalComments::Inline => LegalComment::Inline,
+      LegalComments::Eof | LegalComments::Linked => LegalComment::External,
+    };
+    let minify =
+      self.options.minify.as_ref().map(|options| (minifier_options(options), options.whitespace));
+    let mut module_to_codegen_ret = self
+      .link_stage_output
+      .ecma_ast
+      .par_iter_mut()
+      .filter(|(_, owner)| chunk_graph.module_to_chunk[*owner].is_some())
+      .map(|(ast, owner)| {
+        let ret = match minify {
+          Some((options, remove_whitespace)) => {
+            EcmaCompiler::minify(ast, options, remove_whitespace, legal_comments.clone())
+          }
+          None => EcmaCompiler::print_with_legal_comments(ast, legal_comments.clone()),
+        };
+        let source_text = ast.program().source_text;
+        let codegen_ret = ModuleCodegenOutput {
+          code: ret.code,
+          legal_comments: ret
+            .legal_comments
+            .iter()
+            .map(|comment| comment.span.source_text(source_text).to_string())
+            .collect(),
+        };
+        (*owner, codegen_ret)
+      })
+      .collect::<FxHashMap<_, _>>();
+
     chunk_graph
       .chunk_table
-      .par_iter()
+      .iter()
       .map(|item| {
-        item
-          .modules
-          .par_iter()
-          .map(|&module_idx| {
-            if let Some(module) = self.link_stage_output.module_table[module_idx].as_normal() {
-              let ast = &self.link_stage_output.ecma_ast[module.ecma_ast_idx()].0;
-              Some(EcmaCompiler::print(ast).code)
-            } else {
-              None
-            }
-          })
-          .collect::<Vec<_>>()
+        item.modules.iter().map(|module_idx| module_to_codegen_ret.remove(module_idx)).collect()
       })
-      .collect::<Vec<_>>()
+      .collect()
   }
 }
@@ -0,0 +1,79 @@
+use minipack_common::{ChunkKind, OutputAsset, OutputAssetKind};
+use minipack_error::BuildResult;
+use minipack_utils::{
+  concat_string,
+  rayon::{IntoParallelRefIterator, ParallelIterator},
+};
+
+use crate::{graph::ChunkGraph, utils::bundle_dts::DtsGraph};
+
+use super::GenerateStage;
+
+impl GenerateStage {
+  /// Emit `[name].d.ts` for each user-defined entry chunk whose entry module is written in
+  /// TypeScript.
+  pub fn render_dts(&self, chunk_graph: &ChunkGraph) -> BuildResult<Vec<OutputAsset>> {
+    let module_table = &self.link_stage_output.module_table;
+    let entries = chunk_graph
+      .chunk_table
+      .iter()
+      .filter_map(|chunk| {
+        let ChunkKind::EntryPoint { is_user_defined: true, module, .. } = chunk.kind else {
+          return None;
+        };
+        let module = module_table[module].as_normal()?;
+        module.dts.as_ref()?;
+        Some((chunk.name.as_ref()?, &*module.id))
+      })
+      .collect::<Vec<_>>();
+    if entries.is_empty() {
+      return Ok(vec![]);
+    }
+
+    let graph = DtsGraph::link(module_table, &self.link_stage_output.type_only_modules)?;
+    Ok(
+      entries
+        .par_iter()
+        .map(|(name, id)| OutputAsset {
+          filename: concat_string!(name, ".d.ts"),
+          content: graph.bundle(id),
+          kind: OutputAssetKind::Asset,
+        })
+        .collect(),
+    )
+  }
+}
+
+#[tokio::test]
+async fn test_dts_of_type_only_imports() {
+  use minipack_common::{BundlerOptions, InputItem};
+  use std::path::Path;
+
+  let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/dts");
+  let mut bundler = crate::Bundler::new(BundlerOptions {
+    cwd: Some(cwd),
+    input: Some(vec![InputItem { name: None, import: "./main.ts".to_string() }]),
+    dts: Some(true),
+    ..BundlerOptions::default()
+  })
+  .unwrap();
+  let output = bundler.build(false).await.unwrap();
+  let dts = output.assets.iter().find(|asset| asset.filename == "main.d.ts").unwrap();
+
+  assert!(dts.content.starts_with("import type { Options } from \"external-lib\";\n"));
+  // `t.Unit` of the namespace import is linked to the declaration it resolves to.
+  assert!(dts.content.contains("declare function area(shape: Shape, unit: Unit): number;"));
+  assert!(dts.content.contains("interface Shape$1 {"));
+  assert!(dts.content.contains("declare function helper$1(shape: Shape$1): string;"));
+  assert!(
+    dts
+      .content
+      .contains("export { Options, area, config, main_default$1 as default, helper$1 as helper };")
+  );
+
+  // Modules only imported for their types aren't part of the module graph.
+  let mut modules =
+    output.modules.iter().map(|module| module.stable_id.as_str()).collect::<Vec<_>>();
+  modules.sort_unstable();
+  assert_eq!(modules, ["helper.ts", "main.ts"]);
+}
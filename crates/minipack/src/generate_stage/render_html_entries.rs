@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use minipack_common::{AssetIdx, ChunkIdx, ModuleId, OutputAsset, OutputAssetKind};
+use minipack_error::BuildResult;
+use minipack_utils::{
+  concat_string, html::find_head_end, indexmap::FxIndexSet, xxhash::xxhash_base64_url,
+};
+use oxc_index::IndexVec;
+use rustc_hash::FxHashMap;
+use sugar_path::SugarPath;
+
+use crate::graph::ChunkGraph;
+
+use super::GenerateStage;
+
+#[derive(Default)]
+pub struct HtmlEntryOutputs {
+  pub assets: Vec<OutputAsset>,
+  /// Filenames of the stylesheets loaded by the html entries along with their entry chunks.
+  pub stylesheets: FxHashMap<ChunkIdx, Vec<String>>,
+}
+
+impl GenerateStage {
+  /// Emit html entries with their module scripts pointing to the generated entry chunks, and
+  /// `<link rel="modulepreload">` injected for the static dependencies of these chunks. Local
+  /// stylesheets are emitted as `[name]-[hash].css` next to the chunks.
+  ///
+  /// `chunk_assets` maps the chunks of `chunk_graph` to their finalized asset in `assets`.
+  pub fn render_html_entries(
+    &self,
+    chunk_graph: &ChunkGraph,
+    chunk_assets: &IndexVec<ChunkIdx, Option<AssetIdx>>,
+    assets: &[OutputAsset],
+  ) -> BuildResult<HtmlEntryOutputs> {
+    let dist = self.options.cwd.join(&self.options.dir);
+    let mut outputs = HtmlEntryOutputs::default();
+    let mut stylesheet_filenames = FxHashMap::default();
+
+    for html_entry in &self.link_stage_output.html_entries {
+      let mut filename = ModuleId::new(html_entry.id.clone()).stabilize(&self.options.cwd);
+      if filename.starts_with("..") {
+        // Html files outside of `cwd` are emitted to the root of the output directory.
+        filename = Path::new(html_entry.id.as_str()).file_name().unwrap().to_string_lossy().into();
+      }
+      let html_dir = dist.join(&filename).parent().unwrap().to_path_buf();
+      let url_for = |filename: &str| {
+        let url = dist.join(filename).relative(&html_dir).to_slash_lossy().into_owned();
+        if url.starts_with('.') { url } else { concat_string!("./", url) }
+      };
+      let url_for_chunk = |chunk_idx: ChunkIdx| {
+        let asset_idx = chunk_assets[chunk_idx].expect("chunk should be rendered to an asset");
+        url_for(&assets[asset_idx.index()].filename)
+      };
+
+      let entry_chunks = html_entry
+        .scripts
+        .iter()
+        .map(|(_, module_idx)| {
+          chunk_graph.entry_module_to_chunk.get(module_idx).copied().ok_or_else(|| {
+            anyhow::anyhow!(
+              "Could not find the chunk of {} in {}",
+              self.link_stage_output.module_table[*module_idx].stable_id(),
+              html_entry.id
+            )
+          })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+      let mut preload_chunks = FxIndexSet::default();
+      for chunk_idx in &entry_chunks {
+        collect_static_imports(chunk_graph, *chunk_idx, &mut preload_chunks);
+      }
+      preload_chunks.retain(|chunk_idx| !entry_chunks.contains(chunk_idx));
+
+      let preload_links = preload_chunks
+        .iter()
+        .map(|chunk_idx| {
+          concat_string!("<link rel=\"modulepreload\" href=\"", url_for_chunk(*chunk_idx), "\">\n")
+        })
+        .collect::<String>();
+
+      let mut edits = html_entry
+        .scripts
+        .iter()
+        .zip(&entry_chunks)
+        .map(|((range, _), chunk_idx)| {
+          let tag = concat_string!(
+            "<script type=\"module\" src=\"",
+            url_for_chunk(*chunk_idx),
+            "\"></script>"
+          );
+          (range.clone(), tag)
+        })
+        .collect::<Vec<_>>();
+
+      for stylesheet in &html_entry.stylesheets {
+        let filename = stylesheet_filenames.entry(stylesheet.id.as_str()).or_insert_with(|| {
+          let stem = Path::new(&stylesheet.id).file_stem().unwrap_or_default().to_string_lossy();
+          let hash = xxhash_base64_url(stylesheet.content.as_bytes());
+          let filename = concat_string!(stem, "-", &hash[..8], ".css");
+          outputs.assets.push(OutputAsset {
+            filename: filename.clone(),
+            content: stylesheet.content.clone(),
+            kind: OutputAssetKind::Asset,
+          });
+          filename
+        });
+        edits.push((stylesheet.href_range.clone(), url_for(filename)));
+        for chunk_idx in &entry_chunks {
+          let chunk_stylesheets = outputs.stylesheets.entry(*chunk_idx).or_default();
+          if !chunk_stylesheets.contains(filename) {
+            chunk_stylesheets.push(filename.clone());
+          }
+        }
+      }
+
+      if !preload_links.is_empty() {
+        // Fallback to inject the links before the first script if there is no `</head>`.
+        let offset = find_head_end(&html_entry.source)
+          .or_else(|| html_entry.scripts.first().map(|(range, _)| range.start))
+          .unwrap_or_default();
+        edits.push((offset..offset, preload_links));
+      }
+      edits.sort_by_key(|(range, _)| (range.start, range.end));
+
+      let mut content = String::with_capacity(html_entry.source.len());
+      let mut last_end = 0;
+      for (range, replacement) in edits {
+        content.push_str(&html_entry.source[last_end..range.start]);
+        content.push_str(&replacement);
+        last_end = range.end;
+      }
+      content.push_str(&html_entry.source[last_end..]);
+
+      outputs.assets.push(OutputAsset { filename, content, kind: OutputAssetKind::Asset });
+    }
+
+    Ok(outputs)
+  }
+}
+
+fn collect_static_imports(
+  chunk_graph: &ChunkGraph,
+  chunk_idx: ChunkIdx,
+  visited: &mut FxIndexSet<ChunkIdx>,
+) {
+  for importee in &chunk_graph.chunk_table[chunk_idx].cross_chunk_imports {
+    if visited.insert(*importee) {
+      collect_static_imports(chunk_graph, *importee, visited);
+    }
+  }
+}
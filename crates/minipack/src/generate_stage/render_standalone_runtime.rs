@@ -0,0 +1,62 @@
+use itertools::Itertools;
+use minipack_common::{OutputAsset, OutputAssetKind, OutputFormat};
+use minipack_utils::concat_string;
+
+use crate::scan_stage::runtime_module_task::RUNTIME_SOURCE;
+
+use super::GenerateStage;
+
+impl GenerateStage {
+  /// The external runtime as a module exporting every helper, which chunks of the same format can
+  /// import when it's published under the configured specifier.
+  pub fn render_standalone_runtime(&self, filename: &str) -> OutputAsset {
+    let names = self.link_stage_output.runtime_module.names().sorted_unstable().join(", ");
+    let content = match self.options.format {
+      OutputFormat::Cjs => {
+        concat_string!("\"use strict\";\n", RUNTIME_SOURCE, "\nmodule.exports = { ", names, " };\n")
+      }
+      OutputFormat::Esm | OutputFormat::Iife => {
+        concat_string!(RUNTIME_SOURCE, "\nexport { ", names, " };\n")
+      }
+    };
+    OutputAsset { filename: filename.to_string(), content, kind: OutputAssetKind::Asset }
+  }
+}
+
+#[tokio::test]
+async fn test_external_runtime() {
+  use std::path::Path;
+
+  use minipack_common::{BundlerOptions, InputItem, RuntimeOptions};
+
+  let build = |runtime: RuntimeOptions| async move {
+    let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/runtime");
+    let mut bundler = crate::Bundler::new(BundlerOptions {
+      cwd: Some(cwd),
+      input: Some(vec![InputItem { name: None, import: "./main.js".to_string() }]),
+      runtime: Some(runtime),
+      ..BundlerOptions::default()
+    })
+    .unwrap();
+    bundler.build(false).await.unwrap().assets
+  };
+
+  let assets = build(RuntimeOptions::Inline).await;
+  assert_eq!(assets.len(), 1);
+  assert!(assets[0].content.contains("var __export = "));
+  assert!(!assets[0].content.contains("@minipack/runtime"));
+
+  // Chunks only import the helpers they use, the standalone runtime exports all of them.
+  let assets = build(RuntimeOptions::External {
+    specifier: "@minipack/runtime".to_string(),
+    filename: Some("runtime.js".to_string()),
+  })
+  .await;
+  let asset =
+    |filename: &str| &assets.iter().find(|asset| asset.filename == filename).unwrap().content;
+  assert!(asset("main.js").starts_with("import { __export } from \"@minipack/runtime\";\n"));
+  assert!(!asset("main.js").contains("var __export = "));
+  let runtime = asset("runtime.js");
+  assert!(runtime.contains("var __export = "));
+  assert!(runtime.contains("\nexport { ") && runtime.contains(" __export, "));
+}
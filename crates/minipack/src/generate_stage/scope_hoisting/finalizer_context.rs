@@ -2,11 +2,12 @@ use minipack_common::{
   ChunkIdx, ModuleIdx, NormalModule, RuntimeModuleBrief, SymbolRef, SymbolRefDb,
 };
 use minipack_utils::rstr::Rstr;
-use rustc_hash::FxHashMap;
+use oxc_index::IndexVec;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
   graph::ChunkGraph,
-  types::{IndexModules, SharedOptions, linking_metadata::LinkingMetadata},
+  types::{IndexModules, LinkingMetadataVec, SharedOptions, linking_metadata::LinkingMetadata},
 };
 
 pub struct ScopeHoistingFinalizerContext<'me> {
@@ -15,9 +16,14 @@ pub struct ScopeHoistingFinalizerContext<'me> {
   pub module: &'me NormalModule,
   pub modules: &'me IndexModules,
   pub linking_info: &'me LinkingMetadata,
+  pub metadata: &'me LinkingMetadataVec,
   pub symbol_ref_db: &'me SymbolRefDb,
   pub canonical_names: &'me FxHashMap<SymbolRef, Rstr>,
   pub runtime: &'me RuntimeModuleBrief,
   pub chunk_graph: &'me ChunkGraph,
   pub options: &'me SharedOptions,
+  pub worker_filenames: &'me FxHashMap<ModuleIdx, String>,
+  /// Chunks loaded by the static imports of each chunk, directly or transitively. Only computed when
+  /// the dependencies of dynamic imports are preloaded.
+  pub static_chunk_imports: &'me IndexVec<ChunkIdx, FxHashSet<ChunkIdx>>,
 }
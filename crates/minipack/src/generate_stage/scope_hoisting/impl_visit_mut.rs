@@ -28,12 +28,19 @@ impl<'ast> VisitMut<'ast> for super::ScopeHoistingFinalizer<'_, 'ast> {
 
     self.remove_unused_top_level_stmt(program);
 
+    let mut namespace_stmts_len = 0;
     if self.ctx.module.stmt_infos[StmtInfoIdx::new(0)].is_included {
       let stmts = self.generate_declaration_of_module_namespace_object();
+      namespace_stmts_len = stmts.len();
       program.body.splice(0..0, stmts);
     }
 
     walk_mut::walk_program(self, program);
+
+    // The namespace object only has getters, so it's safe to be kept out of the wrapper.
+    if let Some(wrapper_ref) = self.ctx.linking_info.wrapper_ref {
+      self.wrap_module_body(program, namespace_stmts_len, wrapper_ref);
+    }
   }
 
   fn visit_binding_identifier(&mut self, ident: &mut ast::BindingIdentifier<'ast>) {
@@ -60,7 +67,10 @@ impl<'ast> VisitMut<'ast> for super::ScopeHoistingFinalizer<'_, 'ast> {
   fn visit_expression(&mut self, expr: &mut ast::Expression<'ast>) {
     match expr {
       ast::Expression::ImportExpression(import_expr) => {
-        if let Some(new_expr) = self.try_rewrite_inline_dynamic_import_expr(import_expr) {
+        if let Some(new_expr) = self
+          .try_rewrite_import_glob_expr(import_expr)
+          .or_else(|| self.try_rewrite_inline_dynamic_import_expr(import_expr))
+        {
           *expr = new_expr;
         }
       }
@@ -117,9 +127,11 @@ impl<'ast> VisitMut<'ast> for super::ScopeHoistingFinalizer<'_, 'ast> {
   fn visit_import_expression(&mut self, expr: &mut ast::ImportExpression<'ast>) {
     if expr.options.is_none() {
       // Make sure the import expression is in correct form. If it's not, we should leave it as it is.
-      if let ast::Expression::StringLiteral(str) = &mut expr.source {
-        let rec_id = self.ctx.module.imports[&expr.span];
-        let rec = &self.ctx.module.import_records[rec_id];
+      // Import expressions generated by the finalizer have no import record and are final.
+      if let (ast::Expression::StringLiteral(str), Some(rec_id)) =
+        (&mut expr.source, self.ctx.module.imports.get(&expr.span))
+      {
+        let rec = &self.ctx.module.import_records[*rec_id];
         let importee_id = rec.state;
         match &self.ctx.modules[importee_id] {
           Module::Normal(_importee) => {
@@ -143,6 +155,31 @@ impl<'ast> VisitMut<'ast> for super::ScopeHoistingFinalizer<'_, 'ast> {
     walk_mut::walk_import_expression(self, expr);
   }
 
+  fn visit_new_expression(&mut self, expr: &mut ast::NewExpression<'ast>) {
+    // Point `new URL('./worker.js', import.meta.url)` to the entry chunk of the worker build.
+    if let Some(rec_id) = self.ctx.module.imports.get(&expr.span) {
+      let rec = &self.ctx.module.import_records[*rec_id];
+      if let (true, Some(ast::Argument::StringLiteral(str))) =
+        (rec.kind.is_worker(), expr.arguments.first_mut())
+      {
+        let importer_chunk = &self.ctx.chunk_graph.chunk_table[self.ctx.chunk_id];
+        let worker_filename = self
+          .ctx
+          .options
+          .cwd
+          .join(&self.ctx.options.dir)
+          .join(&self.ctx.worker_filenames[&rec.state]);
+        let import_path = importer_chunk.relative_path_for(&worker_filename);
+        str.value = self.snippet.atom(&if import_path.starts_with('.') {
+          import_path
+        } else {
+          format!("./{import_path}")
+        });
+      }
+    }
+    walk_mut::walk_new_expression(self, expr);
+  }
+
   fn visit_assignment_target_property(
     &mut self,
     property: &mut ast::AssignmentTargetProperty<'ast>,
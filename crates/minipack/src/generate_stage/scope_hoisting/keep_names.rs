@@ -0,0 +1,89 @@
+use oxc::{
+  allocator::TakeIn,
+  ast::ast::{self, BindingPatternKind},
+  span::SPAN,
+};
+
+impl<'ast> super::ScopeHoistingFinalizer<'_, 'ast> {
+  /// Restores the source names of top-level functions and classes renamed by the bundler. Returns
+  /// `__name(foo$1, "foo");` to follow a declaration, while `const foo$1 = __name(() => {}, "foo")`
+  /// is rewritten in place.
+  pub fn keep_names_of_top_level_stmt(
+    &self,
+    stmt: &mut ast::Statement<'ast>,
+  ) -> Option<ast::Statement<'ast>> {
+    let id = match stmt {
+      ast::Statement::FunctionDeclaration(func) => func.id.as_ref()?,
+      ast::Statement::ClassDeclaration(class) => class.id.as_ref()?,
+      ast::Statement::VariableDeclaration(decl) => {
+        for declarator in &mut decl.declarations {
+          let BindingPatternKind::BindingIdentifier(id) = &declarator.id.kind else {
+            continue;
+          };
+          let Some(init) =
+            declarator.init.as_mut().filter(|init| init.is_anonymous_function_definition())
+          else {
+            continue;
+          };
+          if let Some((_, original_name)) = self.renamed_binding(id) {
+            *init = self.keep_name_expr(init.take_in(self.allocator), original_name);
+          }
+        }
+        return None;
+      }
+      _ => return None,
+    };
+    let (name, original_name) = self.renamed_binding(id)?;
+    let expr = self.keep_name_expr(self.snippet.id_ref_expr(name, SPAN), original_name);
+    Some(self.snippet.builder.statement_expression(SPAN, expr))
+  }
+
+  /// The final and the source name of a top-level binding if they differ. Bindings created for
+  /// `export default function() {}` have no symbol, and the name of such functions is `default`.
+  fn renamed_binding<'a>(
+    &'a self,
+    id: &'a ast::BindingIdentifier<'ast>,
+  ) -> Option<(&'a str, &'a str)> {
+    let (name, original_name) = match id.symbol_id.get() {
+      Some(symbol_id) => {
+        (self.canonical_name_for((self.ctx.id, symbol_id).into()), id.name.as_str())
+      }
+      None => (id.name.as_str(), "default"),
+    };
+    (name != original_name).then_some((name, original_name))
+  }
+
+  fn keep_name_expr(&self, target: ast::Expression<'ast>, name: &str) -> ast::Expression<'ast> {
+    self.snippet.call_expr_with_2arg_expr(
+      self.finalized_expr_for_runtime_symbol("__name"),
+      target,
+      self.snippet.string_literal_expr(name, SPAN),
+    )
+  }
+}
+
+#[tokio::test]
+async fn test_keep_names_of_renamed_declarations() {
+  use minipack_common::{BundlerOptions, InputItem};
+  use std::path::Path;
+
+  let build = |input: &str| {
+    let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/keep_names");
+    let mut bundler = crate::Bundler::new(BundlerOptions {
+      cwd: Some(cwd),
+      input: Some(vec![InputItem { name: None, import: input.to_string() }]),
+      keep_names: Some(true),
+      ..BundlerOptions::default()
+    })
+    .unwrap();
+    async move { bundler.build(false).await.unwrap().assets.remove(0).content }
+  };
+
+  let single = build("./single.js").await;
+  assert!(!single.contains("__name"));
+  assert!(!single.contains("__defProp"));
+
+  let multi = build("./multi.js").await;
+  assert!(multi.contains("__name(foo$1, \"foo\");"));
+  assert!(multi.contains("__name(foo$2, \"foo\");"));
+}
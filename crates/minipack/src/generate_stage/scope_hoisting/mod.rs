@@ -1,8 +1,11 @@
 mod finalizer_context;
 mod impl_visit_mut;
+mod keep_names;
 mod rename;
+mod wrapper;
 
-use minipack_common::{AstScopes, Module, OutputFormat, Platform, SymbolRef};
+use itertools::Itertools;
+use minipack_common::{AstScopes, ImportRecordIdx, Module, OutputFormat, Platform, SymbolRef};
 use minipack_ecmascript::{AstSnippet, ExpressionExt, StatementExt};
 use minipack_utils::ecmascript::is_validate_identifier_name;
 use oxc::{
@@ -12,9 +15,9 @@ use oxc::{
     ast::{self, ExportDefaultDeclarationKind, Expression, ImportExpression, MemberExpression},
   },
   semantic::SymbolId,
-  span::{GetSpan, SPAN},
+  span::{GetSpan, SPAN, Span},
 };
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 pub use finalizer_context::ScopeHoistingFinalizerContext;
 
@@ -53,7 +56,9 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
       canonical_symbol = self.ctx.symbol_ref_db.get(canonical_ref);
     }
 
-    let mut expr = if self.ctx.modules[canonical_ref.owner].is_external() {
+    let mut expr = if self.ctx.modules[canonical_ref.owner].is_external()
+      || canonical_ref.owner == self.ctx.runtime.idx && self.ctx.options.runtime.is_external()
+    {
       self.snippet.id_ref_expr(self.canonical_name_for(canonical_ref), SPAN)
     } else {
       match self.ctx.options.format {
@@ -101,6 +106,15 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
     expr
   }
 
+  /// `() => expr`, or `function() { return expr; }` without `generated_code.arrow_functions`.
+  fn glue_fn_expr(&self, expr: ast::Expression<'ast>) -> ast::Expression<'ast> {
+    if self.ctx.options.generated_code.arrow_functions {
+      self.snippet.only_return_arrow_expr(expr)
+    } else {
+      self.snippet.only_return_fn_expr(expr)
+    }
+  }
+
   fn generate_declaration_of_module_namespace_object(&self) -> Vec<ast::Statement<'ast>> {
     let binding_name_for_namespace_object_ref =
       self.canonical_name_for(self.ctx.module.namespace_object_ref);
@@ -122,7 +136,7 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
       // construct `__reExport(importer_exports, importee_exports)`
       let re_export_fn_ref = self.finalized_expr_for_runtime_symbol("__reExport");
       match self.ctx.options.format {
-        OutputFormat::Esm => {
+        OutputFormat::Esm | OutputFormat::Iife => {
           let stmts = export_all_externals_rec_ids.iter().copied().flat_map(|idx| {
             let rec = &self.ctx.module.import_records[idx];
             let importee_namespace_name = self.canonical_name_for(rec.namespace_ref);
@@ -175,6 +189,8 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
     if exports_len == 0 {
       let mut ret = vec![decl_stmt];
       ret.extend(re_export_external_stmts.unwrap_or_default());
+      ret.extend(self.generate_namespace_object_traits(binding_name_for_namespace_object_ref));
+      ret.extend(self.generate_hmr_register_stmt(binding_name_for_namespace_object_ref));
       return ret;
     }
 
@@ -195,7 +211,7 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
           } else {
             ast::PropertyKey::StringLiteral(self.snippet.alloc_string_literal(prop_name, SPAN))
           },
-          value: self.snippet.only_return_arrow_expr(returned),
+          value: self.glue_fn_expr(returned),
           ..ast::ObjectProperty::dummy(self.allocator)
         }
         .into_in(self.allocator),
@@ -216,10 +232,71 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
     let export_call_stmt = self.snippet.builder.statement_expression(SPAN, export_call_expr);
     let mut ret = vec![decl_stmt, export_call_stmt];
     ret.extend(re_export_external_stmts.unwrap_or_default());
+    ret.extend(self.generate_namespace_object_traits(binding_name_for_namespace_object_ref));
+    ret.extend(self.generate_hmr_register_stmt(binding_name_for_namespace_object_ref));
 
     ret
   }
 
+  /// `__hmrRegister('src/foo.js', foo_exports)` lets hmr updates reuse the module.
+  fn generate_hmr_register_stmt(&self, name: &str) -> Option<ast::Statement<'ast>> {
+    if !self.ctx.linking_info.registers_hmr_exports {
+      return None;
+    }
+    let register_call = self.snippet.call_expr_with_2arg_expr(
+      self.finalized_expr_for_runtime_symbol("__hmrRegister"),
+      self.snippet.string_literal_expr(&self.ctx.module.stable_id, SPAN),
+      self.snippet.id_ref_expr(name, SPAN),
+    );
+    Some(self.snippet.builder.statement_expression(SPAN, register_call))
+  }
+
+  /// `Object.defineProperty(foo_exports, Symbol.toStringTag, { value: 'Module' })` and
+  /// `Object.freeze(foo_exports)` make namespace objects behave like the ones of native ESM.
+  fn generate_namespace_object_traits(&self, name: &str) -> Vec<ast::Statement<'ast>> {
+    let builder = &self.snippet.builder;
+    let mut stmts = vec![];
+    if self.ctx.options.generated_code.symbols {
+      let descriptor = builder.expression_object(
+        SPAN,
+        builder.vec1(builder.object_property_kind_object_property(
+          SPAN,
+          ast::PropertyKind::Init,
+          builder.property_key_static_identifier(SPAN, "value"),
+          self.snippet.string_literal_expr("Module", SPAN),
+          false,
+          false,
+          false,
+        )),
+      );
+      let define_call = builder.expression_call(
+        SPAN,
+        self.snippet.literal_prop_access_member_expr_expr("Object", "defineProperty"),
+        NONE,
+        builder.vec_from_array([
+          ast::Argument::from(self.snippet.id_ref_expr(name, SPAN)),
+          ast::Argument::from(
+            self.snippet.literal_prop_access_member_expr_expr("Symbol", "toStringTag"),
+          ),
+          ast::Argument::from(descriptor),
+        ]),
+        false,
+      );
+      stmts.push(builder.statement_expression(SPAN, define_call));
+    }
+    if self.ctx.options.generated_code.freeze {
+      let freeze_call = builder.expression_call(
+        SPAN,
+        self.snippet.literal_prop_access_member_expr_expr("Object", "freeze"),
+        NONE,
+        builder.vec1(ast::Argument::from(self.snippet.id_ref_expr(name, SPAN))),
+        false,
+      );
+      stmts.push(builder.statement_expression(SPAN, freeze_call));
+    }
+    stmts
+  }
+
   // Handle `import.meta.xxx` expression
   pub fn try_rewrite_import_meta_prop_expr(
     &self,
@@ -278,11 +355,45 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
                 false,
               );
             Some(ast::Expression::StaticMemberExpression(require_path_to_file_url_href))
+          } else if matches!(self.ctx.options.format, OutputFormat::Iife) {
+            // Replace it with `self.location.href`, which is the url of the script in workers
+            let self_location = self.snippet.builder.alloc_static_member_expression(
+              SPAN,
+              self.snippet.builder.expression_identifier(SPAN, "self"),
+              self.snippet.builder.identifier_name(SPAN, "location"),
+              false,
+            );
+            Some(ast::Expression::StaticMemberExpression(
+              self.snippet.builder.alloc_static_member_expression(
+                original_expr_span,
+                ast::Expression::StaticMemberExpression(self_location),
+                self.snippet.builder.identifier_name(SPAN, "href"),
+                false,
+              ),
+            ))
           } else {
             None
           };
           return new_expr;
         }
+        // `import.meta.hot` is only available with hmr, otherwise it's `undefined` so that code
+        // guarded by `if (import.meta.hot)` could be eliminated.
+        "hot" => {
+          if !self.ctx.options.hmr {
+            return Some(self.snippet.builder.void_0(original_expr_span));
+          }
+          // Replace it with `__hot("src/foo.js")`
+          let stable_id = self.snippet.atom(&self.ctx.module.stable_id);
+          return Some(self.snippet.builder.expression_call(
+            original_expr_span,
+            self.finalized_expr_for_runtime_symbol("__hot"),
+            NONE,
+            self.snippet.builder.vec1(ast::Argument::StringLiteral(
+              self.snippet.builder.alloc_string_literal(SPAN, stable_id, None),
+            )),
+            false,
+          ));
+        }
         "dirname" | "filename" => {
           let name = self.snippet.atom(&format!("__{property_name}"));
           return is_node_cjs.then_some(ast::Expression::Identifier(
@@ -336,32 +447,180 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
     &mut self,
     import_expr: &mut ImportExpression<'ast>,
   ) -> Option<Expression<'ast>> {
+    let rec_id = *self.ctx.module.imports.get(&import_expr.span)?;
+    self.try_rewrite_dynamic_import_of_record(rec_id, import_expr.span)
+  }
+
+  /// Convert `import(`./locales/${lang}.js`)` to
+  /// `__glob({ "./locales/en.js": () => import('./en.js'), ... }, `./locales/${lang}.js`)`.
+  fn try_rewrite_import_glob_expr(
+    &mut self,
+    import_expr: &mut ImportExpression<'ast>,
+  ) -> Option<Expression<'ast>> {
+    let rec_ids = self.ctx.module.import_globs.get(&import_expr.span)?;
+    let properties = self.snippet.builder.vec_from_iter(rec_ids.iter().map(|&rec_id| {
+      let rec = &self.ctx.module.import_records[rec_id];
+      let key =
+        ast::PropertyKey::StringLiteral(self.snippet.alloc_string_literal(&rec.specifier, SPAN));
+      let value = self.try_rewrite_dynamic_import_of_record(rec_id, SPAN).unwrap_or_else(|| {
+        let import_path = match &self.ctx.modules[rec.state] {
+          Module::Normal(_) => {
+            let importer_chunk = &self.ctx.chunk_graph.chunk_table[self.ctx.chunk_id];
+            let importee_chunk_id = self.ctx.chunk_graph.entry_module_to_chunk[&rec.state];
+            importer_chunk.import_path_for(&self.ctx.chunk_graph.chunk_table[importee_chunk_id])
+          }
+          Module::External(importee) => importee.name.to_string(),
+        };
+        self.snippet.builder.expression_import(
+          SPAN,
+          self.snippet.builder.expression_string_literal(
+            SPAN,
+            self.snippet.atom(&import_path),
+            None,
+          ),
+          None,
+          None,
+        )
+      });
+      self.snippet.builder.object_property_kind_object_property(
+        SPAN,
+        ast::PropertyKind::Init,
+        key,
+        self.glue_fn_expr(value),
+        false,
+        false,
+        false,
+      )
+    }));
+    let args = self.snippet.builder.vec_from_array([
+      ast::Argument::from(self.snippet.builder.expression_object(SPAN, properties)),
+      ast::Argument::from(import_expr.source.take_in(self.allocator)),
+    ]);
+    Some(self.snippet.builder.expression_call(
+      import_expr.span,
+      self.finalized_expr_for_runtime_symbol("__glob"),
+      NONE,
+      args,
+      false,
+    ))
+  }
+
+  fn try_rewrite_dynamic_import_of_record(
+    &mut self,
+    rec_id: ImportRecordIdx,
+    span: Span,
+  ) -> Option<Expression<'ast>> {
+    if self.ctx.options.inline_dynamic_imports {
+      // Convert `import('./foo.mjs')` to `Promise.resolve().then(() => foo_exports)`
+      let rec = &self.ctx.module.import_records[rec_id];
+      let importee = self.ctx.modules[rec.state].as_normal()?;
+      let namespace_expr = self.finalized_expr_for_symbol_ref(importee.namespace_object_ref, false);
+      let Some(wrapper_ref) = self.ctx.metadata[importee.idx].wrapper_ref else {
+        return Some(
+          self.snippet.promise_resolve_then_expr(span, self.glue_fn_expr(namespace_expr)),
+        );
+      };
+      // Convert `import('./foo.mjs')` to `Promise.resolve().then(() => init_foo()).then(() => foo_exports)`
+      // if the importee is wrapped, which also waits for its top-level await.
+      let init_expr = self.snippet.builder.expression_call(
+        SPAN,
+        self.finalized_expr_for_symbol_ref(wrapper_ref, false),
+        NONE,
+        self.snippet.builder.vec(),
+        false,
+      );
+      let init_promise = self.snippet.promise_resolve_then_expr(SPAN, self.glue_fn_expr(init_expr));
+      return Some(self.snippet.builder.expression_call(
+        span,
+        ast::Expression::StaticMemberExpression(
+          self.snippet.builder.alloc_static_member_expression(
+            SPAN,
+            init_promise,
+            self.snippet.id_name("then", SPAN),
+            false,
+          ),
+        ),
+        NONE,
+        self.snippet.builder.vec1(ast::Argument::from(self.glue_fn_expr(namespace_expr))),
+        false,
+      ));
+    }
+
     if matches!(self.ctx.options.format, OutputFormat::Cjs) {
-      // Convert `import('./foo.mjs')` to `Promise.resolve().then(function() { return require('./foo.mjs') })`
-      let rec_id = self.ctx.module.imports.get(&import_expr.span)?;
-      let rec = &self.ctx.module.import_records[*rec_id];
+      // Convert `import('./foo.mjs')` to `Promise.resolve().then(() => require('./foo.mjs'))`
+      let rec = &self.ctx.module.import_records[rec_id];
       let importee_id = rec.state;
       if self.ctx.modules[importee_id].is_normal() {
         let importer_chunk = &self.ctx.chunk_graph.chunk_table[self.ctx.chunk_id];
         let importee_chunk_id = self.ctx.chunk_graph.entry_module_to_chunk[&importee_id];
         let importee_chunk = &self.ctx.chunk_graph.chunk_table[importee_chunk_id];
         let import_path = importer_chunk.import_path_for(importee_chunk);
-        return Some(self.snippet.promise_resolve_then_call_expr(
-          import_expr.span,
-          self.snippet.builder.vec1(ast::Statement::ReturnStatement(
-            self.snippet.builder.alloc_return_statement(
+        let require_expr = self.snippet.call_expr_with_arg_expr_expr(
+          "require",
+          self.snippet.string_literal_expr(&import_path, span),
+        );
+        return Some(self.snippet.promise_resolve_then_expr(span, self.glue_fn_expr(require_expr)));
+      }
+    }
+
+    if self.ctx.options.preloads_dynamic_deps() {
+      // Convert `import('./page.js')` to `__preload(() => import('./page.js'), ['./dep.js'], import.meta.url)`
+      // so that the static imports of the page chunk are fetched along with it.
+      let importee_id = self.ctx.module.import_records[rec_id].state;
+      if self.ctx.modules[importee_id].is_normal()
+        && self.ctx.metadata[importee_id].may_have_preload_deps(self.ctx.runtime.idx)
+      {
+        let importer_chunk = &self.ctx.chunk_graph.chunk_table[self.ctx.chunk_id];
+        let importee_chunk_id = self.ctx.chunk_graph.entry_module_to_chunk[&importee_id];
+        let importee_chunk = &self.ctx.chunk_graph.chunk_table[importee_chunk_id];
+        let import_path = importer_chunk.import_path_for(importee_chunk);
+        // Chunks that are already loaded by the importer don't need to be preloaded.
+        let loaded_chunks = &self.ctx.static_chunk_imports[self.ctx.chunk_id];
+        let dep_paths = self.ctx.static_chunk_imports[importee_chunk_id]
+          .iter()
+          .filter(|dep_chunk_id| {
+            **dep_chunk_id != self.ctx.chunk_id && !loaded_chunks.contains(*dep_chunk_id)
+          })
+          .sorted_by_key(|dep_chunk_id| self.ctx.chunk_graph.chunk_table[**dep_chunk_id].exec_order)
+          .map(|dep_chunk_id| {
+            importer_chunk.import_path_for(&self.ctx.chunk_graph.chunk_table[*dep_chunk_id])
+          })
+          .collect::<Vec<_>>();
+        if dep_paths.is_empty() {
+          return None;
+        }
+        let deps = self.snippet.builder.vec_from_iter(dep_paths.iter().map(|dep_path| {
+          ast::ArrayExpressionElement::from(self.snippet.string_literal_expr(dep_path, SPAN))
+        }));
+        let load_expr = self.snippet.builder.expression_import(
+          SPAN,
+          self.snippet.string_literal_expr(&import_path, SPAN),
+          None,
+          None,
+        );
+        let import_meta_url = ast::Expression::StaticMemberExpression(
+          self.snippet.builder.alloc_static_member_expression(
+            SPAN,
+            self.snippet.builder.expression_meta_property(
               SPAN,
-              Some(ast::Expression::CallExpression(self.snippet.builder.alloc_call_expression(
-                SPAN,
-                self.snippet.builder.expression_identifier(SPAN, "require"),
-                NONE,
-                self.snippet.builder.vec1(ast::Argument::StringLiteral(
-                  self.snippet.alloc_string_literal(&import_path, import_expr.span),
-                )),
-                false,
-              ))),
+              self.snippet.id_name("import", SPAN),
+              self.snippet.id_name("meta", SPAN),
             ),
-          )),
+            self.snippet.id_name("url", SPAN),
+            false,
+          ),
+        );
+        let args = self.snippet.builder.vec_from_array([
+          ast::Argument::from(self.glue_fn_expr(load_expr)),
+          ast::Argument::from(self.snippet.builder.expression_array(SPAN, deps)),
+          ast::Argument::from(import_meta_url),
+        ]);
+        return Some(self.snippet.builder.expression_call(
+          span,
+          self.finalized_expr_for_runtime_symbol("__preload"),
+          NONE,
+          args,
+          false,
         ));
       }
     }
@@ -370,16 +629,30 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
 
   fn remove_unused_top_level_stmt(&mut self, program: &mut ast::Program<'ast>) {
     let old_body = program.body.take_in(self.allocator);
+    // Comments are printed before the statement starting where they are attached, so they have to
+    // follow the statements whose starts change, e.g. `export const a = 1` => `const a = 1`. Legal
+    // comments of removed statements are moved to the next statement to keep them in the output.
+    let mut comment_moves = FxHashMap::<u32, (u32, /* legal_only */ bool)>::default();
+    let mut removed_stmt_starts = vec![];
     old_body.into_iter().zip(self.ctx.module.stmt_infos.iter().skip(1)).for_each(
       |(mut top_stmt, stmt_info)| {
+        if top_stmt.is_module_declaration_with_source() {
+          program
+            .body
+            .extend(self.generate_init_calls_of_wrapped_importees(&stmt_info.import_records));
+        }
+
+        let old_start = top_stmt.span().start;
         if !stmt_info.is_included
           || top_stmt.is_import_declaration()
           || top_stmt.is_export_all_declaration()
         {
+          removed_stmt_starts.push(old_start);
           return;
         }
 
         if let Some(default_decl) = top_stmt.as_export_default_declaration_mut() {
+          let span = default_decl.span;
           match &mut default_decl.declaration {
             decl @ ast::match_expression!(ExportDefaultDeclarationKind) => {
               // "export default foo;" => "var default = foo;"
@@ -387,6 +660,9 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
                 self.canonical_name_for(self.ctx.module.default_export_ref),
                 decl.to_expression_mut().take_in(self.allocator),
               );
+              if let ast::Statement::VariableDeclaration(var_decl) = &mut top_stmt {
+                var_decl.span = span;
+              }
             }
             ast::ExportDefaultDeclarationKind::FunctionDeclaration(func) => {
               // "export default function() {}" => "function default() {}"
@@ -422,9 +698,11 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
           }
         } else if let Some(named_decl) = top_stmt.as_export_named_declaration_mut() {
           if named_decl.source.is_some() {
+            removed_stmt_starts.push(old_start);
             return;
           }
           let Some(decl) = &mut named_decl.declaration else {
+            removed_stmt_starts.push(old_start);
             return;
           };
           // `export var foo = 1` => `var foo = 1`
@@ -432,8 +710,84 @@ impl<'me, 'ast> ScopeHoistingFinalizer<'me, 'ast> {
           // `export class Foo {}` => `class Foo {}`
           top_stmt = ast::Statement::from(decl.take_in(self.allocator));
         }
+
+        let new_start = top_stmt.span().start;
+        if new_start != old_start {
+          comment_moves.insert(old_start, (new_start, false));
+        }
+        for start in removed_stmt_starts.drain(..) {
+          comment_moves.insert(start, (new_start, true));
+        }
+        let keep_name_stmt = if self.ctx.options.keep_names {
+          self.keep_names_of_top_level_stmt(&mut top_stmt)
+        } else {
+          None
+        };
         program.body.push(top_stmt);
+        program.body.extend(keep_name_stmt);
       },
     );
+
+    if !comment_moves.is_empty() {
+      for comment in program.comments.iter_mut() {
+        if let Some(&(start, legal_only)) = comment_moves.get(&comment.attached_to) {
+          if !legal_only || comment.is_legal() {
+            comment.attached_to = start;
+          }
+        }
+      }
+    }
   }
 }
+
+#[tokio::test]
+async fn test_preload_dynamic_deps() {
+  use minipack_common::{BundlerOptions, InputItem, OutputFormat};
+  use std::path::Path;
+
+  let build = |platform: Platform, format: OutputFormat| async move {
+    let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/preload");
+    let mut bundler = crate::Bundler::new(BundlerOptions {
+      cwd: Some(cwd),
+      input: Some(vec![InputItem { name: None, import: "./main.js".to_string() }]),
+      platform: Some(platform),
+      format: Some(format),
+      preload_dynamic_deps: Some(true),
+      ..BundlerOptions::default()
+    })
+    .unwrap();
+    bundler.build(false).await.unwrap()
+  };
+  let filename = |output: &crate::types::bundle_output::BundleOutput, name: &str| {
+    output.assets.iter().find(|asset| asset.filename.starts_with(name)).unwrap().filename.clone()
+  };
+  let main = |output: &crate::types::bundle_output::BundleOutput| {
+    output.assets.iter().find(|asset| asset.filename == "main.js").unwrap().content.clone()
+  };
+
+  let output = build(Platform::Browser, OutputFormat::Esm).await;
+  let (page, shared, leaf) =
+    (filename(&output, "page-"), filename(&output, "shared-"), filename(&output, "leaf-"));
+  let main_js = main(&output);
+  assert!(main_js.contains(&format!(
+    "__preload(function() {{\n\treturn import(\"./{page}\");\n}}, [\"./{shared}\"], import.meta.url);"
+  )));
+  // Chunks without static imports are imported as usual.
+  assert!(main_js.contains(&format!("\nimport(\"./{leaf}\");")));
+  assert!(main_js.contains("typeof document === \"undefined\""));
+  assert!(!main_js.contains("??="));
+  assert!(output.warnings.is_empty());
+
+  // Outside of browsers, the static imports are imported in parallel instead.
+  let output = build(Platform::Node, OutputFormat::Esm).await;
+  assert!(main(&output).contains("__preload("));
+  assert!(output.warnings.is_empty());
+
+  let output = build(Platform::Node, OutputFormat::Cjs).await;
+  assert!(!main(&output).contains("__preload"));
+  assert_eq!(output.warnings.len(), 1);
+  assert_eq!(
+    output.warnings[0].to_string(),
+    r#"Option "preload_dynamic_deps" is ignored with the "cjs" output format - only "esm" chunks are preloaded."#
+  );
+}
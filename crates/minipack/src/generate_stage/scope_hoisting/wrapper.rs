@@ -0,0 +1,189 @@
+use minipack_common::{ImportRecordIdx, ModuleIdx, SymbolRef};
+use minipack_ecmascript::BindingPatternExt;
+use oxc::{
+  allocator::TakeIn,
+  ast::{
+    NONE,
+    ast::{self, AssignmentOperator, VariableDeclarationKind},
+  },
+  span::SPAN,
+};
+
+impl<'ast> super::ScopeHoistingFinalizer<'_, 'ast> {
+  /// `init_foo();` for each wrapped module imported by the import records of a statement. They
+  /// take the place of the import statement, so modules are executed in the order of imports.
+  pub fn generate_init_calls_of_wrapped_importees(
+    &self,
+    import_records: &[ImportRecordIdx],
+  ) -> Vec<ast::Statement<'ast>> {
+    import_records
+      .iter()
+      .filter_map(|rec_idx| {
+        let rec = &self.ctx.module.import_records[*rec_idx];
+        if !rec.kind.is_static() {
+          return None;
+        }
+        let importee = self.ctx.modules[rec.state].as_normal()?;
+        if !importee.meta.is_included() {
+          return None;
+        }
+        let wrapper_ref = self.ctx.metadata[importee.idx].wrapper_ref?;
+        Some(
+          self
+            .snippet
+            .builder
+            .statement_expression(SPAN, self.init_call_expr(importee.idx, wrapper_ref)),
+        )
+      })
+      .collect()
+  }
+
+  /// `init_foo()`, or `await init_foo()` if the module has to wait for top-level await.
+  pub fn init_call_expr(
+    &self,
+    module_idx: ModuleIdx,
+    wrapper_ref: SymbolRef,
+  ) -> ast::Expression<'ast> {
+    let call_expr = self.snippet.builder.expression_call(
+      SPAN,
+      self.finalized_expr_for_symbol_ref(wrapper_ref, false),
+      NONE,
+      self.snippet.builder.vec(),
+      false,
+    );
+    if self.ctx.metadata[module_idx].is_tla_or_contains_tla_dependency {
+      self.snippet.builder.expression_await(SPAN, call_expr)
+    } else {
+      call_expr
+    }
+  }
+
+  /// Move the body of the module into `var init_foo = __esm(() => { ... })`. Top-level
+  /// declarations are hoisted out of the wrapper, so other modules could still refer to them.
+  ///
+  /// - `const a = 1` => `var a;` and `a = 1` in the wrapper
+  /// - `class A {}` => `var A;` and `A = class A {}` in the wrapper
+  /// - `function f() {}` stays outside of the wrapper
+  pub fn wrap_module_body(
+    &self,
+    program: &mut ast::Program<'ast>,
+    unwrapped_len: usize,
+    wrapper_ref: SymbolRef,
+  ) {
+    let builder = &self.snippet.builder;
+    let mut body = program.body.take_in(self.allocator).into_iter();
+    let mut outer_stmts = body.by_ref().take(unwrapped_len).collect::<Vec<_>>();
+    let mut hoisted_names = vec![];
+    let mut wrapped_stmts = builder.vec();
+
+    for stmt in body {
+      match stmt {
+        ast::Statement::FunctionDeclaration(_) => outer_stmts.push(stmt),
+        ast::Statement::VariableDeclaration(mut decl)
+          if !matches!(
+            decl.kind,
+            VariableDeclarationKind::Using | VariableDeclarationKind::AwaitUsing
+          ) =>
+        {
+          for declarator in decl.declarations.take_in(self.allocator) {
+            hoisted_names.extend(declarator.id.binding_identifiers().iter().map(|id| id.name));
+            if let Some(init) = declarator.init {
+              let target = declarator.id.into_assignment_target(self.allocator);
+              wrapped_stmts.push(builder.statement_expression(
+                SPAN,
+                builder.expression_assignment(SPAN, AssignmentOperator::Assign, target, init),
+              ));
+            }
+          }
+        }
+        ast::Statement::ClassDeclaration(mut class) if class.id.is_some() => {
+          let name = class.id.as_ref().unwrap().name;
+          hoisted_names.push(name);
+          class.r#type = ast::ClassType::ClassExpression;
+          wrapped_stmts.push(builder.statement_expression(
+            SPAN,
+            builder.expression_assignment(
+              SPAN,
+              AssignmentOperator::Assign,
+              self.snippet.simple_id_assignment_target(&name, SPAN),
+              ast::Expression::ClassExpression(class),
+            ),
+          ));
+        }
+        _ => wrapped_stmts.push(stmt),
+      }
+    }
+
+    if !hoisted_names.is_empty() {
+      let declarations = builder.vec_from_iter(hoisted_names.into_iter().map(|name| {
+        builder.variable_declarator(
+          SPAN,
+          VariableDeclarationKind::Var,
+          builder.binding_pattern(
+            builder.binding_pattern_kind_binding_identifier(SPAN, name),
+            NONE,
+            false,
+          ),
+          None,
+          false,
+        )
+      }));
+      outer_stmts.push(ast::Statement::VariableDeclaration(builder.alloc_variable_declaration(
+        SPAN,
+        VariableDeclarationKind::Var,
+        declarations,
+        false,
+      )));
+    }
+
+    // var init_foo = __esm(() => { ... })
+    let is_async = self.ctx.linking_info.is_tla_or_contains_tla_dependency;
+    let wrapper_fn = if self.ctx.options.generated_code.arrow_functions {
+      ast::Expression::ArrowFunctionExpression(builder.alloc_arrow_function_expression(
+        SPAN,
+        false,
+        is_async,
+        NONE,
+        builder.formal_parameters(
+          SPAN,
+          ast::FormalParameterKind::ArrowFormalParameters,
+          builder.vec(),
+          NONE,
+        ),
+        NONE,
+        builder.function_body(SPAN, builder.vec(), wrapped_stmts),
+      ))
+    } else {
+      ast::Expression::FunctionExpression(builder.alloc_function(
+        SPAN,
+        ast::FunctionType::FunctionExpression,
+        None::<ast::BindingIdentifier>,
+        false,
+        is_async,
+        false,
+        NONE,
+        NONE,
+        builder.formal_parameters(
+          SPAN,
+          ast::FormalParameterKind::FormalParameter,
+          builder.vec(),
+          NONE,
+        ),
+        NONE,
+        Some(builder.function_body(SPAN, builder.vec(), wrapped_stmts)),
+      ))
+    };
+    outer_stmts.push(self.snippet.var_decl_stmt(
+      self.canonical_name_for(wrapper_ref),
+      builder.expression_call(
+        SPAN,
+        self.finalized_expr_for_runtime_symbol("__esm"),
+        NONE,
+        builder.vec1(ast::Argument::from(wrapper_fn)),
+        false,
+      ),
+    ));
+
+    program.body.extend(outer_stmts);
+  }
+}
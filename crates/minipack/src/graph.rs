@@ -1,6 +1,6 @@
 use minipack_common::{Chunk, ChunkIdx, ModuleIdx};
 use oxc_index::{IndexVec, index_vec};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::types::IndexModules;
 
@@ -26,6 +26,24 @@ impl ChunkGraph {
     self.chunk_table.push(chunk)
   }
 
+  /// Chunks loaded by the static imports of each chunk, directly or transitively.
+  pub fn static_chunk_imports(&self) -> IndexVec<ChunkIdx, FxHashSet<ChunkIdx>> {
+    self
+      .chunk_table
+      .indices()
+      .map(|chunk_id| {
+        let mut visited = FxHashSet::default();
+        let mut stack = self.chunk_table[chunk_id].cross_chunk_imports.clone();
+        while let Some(importee_chunk_id) = stack.pop() {
+          if importee_chunk_id != chunk_id && visited.insert(importee_chunk_id) {
+            stack.extend(self.chunk_table[importee_chunk_id].cross_chunk_imports.iter().copied());
+          }
+        }
+        visited
+      })
+      .collect()
+  }
+
   pub fn add_module_to_chunk(&mut self, module_idx: ModuleIdx, chunk_idx: ChunkIdx) {
     self.chunk_table[chunk_idx].modules.push(module_idx);
     self.module_to_chunk[module_idx] = Some(chunk_idx);
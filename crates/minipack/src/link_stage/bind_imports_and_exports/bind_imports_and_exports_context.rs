@@ -1,10 +1,12 @@
 use indexmap::IndexSet;
-use minipack_common::{Module, ModuleIdx, NamespaceAlias, Specifier, SymbolRef, SymbolRefDb};
+use minipack_common::{
+  Module, ModuleIdx, NamespaceAlias, OutputFormat, Specifier, SymbolRef, SymbolRefDb,
+};
 use minipack_utils::rstr::Rstr;
 use oxc::span::CompactStr;
 use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::types::{IndexModules, LinkingMetadataVec, SharedOptions};
+use crate::types::{IndexModules, LinkingMetadataVec};
 
 #[derive(Clone, Debug)]
 struct ImportTracker {
@@ -34,7 +36,7 @@ pub(super) struct BindImportsAndExportsContext<'a> {
   pub module_table: &'a IndexModules,
   pub metadata: &'a mut LinkingMetadataVec,
   pub symbol_db: &'a mut SymbolRefDb,
-  pub options: &'a SharedOptions,
+  pub format: OutputFormat,
   pub errors: Vec<anyhow::Error>,
   pub side_effects_modules: &'a FxHashSet<ModuleIdx>,
   pub external_imports: FxHashMap<ModuleIdx, FxHashMap<CompactStr, IndexSet<SymbolRef>>>,
@@ -47,7 +49,7 @@ impl BindImportsAndExportsContext<'_> {
       return;
     };
 
-    let is_esm = self.options.format.is_esm();
+    let is_esm = self.format.is_esm();
     for (&imported_as_ref, named_import) in &module.named_imports {
       let import_record = &module.import_records[named_import.record_id];
       let is_external = matches!(self.module_table[import_record.state], Module::External(_));
@@ -151,7 +153,7 @@ impl BindImportsAndExportsContext<'_> {
           }
         }
         ImportStatus::External(symbol_ref) => {
-          if self.options.format.is_esm() {
+          if self.format.is_esm() {
             // Imports from external modules should not be converted to CommonJS
             // if the output format preserves the original ES6 import statements
             MatchImportKind::Normal { symbol: tracker.imported_as, reexports: vec![] }
@@ -0,0 +1,129 @@
+use std::collections::VecDeque;
+
+use minipack_common::ModuleIdx;
+use rustc_hash::FxHashMap;
+
+use super::LinkStage;
+
+impl LinkStage {
+  /// A module has to wait for its dependencies using top-level await, so the flag is propagated
+  /// from modules using top-level await to all of their static importers.
+  pub(crate) fn compute_tla(&mut self) {
+    for &idx in &self.sorted_modules {
+      if let Some(module) = self.module_table[idx].as_normal() {
+        self.metadata[idx].is_tla_or_contains_tla_dependency =
+          module.ecma_view.meta.has_top_level_await();
+      }
+    }
+
+    // `sorted_modules` is in execution order, so dependencies are visited before their importers,
+    // except for the ones in circular dependencies, which need another round.
+    let mut changed = true;
+    while changed {
+      changed = false;
+      for &idx in &self.sorted_modules {
+        if self.metadata[idx].is_tla_or_contains_tla_dependency {
+          continue;
+        }
+        if self.metadata[idx]
+          .dependencies
+          .iter()
+          .any(|dep| self.metadata[*dep].is_tla_or_contains_tla_dependency)
+        {
+          self.metadata[idx].is_tla_or_contains_tla_dependency = true;
+          changed = true;
+        }
+      }
+    }
+
+    if !self.options.format.is_esm() {
+      self.validate_tla_format();
+    }
+  }
+
+  /// Top-level await is a syntax error outside of ES modules, report the shortest import chain from
+  /// each entry to the modules using it.
+  fn validate_tla_format(&mut self) {
+    let mut reported = vec![];
+    for entry in &self.entry_points {
+      if !self.metadata[entry.idx].is_tla_or_contains_tla_dependency {
+        continue;
+      }
+
+      let mut importers = FxHashMap::<ModuleIdx, ModuleIdx>::default();
+      let mut queue = VecDeque::from([entry.idx]);
+      let mut tla_module = None;
+      while let Some(idx) = queue.pop_front() {
+        if self.module_table[idx]
+          .as_normal()
+          .is_some_and(|module| module.ecma_view.meta.has_top_level_await())
+        {
+          tla_module = Some(idx);
+          break;
+        }
+        for &dep in &self.metadata[idx].dependencies {
+          if dep != entry.idx
+            && self.metadata[dep].is_tla_or_contains_tla_dependency
+            && !importers.contains_key(&dep)
+          {
+            importers.insert(dep, idx);
+            queue.push_back(dep);
+          }
+        }
+      }
+      let Some(tla_module) = tla_module else {
+        continue;
+      };
+
+      if reported.contains(&tla_module) {
+        continue;
+      }
+      reported.push(tla_module);
+
+      let mut chain = vec![tla_module];
+      while let Some(importer) = importers.get(chain.last().unwrap()) {
+        chain.push(*importer);
+      }
+      let chain =
+        chain.iter().rev().map(|idx| self.module_table[*idx].stable_id()).collect::<Vec<_>>();
+
+      self.errors.push(anyhow::anyhow!(
+        r#"Top-level await is not supported with the "{}" output format, but "{}" uses it: {}."#,
+        self.options.format,
+        self.module_table[tla_module].stable_id(),
+        chain.join(" -> ")
+      ));
+    }
+  }
+}
+
+#[tokio::test]
+async fn test_tla_is_rejected_outside_of_esm() {
+  use std::path::Path;
+
+  use minipack_common::{BundlerOptions, InputItem, OutputFormat};
+
+  let build = |format: OutputFormat| async move {
+    let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tla_order");
+    let mut bundler = crate::Bundler::new(BundlerOptions {
+      cwd: Some(cwd),
+      input: Some(vec![InputItem { name: None, import: "./another.js".to_string() }]),
+      format: Some(format),
+      ..BundlerOptions::default()
+    })
+    .unwrap();
+    bundler.build(false).await
+  };
+
+  for format in [OutputFormat::Cjs, OutputFormat::Iife] {
+    let Err(errors) = build(format).await else { panic!("{format} can't use top-level await") };
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+      errors[0].to_string(),
+      format!(
+        r#"Top-level await is not supported with the "{format}" output format, but "second.js" uses it: another.js -> second.js."#
+      )
+    );
+  }
+  assert!(build(OutputFormat::Esm).await.is_ok());
+}
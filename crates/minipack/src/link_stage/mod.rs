@@ -1,18 +1,24 @@
 mod bind_imports_and_exports;
+mod compute_tla;
 mod patch_module_dependencies;
 mod prepare_ecma_module_export_info;
 mod reference_needed_symbols;
 mod sort_modules;
 mod tree_shaking;
+mod wrap_modules;
+
+pub use bind_imports_and_exports::bind_imports_and_exports;
 
 use minipack_common::{
-  EntryPoint, ImportKind, ModuleIdx, RuntimeModuleBrief, SymbolRef, SymbolRefDb,
+  EntryPoint, EntryPointKind, ImportKind, ModuleIdx, RuntimeModuleBrief, SymbolRef, SymbolRefDb,
+  TypeOnlyModule,
 };
 use oxc_index::IndexVec;
 use rustc_hash::FxHashSet;
 
 use crate::types::{
-  IndexEcmaAst, IndexModules, LinkingMetadataVec, SharedOptions, linking_metadata::LinkingMetadata,
+  IndexEcmaAst, IndexModules, LinkingMetadataVec, SharedOptions, html_entry::HtmlEntry,
+  linking_metadata::LinkingMetadata,
 };
 
 use super::scan_stage::ScanStageOutput;
@@ -21,6 +27,7 @@ use super::scan_stage::ScanStageOutput;
 pub struct LinkStageOutput {
   pub module_table: IndexModules,
   pub entry_points: Vec<EntryPoint>,
+  pub worker_entries: Vec<EntryPoint>,
   pub ecma_ast: IndexEcmaAst,
   pub metadata: LinkingMetadataVec,
   pub symbol_ref_db: SymbolRefDb,
@@ -28,21 +35,27 @@ pub struct LinkStageOutput {
   pub warnings: Vec<anyhow::Error>,
   pub errors: Vec<anyhow::Error>,
   pub used_symbol_refs: FxHashSet<SymbolRef>,
+  pub html_entries: Vec<HtmlEntry>,
+  pub type_only_modules: Vec<TypeOnlyModule>,
 }
 
 #[derive(Debug)]
 pub struct LinkStage {
   pub module_table: IndexModules,
   pub entry_points: Vec<EntryPoint>,
+  pub worker_entries: Vec<EntryPoint>,
   pub symbol_ref_db: SymbolRefDb,
   pub runtime_module: RuntimeModuleBrief,
   pub sorted_modules: Vec<ModuleIdx>,
+  pub has_circular_dependencies: bool,
   pub metadata: LinkingMetadataVec,
   pub warnings: Vec<anyhow::Error>,
   pub errors: Vec<anyhow::Error>,
   pub ecma_ast: IndexEcmaAst,
   pub options: SharedOptions,
   pub used_symbol_refs: FxHashSet<SymbolRef>,
+  pub html_entries: Vec<HtmlEntry>,
+  pub type_only_modules: Vec<TypeOnlyModule>,
 }
 
 impl LinkStage {
@@ -53,9 +66,14 @@ impl LinkStage {
       symbol_ref_db,
       entry_points,
       runtime_module,
+      html_entries,
+      type_only_modules,
       warnings,
     } = scan_stage_output;
 
+    let (worker_entries, entry_points) =
+      entry_points.into_iter().partition(|entry| entry.kind == EntryPointKind::Worker);
+
     let metadata = module_table
       .iter()
       .map(|module| {
@@ -63,7 +81,7 @@ impl LinkStage {
           .import_records()
           .iter()
           .filter_map(|rec| match rec.kind {
-            ImportKind::DynamicImport => None,
+            ImportKind::DynamicImport | ImportKind::Worker => None,
             _ => Some(rec.state),
           })
           .collect();
@@ -85,22 +103,28 @@ impl LinkStage {
       metadata,
       module_table,
       entry_points,
+      worker_entries,
       symbol_ref_db,
       runtime_module,
       warnings,
       errors: vec![],
       sorted_modules: vec![],
+      has_circular_dependencies: false,
       ecma_ast,
       options,
       used_symbol_refs: FxHashSet::default(),
+      html_entries,
+      type_only_modules,
     }
   }
 
   pub fn link(mut self) -> LinkStageOutput {
     self.sort_modules();
+    self.compute_tla();
     self.determine_side_effects();
     self.bind_imports_and_exports();
     self.prepare_ecma_module_export_info();
+    self.wrap_modules();
     self.reference_needed_symbols();
     self.include_statements();
     self.patch_module_dependencies();
@@ -109,10 +133,13 @@ impl LinkStage {
       symbol_ref_db: self.symbol_ref_db,
       metadata: self.metadata,
       entry_points: self.entry_points,
+      worker_entries: self.worker_entries,
       module_table: self.module_table,
       runtime_module: self.runtime_module,
       ecma_ast: self.ecma_ast,
       used_symbol_refs: self.used_symbol_refs,
+      html_entries: self.html_entries,
+      type_only_modules: self.type_only_modules,
       warnings: self.warnings,
       errors: self.errors,
     }
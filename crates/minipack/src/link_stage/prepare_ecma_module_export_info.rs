@@ -1,4 +1,5 @@
 use minipack_common::{OutputFormat, StmtInfo};
+use rustc_hash::FxHashSet;
 
 impl super::LinkStage {
   pub(crate) fn prepare_ecma_module_export_info(&mut self) {
@@ -21,10 +22,26 @@ impl super::LinkStage {
   }
 
   fn create_module_namespace_object_stmt_info(&mut self) {
+    let hmr_reused_ids = self
+      .options
+      .hmr_reused_modules
+      .iter()
+      .map(|module| module.id.as_str())
+      .collect::<FxHashSet<_>>();
+
     self.module_table.iter_mut().filter_map(|m| m.as_normal_mut()).for_each(|normal_module| {
       let mut declared_symbols = vec![];
       let mut referenced_symbols = vec![];
 
+      // The modules reused by a hmr update keep the namespace objects registered by the running build.
+      if self.options.hmr
+        && normal_module.idx != self.runtime_module.idx
+        && !hmr_reused_ids.contains(normal_module.id.as_ref())
+      {
+        self.metadata[normal_module.idx].registers_hmr_exports = true;
+        referenced_symbols.push(self.runtime_module.resolve_symbol("__hmrRegister").into());
+      }
+
       let linking_info = &self.metadata[normal_module.idx];
 
       if !linking_info.sorted_resolved_exports.is_empty() {
@@ -1,12 +1,15 @@
 use std::{ptr::addr_of, sync::Mutex};
 
-use minipack_common::{ImportRecordMeta, Module, OutputFormat};
+use minipack_common::{
+  EntryPointKind, GetLocalDb, ImportRecordMeta, Interop, Module, OutputFormat, SymbolRef,
+};
 use minipack_utils::{
   concat_string,
   rayon::{IntoParallelRefIterator, ParallelIterator},
 };
+use rustc_hash::{FxHashMap, FxHashSet};
 
-use crate::utils::ecmascript::legitimize_identifier_name;
+use crate::utils::{ecmascript::legitimize_identifier_name, renamer::reserved_names};
 
 impl super::LinkStage {
   pub(crate) fn reference_needed_symbols(&mut self) {
@@ -19,10 +22,43 @@ impl super::LinkStage {
       let stmt_infos = unsafe { &mut *(addr_of!(normal_module.stmt_infos).cast_mut()) };
 
       stmt_infos.infos.iter_mut().for_each(|stmt_info| {
+        // `import.meta.hot` will be rewritten to `__hot(stable_id)`
+        if self.options.hmr && stmt_info.has_import_meta_hot {
+          stmt_info.referenced_symbols.push(self.runtime_module.resolve_symbol("__hot").into());
+        }
+
+        // `import(`./locales/${lang}.js`)` will be rewritten to `__glob({ ... }, `./locales/${lang}.js`)`
+        if stmt_info.import_records.iter().any(|&import_record_id| {
+          normal_module.import_records[import_record_id]
+            .meta
+            .contains(ImportRecordMeta::IS_IMPORT_GLOB)
+        }) {
+          stmt_info.referenced_symbols.push(self.runtime_module.resolve_symbol("__glob").into());
+        }
+
         stmt_info.import_records.iter().for_each(|&import_record_id| {
           let import_record = &normal_module.import_records[import_record_id];
 
-          if import_record.kind.is_static() {
+          if let Module::Normal(importee) = &self.module_table[import_record.state] {
+            // Workers are bundled by builds of their own, see `GenerateStage::build_workers`.
+            if !import_record.kind.is_dynamic() {
+              return;
+            }
+            // `import('./foo')` will be rewritten to `Promise.resolve().then(() => foo_exports)`
+            // when dynamic imports are inlined, so the namespace object of the importee is needed.
+            if self.options.inline_dynamic_imports {
+              stmt_info.referenced_symbols.push(importee.namespace_object_ref.into());
+              if let Some(wrapper_ref) = self.metadata[importee.idx].wrapper_ref {
+                stmt_info.referenced_symbols.push(wrapper_ref.into());
+              }
+            } else if self.options.preloads_dynamic_deps()
+              && self.metadata[importee.idx].may_have_preload_deps(self.runtime_module.idx)
+            {
+              // `import('./foo')` will be rewritten to `__preload(() => import('./foo'), [...], import.meta.url)`
+              stmt_info
+                .referenced_symbols
+                .push(self.runtime_module.resolve_symbol("__preload").into());
+            }
             return;
           }
 
@@ -38,6 +74,7 @@ impl super::LinkStage {
               // import ... from 'external' or export ... from 'external'
               if !import_record.meta.contains(ImportRecordMeta::IS_PLAIN_IMPORT)
                 && matches!(self.options.format, OutputFormat::Cjs)
+                && !matches!(self.options.interop, Interop::EsModule)
               {
                 stmt_info.side_effect = true;
                 stmt_info
@@ -49,5 +86,85 @@ impl super::LinkStage {
         });
       });
     });
+
+    if self.options.keep_names {
+      self.reference_keep_names_helper();
+    }
+  }
+
+  /// Renamed functions and classes get their names back by `__name(foo$1, "foo")`. The final names
+  /// are only known once the chunks are deconflicted, so `__name` is referenced by the declarations
+  /// whose names clash with a reserved name, a global or a symbol of the bundle that isn't linked to
+  /// them, i.e. the ones that may be renamed.
+  fn reference_keep_names_helper(&mut self) {
+    let format = self.options.format;
+    let mut reserved = reserved_names(format).collect::<FxHashSet<_>>();
+    let mut symbols_by_name = FxHashMap::<&str, Vec<SymbolRef>>::default();
+    for module in self.module_table.iter() {
+      let Some(db) = self.symbol_ref_db[module.idx()].as_ref() else {
+        continue;
+      };
+      let scoping = &db.ast_scopes;
+      for (symbol_id, name) in scoping.symbol_ids().zip(scoping.symbol_names()) {
+        symbols_by_name.entry(name).or_default().push((module.idx(), symbol_id).into());
+      }
+      reserved.extend(scoping.root_unresolved_references().keys().copied());
+    }
+
+    let entries = self.entry_points.iter().map(|entry| entry.idx).collect::<FxHashSet<_>>();
+    let user_defined_entries = self
+      .entry_points
+      .iter()
+      .filter(|entry| matches!(entry.kind, EntryPointKind::UserDefined))
+      .map(|entry| entry.idx)
+      .collect::<FxHashSet<_>>();
+    let symbol_ref_db = &self.symbol_ref_db;
+    let may_be_renamed = |default_export_ref: SymbolRef, symbol_ref: SymbolRef| {
+      // `export default function() {}` is named `default`, not after its binding.
+      if symbol_ref == default_export_ref {
+        return true;
+      }
+      let name = symbol_ref.name(symbol_ref_db);
+      // Bindings of `require_foo` are created for the chunks imported by `cjs` chunks.
+      if reserved.contains(name)
+        || (matches!(format, OutputFormat::Cjs) && name.starts_with("require_"))
+      {
+        return true;
+      }
+      let canonical_ref = symbol_ref.canonical_ref(symbol_ref_db);
+      symbols_by_name[name].iter().any(|&other| {
+        let scoping = &symbol_ref_db.local_db(other.owner).ast_scopes;
+        if other == symbol_ref
+          || (scoping.get_root_binding(name) == Some(other.symbol)
+            && other.canonical_ref(symbol_ref_db) == canonical_ref)
+        {
+          return false;
+        }
+        // Nested symbols only clash with the names given in the module they are declared in, or in
+        // any module of the chunk of an entry. Top-level symbols of an entry keep their names though.
+        if scoping.symbol_scope_id(other.symbol) == scoping.root_scope_id() {
+          true
+        } else if other.owner == symbol_ref.owner {
+          !user_defined_entries.contains(&other.owner)
+        } else {
+          entries.contains(&other.owner)
+        }
+      })
+    };
+
+    let name_ref = self.runtime_module.resolve_symbol("__name");
+    for module in self.module_table.iter_mut().filter_map(Module::as_normal_mut) {
+      let default_export_ref = module.default_export_ref;
+      for stmt_info in module.stmt_infos.infos.iter_mut() {
+        if stmt_info.declares_function_or_class
+          && stmt_info
+            .declared_symbols
+            .iter()
+            .any(|&symbol_ref| may_be_renamed(default_export_ref, symbol_ref))
+        {
+          stmt_info.referenced_symbols.push(name_ref.into());
+        }
+      }
+    }
   }
 }
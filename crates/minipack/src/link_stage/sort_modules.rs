@@ -77,7 +77,8 @@ impl LinkStage {
       }
     }
 
-    if !circular_dependencies.is_empty() {
+    self.has_circular_dependencies = !circular_dependencies.is_empty();
+    if self.has_circular_dependencies {
       for cycle in circular_dependencies {
         let paths = cycle
           .iter()
@@ -76,6 +76,14 @@ fn include_module(ctx: &mut Context, module: &NormalModule) {
     return;
   }
 
+  if let Some(wrapper_ref) = ctx.metadata[module.idx].wrapper_ref {
+    include_symbol(ctx, wrapper_ref);
+  }
+
+  if ctx.metadata[module.idx].registers_hmr_exports {
+    include_statement(ctx, module, StmtInfoIdx::new(0));
+  }
+
   module.stmt_infos.iter_enumerated().for_each(|(stmt_info_id, stmt_info)| {
     if stmt_info.side_effect {
       include_statement(ctx, module, stmt_info_id);
@@ -86,7 +94,10 @@ fn include_module(ctx: &mut Context, module: &NormalModule) {
   ctx.metadata[module.idx].dependencies.iter().copied().for_each(|dependency_idx| {
     match &ctx.module_table[dependency_idx] {
       Module::Normal(importee) => {
-        if importee.side_effects.has_side_effects() {
+        // Hmr updates may reuse any module, so none of them is tree-shaken.
+        if importee.side_effects.has_side_effects()
+          || ctx.metadata[importee.idx].registers_hmr_exports
+        {
           include_module(ctx, importee);
         }
       }
@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use minipack_common::{EntryPointKind, ModuleIdx, StmtInfo};
+use minipack_utils::{concat_string, path_ext::PathExt};
+use oxc_index::index_vec;
+use rustc_hash::FxHashSet;
+
+use crate::utils::ecmascript::legitimize_identifier_name;
+
+impl super::LinkStage {
+  /// Scope hoisting executes modules in the order they are placed in chunks, which differs from
+  /// native ESM if there are circular imports, or if modules are shared by several entries and
+  /// split into common chunks. With `strict_execution_order`, modules are wrapped in
+  /// `var init_foo = __esm(() => { ... })` and executed by `init_foo()` where they are imported.
+  ///
+  /// Once a module runs lazily, any module placed after it would run too early at the top level,
+  /// so all modules are wrapped except entries which are never imported by other modules.
+  ///
+  /// Modules are also wrapped without `strict_execution_order` if a module using top-level await
+  /// is shared. Chunks imported by the same chunk run concurrently while one of them awaits, but
+  /// `await init_foo()` runs them one after another in the order of imports.
+  pub(crate) fn wrap_modules(&mut self) {
+    let is_wrapped = if self.options.strict_execution_order {
+      self.has_circular_dependencies || self.has_shared_modules(|_| true)
+    } else {
+      self.has_shared_modules(|idx| self.metadata[idx].is_tla_or_contains_tla_dependency)
+    };
+    if !is_wrapped {
+      return;
+    }
+
+    let imported_modules = self
+      .module_table
+      .iter()
+      .filter_map(|module| module.as_normal())
+      .flat_map(|module| module.import_records.iter())
+      .filter(|rec| rec.kind.is_static())
+      .map(|rec| rec.state)
+      .collect::<FxHashSet<_>>();
+
+    for idx in self.sorted_modules.clone() {
+      if idx == self.runtime_module.idx {
+        continue;
+      }
+      let Some(module) = self.module_table[idx].as_normal_mut() else {
+        continue;
+      };
+      if !imported_modules.contains(&idx)
+        && self.entry_points.iter().any(|entry| entry.idx == module.idx)
+      {
+        continue;
+      }
+
+      let repr_name = Path::new(module.id.as_ref()).representative_file_name();
+      let wrapper_ref = self.symbol_ref_db.create_facade_root_symbol_ref(
+        idx,
+        &concat_string!("init_", legitimize_identifier_name(&repr_name)),
+      );
+
+      // The statement declaring `init_foo`, which is created by the finalizer.
+      module.stmt_infos.add_stmt_info(StmtInfo {
+        declared_symbols: vec![wrapper_ref],
+        referenced_symbols: vec![self.runtime_module.resolve_symbol("__esm").into()],
+        ..Default::default()
+      });
+      self.metadata[idx].wrapper_ref = Some(wrapper_ref);
+
+      // The entry chunk runs the entry module by calling `init_foo()` at the end.
+      if self.entry_points.iter().any(|entry| entry.idx == idx) {
+        self.metadata[idx].referenced_symbols_by_entry_point_chunk.push(wrapper_ref);
+      }
+    }
+  }
+
+  /// Whether a module matching `filter` is statically imported by more than one entry, so that it
+  /// might be placed in a common chunk executed before the modules imported prior to it.
+  fn has_shared_modules(&self, filter: impl Fn(ModuleIdx) -> bool) -> bool {
+    let mut owner = index_vec![None::<usize>; self.module_table.len()];
+    let entries = self.entry_points.iter().filter(|entry| {
+      !(self.options.inline_dynamic_imports && matches!(entry.kind, EntryPointKind::DynamicImport))
+    });
+    for (i, entry) in entries.enumerate() {
+      let mut visited = FxHashSet::default();
+      let mut stack = vec![entry.idx];
+      while let Some(idx) = stack.pop() {
+        if !visited.insert(idx) || !self.module_table[idx].is_normal() {
+          continue;
+        }
+        match owner[idx] {
+          Some(owner) if owner != i && filter(idx) => return true,
+          _ => owner[idx] = Some(i),
+        }
+        stack.extend(self.metadata[idx].dependencies.iter().copied());
+      }
+    }
+    false
+  }
+}
+
+#[tokio::test]
+async fn test_tla_chunks_are_awaited_in_order() {
+  use minipack_common::{BundlerOptions, InputItem};
+
+  let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tla_order");
+  let input = ["main.js", "other.js", "another.js"]
+    .map(|import| InputItem { name: None, import: concat_string!("./", import) });
+  let mut bundler = crate::Bundler::new(BundlerOptions {
+    cwd: Some(cwd),
+    input: Some(input.to_vec()),
+    ..BundlerOptions::default()
+  })
+  .unwrap();
+  let output = bundler.build(false).await.unwrap();
+
+  // `first.js` and `second.js` are split into chunks of their own, which `main.js` imports.
+  let chunk = |name: &str| {
+    output.assets.iter().find(|asset| asset.filename.starts_with(name)).unwrap().content.as_str()
+  };
+  assert!(chunk("first-").contains("var init_first = __esm(async function() {"));
+  assert!(chunk("second-").contains("var init_second = __esm(async function() {"));
+  assert!(chunk("main.js").contains("await init_first();\nawait init_second();\n"));
+}
+
+#[tokio::test]
+async fn test_strict_execution_order() {
+  use minipack_common::{BundlerOptions, InputItem};
+
+  let build = |strict_execution_order: bool| async move {
+    let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/strict_order");
+    let input = ["entry-a.js", "entry-b.js"]
+      .map(|import| InputItem { name: None, import: concat_string!("./", import) });
+    let mut bundler = crate::Bundler::new(BundlerOptions {
+      cwd: Some(cwd),
+      input: Some(input.to_vec()),
+      strict_execution_order: Some(strict_execution_order),
+      ..BundlerOptions::default()
+    })
+    .unwrap();
+    let output = bundler.build(false).await.unwrap();
+    ["entry-a.js", "entry-b.js", "shared-"].map(|name| {
+      output.assets.iter().find(|asset| asset.filename.starts_with(name)).unwrap().content.clone()
+    })
+  };
+
+  // `shared.js` runs when its chunk is imported, before `setup.js` which `entry-a.js` imports first.
+  let [_, _, shared] = build(false).await;
+  assert!(shared.starts_with("\nconsole.log(\"shared\");"));
+
+  let [entry_a, entry_b, shared] = build(true).await;
+  assert!(shared.contains("var init_shared = __esm(function() {\n\tconsole.log(\"shared\");"));
+  assert!(entry_a.contains("init_setup();\ninit_shared();\ninit_a();\nconsole.log(\"entry-a\""));
+  assert!(entry_b.contains("init_shared();\nconsole.log(\"entry-b\""));
+  // `a.js` runs `b.js` first, which doesn't run `a.js` again since it has already started.
+  assert!(entry_a.contains("var init_b = __esm(function() {\n\tinit_a();\n\tconsole.log(\"b\");"));
+  assert!(
+    entry_a.contains("var init_a = __esm(function() {\n\tinit_b();\n\tconsole.log(\"a\", b());")
+  );
+}
@@ -1,4 +1,5 @@
 use minipack_common::{ImportKind, ImportRecordMeta};
+use minipack_ecmascript::ExpressionExt;
 use minipack_utils::option_ext::OptionExt;
 use oxc::{
   ast::ast::{self, IdentifierReference},
@@ -6,7 +7,7 @@ use oxc::{
   span::GetSpan,
 };
 
-use super::side_effect_detector::SideEffectDetector;
+use super::{declares_function_or_class, side_effect_detector::SideEffectDetector};
 
 impl<'ast> Visit<'ast> for super::AstScanner<'ast> {
   fn enter_node(&mut self, kind: oxc::ast::AstKind<'ast>) {
@@ -23,6 +24,7 @@ impl<'ast> Visit<'ast> for super::AstScanner<'ast> {
 
       self.current_stmt_info.stmt_idx = Some(idx.into());
       self.current_stmt_info.side_effect = side_effect_detector.detect_side_effect_of_stmt(stmt);
+      self.current_stmt_info.declares_function_or_class = declares_function_or_class(stmt);
 
       self.visit_statement(stmt);
       self.result.stmt_infos.add_stmt_info(std::mem::take(&mut self.current_stmt_info));
@@ -56,7 +58,59 @@ impl<'ast> Visit<'ast> for super::AstScanner<'ast> {
         ImportRecordMeta::empty(),
       );
       self.result.imports.insert(expr.span, import_rec_idx);
+    } else {
+      self.scan_import_glob(expr);
     }
     walk::walk_import_expression(self, expr);
   }
+
+  fn visit_new_expression(&mut self, expr: &ast::NewExpression<'ast>) {
+    self.scan_new_worker(expr);
+    walk::walk_new_expression(self, expr);
+  }
+
+  fn visit_await_expression(&mut self, expr: &ast::AwaitExpression<'ast>) {
+    self.scan_top_level_await();
+    walk::walk_await_expression(self, expr);
+  }
+
+  fn visit_for_of_statement(&mut self, stmt: &ast::ForOfStatement<'ast>) {
+    if stmt.r#await {
+      self.scan_top_level_await();
+    }
+    walk::walk_for_of_statement(self, stmt);
+  }
+
+  fn visit_variable_declaration(&mut self, decl: &ast::VariableDeclaration<'ast>) {
+    if decl.kind == ast::VariableDeclarationKind::AwaitUsing {
+      self.scan_top_level_await();
+    }
+    walk::walk_variable_declaration(self, decl);
+  }
+
+  fn visit_static_member_expression(&mut self, expr: &ast::StaticMemberExpression<'ast>) {
+    if expr.object.is_import_meta() && expr.property.name == "hot" {
+      self.current_stmt_info.has_import_meta_hot = true;
+      self.result.has_import_meta_hot = true;
+    }
+    walk::walk_static_member_expression(self, expr);
+  }
+
+  fn visit_call_expression(&mut self, expr: &ast::CallExpression<'ast>) {
+    if is_import_meta_hot_accept(&expr.callee) {
+      self.scan_import_meta_hot_accept(expr);
+    }
+    walk::walk_call_expression(self, expr);
+  }
+}
+
+/// `import.meta.hot.accept`
+fn is_import_meta_hot_accept(callee: &ast::Expression) -> bool {
+  match callee {
+    ast::Expression::StaticMemberExpression(accept) if accept.property.name == "accept" => {
+      matches!(&accept.object, ast::Expression::StaticMemberExpression(hot)
+        if hot.object.is_import_meta() && hot.property.name == "hot")
+    }
+    _ => false,
+  }
 }
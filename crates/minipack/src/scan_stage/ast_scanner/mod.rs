@@ -8,10 +8,10 @@ use std::borrow::Cow;
 
 use minipack_common::{
   ImportKind, ImportRecordIdx, ImportRecordMeta, LocalExport, MemberExprRef, ModuleIdx,
-  NamedImport, RawImportRecord, Specifier, StmtInfo, StmtInfos, SymbolRef, SymbolRefDbForModule,
-  SymbolRefFlags,
+  NamedImport, RawImportRecord, Specifier, StmtInfo, StmtInfoIdx, StmtInfos, SymbolRef,
+  SymbolRefDbForModule, SymbolRefFlags,
 };
-use minipack_ecmascript::{BindingIdentifierExt, BindingPatternExt};
+use minipack_ecmascript::{BindingIdentifierExt, BindingPatternExt, ExpressionExt};
 use minipack_error::BuildResult;
 use minipack_utils::{concat_string, path_ext::PathExt, rstr::Rstr};
 use oxc::{
@@ -32,17 +32,32 @@ use sugar_path::SugarPath;
 
 use crate::utils::ecmascript::legitimize_identifier_name;
 
+/// `import()` with a variable path, e.g. `` import(`./locales/${lang}.js`) ``.
+#[derive(Debug)]
+pub struct ImportGlob {
+  /// Span of the `import()` expression.
+  pub span: Span,
+  /// `./locales/*.js`, or `None` if the path can't be matched against files.
+  pub pattern: Option<String>,
+  pub stmt_info_idx: StmtInfoIdx,
+}
+
 #[derive(Debug)]
 pub struct AstScanResult {
   pub symbols: SymbolRefDbForModule,
   pub stmt_infos: StmtInfos,
   pub has_star_exports: bool,
+  pub has_import_meta_hot: bool,
+  pub has_top_level_await: bool,
+  pub hmr_self_accepting: bool,
+  pub hmr_accepted_deps: Vec<ImportRecordIdx>,
   pub default_export_ref: SymbolRef,
   pub namespace_object_ref: SymbolRef,
   pub imports: FxHashMap<Span, ImportRecordIdx>,
   pub named_imports: FxHashMap<SymbolRef, NamedImport>,
   pub named_exports: FxHashMap<Rstr, LocalExport>,
   pub import_records: IndexVec<ImportRecordIdx, RawImportRecord>,
+  pub import_globs: Vec<ImportGlob>,
   pub errors: Vec<anyhow::Error>,
   pub warnings: Vec<anyhow::Error>,
 }
@@ -52,6 +67,8 @@ pub struct AstScanner<'ast> {
   result: AstScanResult,
   current_stmt_info: StmtInfo,
   visit_path: Vec<AstKind<'ast>>,
+  /// Specifiers passed to `import.meta.hot.accept(deps, cb)`.
+  hmr_accepted_specifiers: Vec<CompactStr>,
 }
 
 impl<'ast> AstScanner<'ast> {
@@ -81,6 +98,7 @@ impl<'ast> AstScanner<'ast> {
         stmt_infos
       },
       import_records: IndexVec::new(),
+      import_globs: Vec::new(),
       default_export_ref,
       imports: FxHashMap::default(),
       warnings: Vec::new(),
@@ -88,16 +106,51 @@ impl<'ast> AstScanner<'ast> {
       symbols: symbol_ref_db,
       namespace_object_ref,
       has_star_exports: false,
+      has_import_meta_hot: false,
+      has_top_level_await: false,
+      hmr_self_accepting: false,
+      hmr_accepted_deps: Vec::new(),
     };
 
-    Self { idx, current_stmt_info: StmtInfo::default(), result, visit_path: vec![] }
+    Self {
+      idx,
+      current_stmt_info: StmtInfo::default(),
+      result,
+      visit_path: vec![],
+      hmr_accepted_specifiers: vec![],
+    }
   }
 
   pub fn scan(mut self, program: &Program<'ast>) -> BuildResult<AstScanResult> {
     self.visit_program(program);
+
+    // Accepted dependencies must be imported by the module, so they are mapped to import records
+    // after all imports are collected.
+    self.result.hmr_accepted_deps = self
+      .hmr_accepted_specifiers
+      .iter()
+      .filter_map(|specifier| {
+        self.result.import_records.iter_enumerated().find_map(|(idx, rec)| {
+          (rec.kind.is_static() && rec.specifier.as_str() == specifier.as_str()).then_some(idx)
+        })
+      })
+      .collect();
+
     Ok(self.result)
   }
 
+  /// `await` is only a top-level await if it's not nested in any function or static block.
+  fn scan_top_level_await(&mut self) {
+    if !self.visit_path.iter().any(|kind| {
+      matches!(
+        kind,
+        AstKind::Function(_) | AstKind::ArrowFunctionExpression(_) | AstKind::StaticBlock(_)
+      )
+    }) {
+      self.result.has_top_level_await = true;
+    }
+  }
+
   fn add_declared_id(&mut self, id: SymbolId) {
     self.current_stmt_info.declared_symbols.push((self.idx, id).into());
   }
@@ -332,7 +385,24 @@ impl<'ast> AstScanner<'ast> {
             let id = cls_decl.id.as_ref().unwrap();
             self.add_local_export(id.name.as_str(), id.expect_symbol_id(), id.span);
           }
-          _ => unreachable!("doesn't support ts now"),
+          // Only left in the declarations of the `dts` option, the code is transpiled.
+          ast::Declaration::TSTypeAliasDeclaration(decl) => {
+            self.add_local_export(decl.id.name.as_str(), decl.id.expect_symbol_id(), decl.id.span);
+          }
+          ast::Declaration::TSInterfaceDeclaration(decl) => {
+            self.add_local_export(decl.id.name.as_str(), decl.id.expect_symbol_id(), decl.id.span);
+          }
+          ast::Declaration::TSEnumDeclaration(decl) => {
+            self.add_local_export(decl.id.name.as_str(), decl.id.expect_symbol_id(), decl.id.span);
+          }
+          ast::Declaration::TSImportEqualsDeclaration(decl) => {
+            self.add_local_export(decl.id.name.as_str(), decl.id.expect_symbol_id(), decl.id.span);
+          }
+          ast::Declaration::TSModuleDeclaration(decl) => {
+            if let ast::TSModuleDeclarationName::Identifier(id) = &decl.id {
+              self.add_local_export(id.name.as_str(), id.expect_symbol_id(), id.span);
+            }
+          }
         }
       }
     }
@@ -357,7 +427,9 @@ impl<'ast> AstScanner<'ast> {
         .id
         .as_ref()
         .map(|id| (minipack_ecmascript::BindingIdentifierExt::expect_symbol_id(id), id.span)),
-      ast::ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => unreachable!(),
+      ast::ExportDefaultDeclarationKind::TSInterfaceDeclaration(decl) => {
+        Some((decl.id.expect_symbol_id(), decl.id.span))
+      }
     };
 
     let (reference, span) = local_binding_for_default_export
@@ -418,6 +490,75 @@ impl<'ast> AstScanner<'ast> {
     }
   }
 
+  /// Collect accepted dependencies of `import.meta.hot.accept(...)`:
+  /// - `accept()` or `accept(cb)` accepts updates of the module itself.
+  /// - `accept('./dep.js', cb)` or `accept(['./a.js', './b.js'], cb)` accepts updates of dependencies.
+  fn scan_import_meta_hot_accept(&mut self, expr: &ast::CallExpression<'ast>) {
+    match expr.arguments.first() {
+      Some(ast::Argument::StringLiteral(dep)) => {
+        self.hmr_accepted_specifiers.push(dep.value.as_str().into());
+      }
+      Some(ast::Argument::ArrayExpression(deps)) => {
+        self.hmr_accepted_specifiers.extend(deps.elements.iter().filter_map(|dep| match dep {
+          ast::ArrayExpressionElement::StringLiteral(dep) => Some(dep.value.as_str().into()),
+          _ => None,
+        }));
+      }
+      _ => self.result.hmr_self_accepting = true,
+    }
+  }
+
+  /// The files an `import()` with a template literal or a string concatenation could load are
+  /// matched by a glob of its static parts, only relative paths with a static file name are supported.
+  fn scan_import_glob(&mut self, expr: &ast::ImportExpression<'ast>) {
+    let mut pattern = String::new();
+    push_import_glob_parts(&expr.source, &mut pattern);
+    let is_supported = (pattern.starts_with("./") || pattern.starts_with("../"))
+      && pattern.rsplit('/').next().is_some_and(|file_name| file_name != "*");
+    self.result.import_globs.push(ImportGlob {
+      span: expr.span,
+      pattern: is_supported.then_some(pattern),
+      stmt_info_idx: self.result.stmt_infos.next_idx(),
+    });
+  }
+
+  /// `new Worker(new URL('./worker.js', import.meta.url))` makes the worker file an entry of its own,
+  /// `SharedWorker` is handled the same way.
+  fn scan_new_worker(&mut self, expr: &ast::NewExpression<'ast>) {
+    let ast::Expression::Identifier(callee) = &expr.callee else {
+      return;
+    };
+    if !matches!(callee.name.as_str(), "Worker" | "SharedWorker")
+      || !self.result.symbols.ast_scopes.is_unresolved(callee.reference_id())
+    {
+      return;
+    }
+    let Some(ast::Argument::NewExpression(url)) = expr.arguments.first() else {
+      return;
+    };
+    let (Some(ast::Argument::StringLiteral(request)), Some(base)) =
+      (url.arguments.first(), url.arguments.get(1))
+    else {
+      return;
+    };
+    if !matches!(&url.callee, ast::Expression::Identifier(ident) if ident.name == "URL")
+      || !matches!(base, ast::Argument::StaticMemberExpression(base)
+        if base.object.is_import_meta() && base.property.name == "url")
+    {
+      return;
+    }
+
+    let is_module_worker = matches!(expr.arguments.get(1), Some(ast::Argument::ObjectExpression(options))
+      if options.properties.iter().any(|prop| matches!(prop, ast::ObjectPropertyKind::ObjectProperty(prop)
+        if prop.key.static_name().is_some_and(|name| name == "type")
+          && matches!(&prop.value, ast::Expression::StringLiteral(value) if value.value == "module"))));
+    let meta =
+      if is_module_worker { ImportRecordMeta::IS_MODULE_WORKER } else { ImportRecordMeta::empty() };
+    let import_rec_idx =
+      self.add_import_record(request.value.as_str(), ImportKind::Worker, request.span, meta);
+    self.result.imports.insert(url.span, import_rec_idx);
+  }
+
   pub fn add_referenced_symbol(&mut self, sym_ref: SymbolRef) {
     self.current_stmt_info.referenced_symbols.push(sym_ref.into());
   }
@@ -484,9 +625,66 @@ impl<'ast> AstScanner<'ast> {
             break;
           }
         }
+        // `ns.Foo` in a type of the declarations of the `dts` option.
+        AstKind::TSQualifiedName(name) => {
+          span = name.span;
+          props.push(name.right.name.as_str().into());
+        }
+        AstKind::TSTypeName(_) => {}
         _ => break,
       }
     }
     (!props.is_empty() && span != SPAN).then_some((span, props))
   }
 }
+
+/// `./locales/*.js` for `` `./locales/${lang}.js` `` and `'./locales/' + lang + '.js'`.
+fn push_import_glob_parts(expr: &ast::Expression, pattern: &mut String) {
+  match expr {
+    ast::Expression::StringLiteral(lit) => pattern.push_str(&lit.value),
+    ast::Expression::TemplateLiteral(tpl) => {
+      for (i, quasi) in tpl.quasis.iter().enumerate() {
+        if let Some(expr) = i.checked_sub(1).and_then(|i| tpl.expressions.get(i)) {
+          push_import_glob_parts(expr, pattern);
+        }
+        pattern.push_str(quasi.value.cooked.as_ref().unwrap_or(&quasi.value.raw));
+      }
+    }
+    ast::Expression::BinaryExpression(expr) if expr.operator == ast::BinaryOperator::Addition => {
+      push_import_glob_parts(&expr.left, pattern);
+      push_import_glob_parts(&expr.right, pattern);
+    }
+    ast::Expression::ParenthesizedExpression(expr) => {
+      push_import_glob_parts(&expr.expression, pattern);
+    }
+    // `**` would match nested directories, so adjacent variables share a wildcard.
+    _ if !pattern.ends_with('*') => pattern.push('*'),
+    _ => {}
+  }
+}
+
+/// `function foo() {}`, `class Foo {}` and `const foo = () => {}`, also when they are exported.
+fn declares_function_or_class(stmt: &ast::Statement) -> bool {
+  let is_named_by_binding = |decl: &ast::VariableDeclaration| {
+    decl.declarations.iter().any(|declarator| {
+      declarator.init.as_ref().is_some_and(ast::Expression::is_anonymous_function_definition)
+    })
+  };
+  match stmt {
+    ast::Statement::FunctionDeclaration(_) | ast::Statement::ClassDeclaration(_) => true,
+    ast::Statement::VariableDeclaration(decl) => is_named_by_binding(decl),
+    ast::Statement::ExportNamedDeclaration(decl) => match &decl.declaration {
+      Some(ast::Declaration::FunctionDeclaration(_) | ast::Declaration::ClassDeclaration(_)) => {
+        true
+      }
+      Some(ast::Declaration::VariableDeclaration(decl)) => is_named_by_binding(decl),
+      _ => false,
+    },
+    ast::Statement::ExportDefaultDeclaration(decl) => match &decl.declaration {
+      ast::ExportDefaultDeclarationKind::FunctionDeclaration(_)
+      | ast::ExportDefaultDeclarationKind::ClassDeclaration(_) => true,
+      decl => decl.as_expression().is_some_and(ast::Expression::is_anonymous_function_definition),
+    },
+    _ => false,
+  }
+}
@@ -92,7 +92,7 @@ impl<'a> SideEffectDetector<'a> {
           }
         } || def.value.as_ref().is_some_and(|init| self.detect_side_effect_of_expr(init)))
       }
-      ClassElement::TSIndexSignature(_) => unreachable!("ts should be transpiled"),
+      ClassElement::TSIndexSignature(_) => false,
     })
   }
 
@@ -377,11 +377,12 @@ impl<'a> SideEffectDetector<'a> {
       Declaration::VariableDeclaration(var_decl) => self.detect_side_effect_of_var_decl(var_decl),
       Declaration::FunctionDeclaration(_) => false,
       Declaration::ClassDeclaration(cls_decl) => self.detect_side_effect_of_class(cls_decl),
+      // Only left in the declarations of the `dts` option, which are never executed.
       Declaration::TSTypeAliasDeclaration(_)
       | Declaration::TSInterfaceDeclaration(_)
       | Declaration::TSEnumDeclaration(_)
       | Declaration::TSModuleDeclaration(_)
-      | Declaration::TSImportEqualsDeclaration(_) => unreachable!("ts should be transpiled"),
+      | Declaration::TSImportEqualsDeclaration(_) => false,
     }
   }
 
@@ -432,9 +433,7 @@ impl<'a> SideEffectDetector<'a> {
             ast::ExportDefaultDeclarationKind::ClassDeclaration(decl) => {
               self.detect_side_effect_of_class(decl)
             }
-            ast::ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => {
-              unreachable!("ts should be transpiled")
-            }
+            ast::ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => false,
           }
         }
         ast::ModuleDeclaration::ExportNamedDeclaration(named_decl) => {
@@ -448,9 +447,7 @@ impl<'a> SideEffectDetector<'a> {
           }
         }
         ast::ModuleDeclaration::TSExportAssignment(_)
-        | ast::ModuleDeclaration::TSNamespaceExportDeclaration(_) => {
-          unreachable!("ts should be transpiled")
-        }
+        | ast::ModuleDeclaration::TSNamespaceExportDeclaration(_) => false,
       },
       Statement::BlockStatement(block) => self.detect_side_effect_of_block(block),
       Statement::DoWhileStatement(do_while) => {
@@ -1,23 +1,36 @@
+use std::path::Path;
+
 use minipack_common::{
-  EcmaRelated, EcmaView, EcmaViewMeta, ImportRecordIdx, ModuleIdx, ModuleType, RawImportRecord,
-  side_effects::DeterminedSideEffects,
+  EcmaRelated, EcmaView, EcmaViewMeta, ImportKind, ImportRecordIdx, ImportRecordMeta, ModuleIdx,
+  ModuleType, RawImportRecord, side_effects::DeterminedSideEffects,
 };
 use minipack_error::BuildResult;
+use minipack_fs::FileSystem;
+use minipack_utils::rstr::Rstr;
+use oxc::span::Span;
 use oxc_index::IndexVec;
+use rustc_hash::FxHashMap;
 
 use sugar_path::SugarPath;
 
 use crate::{
-  scan_stage::ast_scanner::{AstScanResult, AstScanner},
-  utils::parse_to_ecma_ast,
+  scan_stage::{
+    ast_scanner::{AstScanResult, AstScanner},
+    scan_cache::ScanCache,
+  },
+  utils::{match_import_glob, parse_to_ecma_ast, to_ecma_source},
 };
 
 pub struct CreateModuleContext<'a> {
+  pub id: &'a str,
   pub stable_id: &'a str,
   pub repr_name: &'a str,
   pub module_idx: ModuleIdx,
   pub module_type: ModuleType,
   pub warnings: &'a mut Vec<anyhow::Error>,
+  pub cache: Option<&'a ScanCache>,
+  pub fs: &'a dyn FileSystem,
+  pub dts: bool,
 }
 
 pub struct CreateEcmaViewReturn {
@@ -26,11 +39,23 @@ pub struct CreateEcmaViewReturn {
   pub raw_import_records: IndexVec<ImportRecordIdx, RawImportRecord>,
 }
 
-pub async fn create_ecma_view(
+pub fn create_ecma_view(
   ctx: &mut CreateModuleContext<'_>,
   source: String,
 ) -> BuildResult<CreateEcmaViewReturn> {
-  let (ast, scoping) = parse_to_ecma_ast(source, ctx.stable_id.as_path(), &ctx.module_type)?;
+  let source = to_ecma_source(source, &ctx.module_type);
+  let (ast, mut scan_result, dts) = match ctx.cache {
+    Some(cache) => cache.parse_and_scan(ctx, &source)?,
+    None => {
+      let (ast, scoping, dts) =
+        parse_to_ecma_ast(source.clone(), ctx.stable_id.as_path(), &ctx.module_type, ctx.dts)?;
+      let scan_result =
+        AstScanner::new(ctx.module_idx, scoping, ctx.repr_name).scan(ast.program())?;
+      (ast, scan_result, dts)
+    }
+  };
+
+  let import_globs = expand_import_globs(ctx, &mut scan_result);
 
   let AstScanResult {
     named_imports,
@@ -44,7 +69,12 @@ pub async fn create_ecma_view(
     errors,
     symbols,
     has_star_exports,
-  } = AstScanner::new(ctx.module_idx, scoping, ctx.repr_name).scan(ast.program())?;
+    has_import_meta_hot,
+    has_top_level_await,
+    hmr_self_accepting,
+    hmr_accepted_deps,
+    import_globs: _,
+  } = scan_result;
 
   if !errors.is_empty() {
     Err(errors)?;
@@ -52,7 +82,7 @@ pub async fn create_ecma_view(
 
   let has_side_effects = stmt_infos.iter().any(|stmt_info| stmt_info.side_effect);
   let ecma_view = EcmaView {
-    source: ast.source().clone(),
+    source,
     ecma_ast_idx: None,
     named_imports,
     named_exports,
@@ -61,10 +91,16 @@ pub async fn create_ecma_view(
     default_export_ref,
     namespace_object_ref,
     import_records: IndexVec::default(),
+    import_globs,
+    hmr_accepted_deps,
+    dts,
     side_effects: DeterminedSideEffects::Analyzed(has_side_effects),
     meta: {
       let mut meta = EcmaViewMeta::default();
       meta.set(EcmaViewMeta::HAS_STAR_EXPORT, has_star_exports);
+      meta.set(EcmaViewMeta::HAS_IMPORT_META_HOT, has_import_meta_hot);
+      meta.set(EcmaViewMeta::HMR_SELF_ACCEPTING, hmr_self_accepting);
+      meta.set(EcmaViewMeta::HAS_TOP_LEVEL_AWAIT, has_top_level_await);
       meta
     },
   };
@@ -76,3 +112,82 @@ pub async fn create_ecma_view(
     raw_import_records,
   })
 }
+
+/// Files matched by `import()` with a variable path become dynamic imports of the module. They are
+/// matched after scanning, so the scan cache stays valid when files are added or removed.
+fn expand_import_globs(
+  ctx: &mut CreateModuleContext<'_>,
+  scan_result: &mut AstScanResult,
+) -> FxHashMap<Span, Vec<ImportRecordIdx>> {
+  let mut import_globs = FxHashMap::default();
+  for glob in std::mem::take(&mut scan_result.import_globs) {
+    let Some(pattern) = glob.pattern else {
+      ctx.warnings.push(anyhow::anyhow!(
+        "`import()` with a variable path in {} can't be bundled and is left as-is, only relative paths with a static file name like `./locales/${{lang}}.js` are supported.",
+        ctx.stable_id
+      ));
+      continue;
+    };
+    let specifiers = match_import_glob(ctx.fs, Path::new(ctx.id), &pattern);
+    if specifiers.is_empty() {
+      ctx.warnings.push(anyhow::anyhow!(
+        "`import()` with a variable path in {} doesn't match any file with \"{pattern}\".",
+        ctx.stable_id
+      ));
+      continue;
+    }
+    let records = specifiers
+      .into_iter()
+      .map(|specifier| {
+        let namespace_ref =
+          scan_result.symbols.create_facade_root_symbol_ref("#LOCAL_NAMESPACE_IN_GLOB#");
+        let rec = RawImportRecord::new(
+          Rstr::from(specifier.as_str()),
+          ImportKind::DynamicImport,
+          namespace_ref,
+          glob.span,
+        )
+        .with_meta(ImportRecordMeta::IS_IMPORT_GLOB);
+        let rec_idx = scan_result.import_records.push(rec);
+        scan_result.stmt_infos.get_mut(glob.stmt_info_idx).import_records.push(rec_idx);
+        rec_idx
+      })
+      .collect();
+    import_globs.insert(glob.span, records);
+  }
+  import_globs
+}
+
+#[tokio::test]
+async fn test_import_glob() {
+  use minipack_common::{BundlerOptions, InputItem, OutputAssetKind};
+
+  let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/import_glob");
+  let mut bundler = crate::Bundler::new(BundlerOptions {
+    cwd: Some(cwd),
+    input: Some(vec![InputItem { name: None, import: "./main.js".to_string() }]),
+    ..BundlerOptions::default()
+  })
+  .unwrap();
+  let output = bundler.build(false).await.unwrap();
+  assert!(output.warnings.is_empty());
+
+  let main = output.assets.iter().find(|asset| asset.filename == "main.js").unwrap();
+  let mut chunks = output
+    .assets
+    .iter()
+    .filter(|asset| matches!(asset.kind, OutputAssetKind::Entry) && asset.filename != "main.js")
+    .map(|asset| asset.filename.as_str())
+    .collect::<Vec<_>>();
+  chunks.sort_unstable();
+  // `locales/README.md` doesn't match `./locales/*.js`.
+  assert_eq!(chunks.len(), 2);
+  for (lang, chunk) in ["en", "fr"].iter().zip(chunks) {
+    // Both the template literal and the concatenated path are bundled.
+    let key = format!("\"./locales/{lang}.js\": function() {{");
+    assert_eq!(main.content.matches(&key).count(), 2);
+    let import = format!("return import(\"./{chunk}\");");
+    assert_eq!(main.content.matches(&import).count(), 2);
+  }
+  assert!(main.content.contains("__glob({"));
+}
@@ -0,0 +1,22 @@
+use std::fmt::Write as _;
+
+use minipack_utils::ecmascript::{property_access_str, to_module_import_export_name};
+
+/// The source of a module reused by a hmr update. It re-exports the namespace object that the
+/// running build registered under the stable id of the module.
+pub fn hmr_reused_module_source(stable_id: &str, exports: &[String]) -> String {
+  let mut source = format!(
+    "var ns = globalThis.__minipack_hmr__.exports.get({});\n",
+    serde_json::to_string(stable_id).expect("string should be serializable")
+  );
+  for (index, name) in exports.iter().enumerate() {
+    let _ = writeln!(source, "var e{index} = {};", property_access_str("ns", name));
+  }
+  let specifiers = exports
+    .iter()
+    .enumerate()
+    .map(|(index, name)| format!("e{index} as {}", to_module_import_export_name(name)))
+    .collect::<Vec<_>>();
+  let _ = writeln!(source, "export {{ {} }};", specifiers.join(", "));
+  source
+}
@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use minipack_utils::html::{HtmlModuleScript, find_module_scripts};
+
+/// Inline `<script type="module">` of a html entry are loaded as virtual modules with ids like
+/// `/path/to/index.html?html-proxy&index=0.js`.
+pub const HTML_PROXY_QUERY: &str = "?html-proxy&index=";
+
+pub fn is_html_id(id: &str) -> bool {
+  Path::new(id).extension().is_some_and(|ext| ext == "html" || ext == "htm")
+}
+
+pub fn html_proxy_id(html_id: &str, index: usize) -> String {
+  format!("{html_id}{HTML_PROXY_QUERY}{index}.js")
+}
+
+/// Split a html proxy id into the id of the html file and the index of the inline script.
+pub fn parse_html_proxy_id(id: &str) -> Option<(&str, usize)> {
+  let (html_id, query) = id.split_once(HTML_PROXY_QUERY)?;
+  let index = query.strip_suffix(".js")?.parse::<usize>().ok()?;
+  Some((html_id, index))
+}
+
+/// Extract the source of the `index`-th inline module script of a html file.
+pub fn extract_inline_script(html: &str, html_id: &str, index: usize) -> anyhow::Result<String> {
+  find_module_scripts(html)
+    .into_iter()
+    .filter_map(|(_, script)| match script {
+      HtmlModuleScript::Inline(content) => Some(content.to_string()),
+      HtmlModuleScript::External(_) => None,
+    })
+    .nth(index)
+    .ok_or_else(|| anyhow::anyhow!("Inline script {index} is not found in {html_id}"))
+}
@@ -1 +1,3 @@
 pub mod ecmascript;
+pub mod hmr;
+pub mod html;
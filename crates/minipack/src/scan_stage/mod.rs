@@ -3,11 +3,13 @@ pub mod loaders;
 pub mod module_loader;
 
 mod module_task;
-mod runtime_module_task;
+pub mod runtime_module_task;
+mod scan_cache;
 
 use module_loader::{ModuleLoader, ModuleLoaderOutput};
 
 use arcstr::ArcStr;
+use minipack_common::ResolvedId;
 use minipack_error::BuildResult;
 use minipack_fs::OsFileSystem;
 
@@ -47,6 +49,11 @@ impl ScanStage {
           })?,
       );
     }
+    if let Some(stdin) = &self.options.stdin {
+      let id = stdin.id(&self.options.cwd);
+      user_defined_entries
+        .push((Some(stdin.name().into()), ResolvedId { id: id.into(), is_external: false }));
+    }
     module_loader.fetch_all_modules(user_defined_entries).await
   }
 }
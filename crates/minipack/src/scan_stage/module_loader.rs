@@ -1,26 +1,34 @@
 use std::collections::hash_map::Entry;
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 use arcstr::ArcStr;
 use minipack_common::{
-  EntryPoint, EntryPointKind, ExternalModule, ImportRecordIdx, ImporterRecord, Module, ModuleIdx,
-  ModuleLoaderMsg, NormalModuleTaskResult, RUNTIME_MODULE_ID, ResolvedId, RuntimeModuleBrief,
-  RuntimeModuleTaskResult, SymbolRefDb, SymbolRefDbForModule,
+  EntryPoint, EntryPointKind, ExternalModule, ImportRecordIdx, ImporterRecord, Module, ModuleId,
+  ModuleIdx, ModuleLoaderMsg, NormalModuleTaskResult, RUNTIME_MODULE_ID, ResolvedId,
+  RuntimeModuleBrief, RuntimeModuleTaskResult, SymbolRefDb, SymbolRefDbForModule, TypeOnlyModule,
 };
 use minipack_error::BuildResult;
-use minipack_fs::OsFileSystem;
+use minipack_fs::{FileSystem, OsFileSystem};
+use minipack_utils::html::{HtmlModuleScript, find_module_scripts, find_stylesheets};
 use minipack_utils::rstr::Rstr;
 use minipack_utils::rustc_hash::FxHashSetExt;
 use oxc::semantic::{ScopeId, Scoping};
 use oxc_index::IndexVec;
 use rustc_hash::{FxHashMap, FxHashSet};
+use sugar_path::SugarPath;
 use tokio::sync::mpsc::Receiver;
 
+use crate::types::html_entry::{HtmlEntry, HtmlStylesheet};
 use crate::types::{IndexEcmaAst, IndexModules, SharedOptions, SharedResolver};
 use crate::utils::ecmascript::legitimize_identifier_name;
+use crate::utils::generate_dts;
 
+use super::loaders::hmr::hmr_reused_module_source;
+use super::loaders::html::{html_proxy_id, is_html_id};
 use super::module_task::{ModuleTask, TaskContext};
 use super::runtime_module_task::RuntimeModuleTask;
+use super::scan_cache::ScanCache;
 
 pub struct IntermediateNormalModules {
   pub ecma_ast: IndexEcmaAst,
@@ -60,6 +68,9 @@ pub struct ModuleLoaderOutput {
   pub symbol_ref_db: SymbolRefDb,
   pub entry_points: Vec<EntryPoint>,
   pub runtime_module: RuntimeModuleBrief,
+  pub html_entries: Vec<HtmlEntry>,
+  /// Modules that are only imported for their types with the `dts` option.
+  pub type_only_modules: Vec<TypeOnlyModule>,
   pub warnings: Vec<anyhow::Error>,
 }
 
@@ -77,7 +88,26 @@ impl ModuleLoader {
     let symbol_ref_db = SymbolRefDb::default();
 
     let visited = FxHashMap::from_iter([(RUNTIME_MODULE_ID.into(), runtime_idx)]);
-    let shared_context = Arc::new(TaskContext { fs, resolver, options, tx: tx.clone() });
+    let cache = ScanCache::new(fs, &options);
+    let reused_modules = options.hmr_reused_modules.iter().map(|module| {
+      let stable_id = ModuleId::new(module.id.as_str()).stabilize(&options.cwd);
+      (module.id.as_str().into(), hmr_reused_module_source(&stable_id, &module.exports))
+    });
+    let virtual_modules = options
+      .stdin
+      .iter()
+      .map(|stdin| (stdin.id(&options.cwd).into(), stdin.contents.clone()))
+      .chain(reused_modules)
+      .collect();
+    let shared_context = Arc::new(TaskContext {
+      fs,
+      cache,
+      resolver,
+      options,
+      tx: tx.clone(),
+      resolve_cache: Mutex::default(),
+      virtual_modules,
+    });
 
     let task = RuntimeModuleTask::new(runtime_idx, tx.clone());
     tokio::spawn(async { task.run() });
@@ -98,7 +128,15 @@ impl ModuleLoader {
     let mut entry_points = Vec::with_capacity(entries_count);
     let mut user_defined_entry_ids = FxHashSet::with_capacity(entries_count);
 
+    let mut html_entries = vec![];
+
     for (name, resolved_id) in user_defined_entries.into_iter() {
+      if is_html_id(&resolved_id.id) {
+        let html_entry = self.load_html_entry(resolved_id.id, &mut entry_points)?;
+        user_defined_entry_ids.extend(html_entry.scripts.iter().map(|(_, idx)| *idx));
+        html_entries.push(html_entry);
+        continue;
+      }
       let idx = self.try_spawn_new_task(None, resolved_id, true);
       user_defined_entry_ids.insert(idx);
       entry_points.push(EntryPoint { idx, name, kind: EntryPointKind::UserDefined });
@@ -108,6 +146,7 @@ impl ModuleLoader {
     let mut runtime_module = None;
 
     let mut dynamic_import_entry_ids = user_defined_entry_ids.clone();
+    let mut worker_entry_ids = FxHashSet::default();
 
     while self.remaining > 0 {
       let Some(msg) = self.rx.recv().await else {
@@ -120,6 +159,7 @@ impl ModuleLoader {
             mut module,
             ecma_related,
             resolved_deps,
+            resolved_dts_deps,
             raw_import_records,
             warnings: task_result_warnings,
           } = *task_result;
@@ -131,7 +171,12 @@ impl ModuleLoader {
           for (import_record, resolved_id) in raw_import_records.into_iter().zip(resolved_deps) {
             let owner = normal_module.stable_id.as_str().into();
             let idx = self.try_spawn_new_task(Some(owner), resolved_id, false);
-            if import_record.kind.is_dynamic() && !dynamic_import_entry_ids.contains(&idx) {
+            // With `inline_dynamic_imports`, dynamically imported modules are bundled into the importer's chunk
+            // instead of becoming entries of their own.
+            if import_record.kind.is_dynamic()
+              && !self.shared_context.options.inline_dynamic_imports
+              && !dynamic_import_entry_ids.contains(&idx)
+            {
               dynamic_import_entry_ids.insert(idx);
               entry_points.push(EntryPoint {
                 idx,
@@ -139,6 +184,9 @@ impl ModuleLoader {
                 kind: EntryPointKind::DynamicImport,
               });
             }
+            if import_record.kind.is_worker() && worker_entry_ids.insert(idx) {
+              entry_points.push(EntryPoint { idx, name: None, kind: EntryPointKind::Worker });
+            }
 
             self.inm.importer_record[idx].push(ImporterRecord {
               kind: import_record.kind,
@@ -148,6 +196,13 @@ impl ModuleLoader {
             import_records.push(import_record.into_resolved(idx));
           }
 
+          if let Some(dts) = &mut normal_module.ecma_view.dts {
+            dts.deps = resolved_dts_deps
+              .into_iter()
+              .map(|resolved_id| resolved_id.map(|resolved_id| resolved_id.id))
+              .collect();
+          }
+
           warnings.extend(task_result_warnings);
           normal_module.import_records = import_records;
 
@@ -180,8 +235,18 @@ impl ModuleLoader {
       }
     }
 
-    let module_table = self.inm.module_table.into_iter().flatten().collect();
+    if let Some(cache) = &self.shared_context.cache {
+      cache.evict();
+    }
+
+    let module_table: IndexModules =
+      std::mem::take(&mut self.inm.module_table).into_iter().flatten().collect();
     let runtime_module = runtime_module.expect("Failed to find runtime module.");
+    let type_only_modules = if self.shared_context.options.dts {
+      self.load_type_only_modules(&module_table)?
+    } else {
+      vec![]
+    };
 
     Ok(ModuleLoaderOutput {
       entry_points,
@@ -189,10 +254,116 @@ impl ModuleLoader {
       runtime_module,
       ecma_ast: self.inm.ecma_ast,
       symbol_ref_db: self.symbol_ref_db,
+      html_entries,
+      type_only_modules,
       warnings,
     })
   }
 
+  /// Modules that are only imported for their types aren't loaded into the module graph, only their
+  /// declarations are generated. They are loaded after the graph is complete, when it's known which
+  /// of the imported modules are part of it.
+  fn load_type_only_modules(
+    &self,
+    module_table: &IndexModules,
+  ) -> BuildResult<Vec<TypeOnlyModule>> {
+    let fs: &dyn FileSystem = &self.shared_context.fs;
+    let mut queue = module_table
+      .iter()
+      .filter_map(|module| module.as_normal()?.dts.as_ref())
+      .flat_map(|dts| dts.deps.iter().flatten().cloned())
+      .collect::<Vec<_>>();
+    let mut visited = FxHashSet::default();
+    let mut type_only_modules = vec![];
+    while let Some(id) = queue.pop() {
+      if self.visited.contains_key(&id) || !visited.insert(id.clone()) {
+        continue;
+      }
+      let source = fs
+        .read_to_string(Path::new(id.as_str()))
+        .map_err(|err| anyhow::anyhow!("Could not load {id} - {err}."))?;
+      let stable_id = ModuleId::new(&id).stabilize(&self.shared_context.options.cwd);
+      let mut dts = generate_dts(source.into(), Path::new(&stable_id))?;
+      dts.deps = dts
+        .specifiers
+        .iter()
+        .map(|specifier| {
+          self.shared_context.resolve_dts_import(specifier, &id).map(|resolved_id| resolved_id.id)
+        })
+        .collect();
+      queue.extend(dts.deps.iter().flatten().cloned());
+      type_only_modules.push(TypeOnlyModule { id, stable_id, dts });
+    }
+    Ok(type_only_modules)
+  }
+
+  /// Spawn tasks for the `<script type="module">` tags of a html entry, each of them is a user-defined entry.
+  fn load_html_entry(
+    &mut self,
+    id: ArcStr,
+    entry_points: &mut Vec<EntryPoint>,
+  ) -> BuildResult<HtmlEntry> {
+    let fs: &dyn FileSystem = &self.shared_context.fs;
+    let source = fs
+      .read_to_string(Path::new(id.as_str()))
+      .map_err(|err| anyhow::anyhow!("Could not load {id} - {err}."))?;
+
+    let html_dir = Path::new(id.as_str()).parent().unwrap_or(Path::new(""));
+    let mut stylesheets = vec![];
+    for (href_range, href) in find_stylesheets(&source) {
+      // Remote stylesheets are left as they are.
+      if href.starts_with("//") || href.contains(':') {
+        continue;
+      }
+      let href = href.split(['?', '#']).next().unwrap_or_default();
+      let path = match href.strip_prefix('/') {
+        Some(href) => self.shared_context.options.cwd.join(href),
+        None => html_dir.join(href),
+      }
+      .normalize();
+      let content = fs
+        .read_to_string(&path)
+        .map_err(|err| anyhow::anyhow!("Could not load {href:?} in {id} - {err}."))?;
+      stylesheets.push(HtmlStylesheet {
+        href_range,
+        id: path.to_string_lossy().into_owned(),
+        content,
+      });
+    }
+
+    // Inline scripts are named after the html file, e.g. `index.js` for `index.html`.
+    let html_name = Path::new(id.as_str()).file_stem().map(|stem| stem.to_string_lossy().into());
+
+    let mut scripts = vec![];
+    let mut inline_script_count = 0;
+    for (range, script) in find_module_scripts(&source) {
+      let (name, resolved_id) = match script {
+        HtmlModuleScript::External(src) => {
+          // `/main.js` is relative to the project root rather than the root of the file system.
+          let resolved_id = match src.strip_prefix('/') {
+            Some(src) => self.shared_context.resolver.resolve_id(&format!("./{src}"), None, true),
+            None => self.shared_context.resolver.resolve_id(src, Some(&id), false),
+          }
+          .map_err(|e| anyhow::anyhow!("Failed to resolve {src:?} in {id} - {:?}", e.0))?;
+          if resolved_id.is_external {
+            Err(anyhow::anyhow!("Failed to resolve {src:?} in {id} - entry can't be external"))?;
+          }
+          (None, resolved_id)
+        }
+        HtmlModuleScript::Inline(_) => {
+          let proxy_id = html_proxy_id(&id, inline_script_count);
+          inline_script_count += 1;
+          (html_name.clone(), ResolvedId { id: proxy_id.into(), is_external: false })
+        }
+      };
+      let idx = self.try_spawn_new_task(Some(id.as_str().into()), resolved_id, true);
+      entry_points.push(EntryPoint { idx, name, kind: EntryPointKind::UserDefined });
+      scripts.push((range, idx));
+    }
+
+    Ok(HtmlEntry { id, source, scripts, stylesheets })
+  }
+
   fn try_spawn_new_task(
     &mut self,
     owner: Option<Rstr>,
@@ -1,13 +1,23 @@
-use std::{path::Path, sync::Arc};
+use std::{
+  panic::{self, AssertUnwindSafe},
+  path::Path,
+  sync::{Arc, Mutex},
+};
 
+use arcstr::ArcStr;
 use minipack_common::{
   Module, ModuleId, ModuleIdx, ModuleLoaderMsg, ModuleType, NormalModule, NormalModuleTaskResult,
   RUNTIME_MODULE_ID, ResolvedId,
 };
 use minipack_error::BuildResult;
 use minipack_fs::{FileSystem, OsFileSystem};
-use minipack_utils::{path_ext::PathExt, rstr::Rstr};
+use minipack_utils::{
+  path_ext::PathExt,
+  rayon::{self, IntoParallelRefIterator, ParallelIterator},
+  rstr::Rstr,
+};
 use oxc_index::IndexVec;
+use rustc_hash::FxHashMap;
 use tokio::sync::mpsc::Sender;
 
 use crate::{
@@ -15,13 +25,53 @@ use crate::{
   utils::ecmascript::legitimize_identifier_name,
 };
 
-use super::loaders::ecmascript::{CreateEcmaViewReturn, CreateModuleContext, create_ecma_view};
+use super::{
+  loaders::{
+    ecmascript::{CreateEcmaViewReturn, CreateModuleContext, create_ecma_view},
+    html::{extract_inline_script, parse_html_proxy_id},
+  },
+  scan_cache::ScanCache,
+};
 
 pub struct TaskContext {
   pub fs: OsFileSystem,
+  pub cache: Option<ScanCache>,
   pub options: SharedOptions,
   pub resolver: SharedResolver,
   pub tx: Sender<ModuleLoaderMsg>,
+  /// Resolved ids keyed by `(directory of the importer, specifier)`, sibling modules usually share
+  /// most of their imports.
+  pub resolve_cache: Mutex<FxHashMap<(ArcStr, ArcStr), ResolvedId>>,
+  /// Sources of modules that don't exist on disk, e.g. the entry read from stdin.
+  pub virtual_modules: FxHashMap<ArcStr, String>,
+}
+
+impl TaskContext {
+  pub fn resolve_import(&self, specifier: &str, importer: &str) -> BuildResult<ResolvedId> {
+    if specifier == RUNTIME_MODULE_ID {
+      return Ok(ResolvedId { id: specifier.into(), is_external: false });
+    }
+
+    let dir = Path::new(importer).parent().map(|dir| dir.to_string_lossy()).unwrap_or_default();
+    let key = (ArcStr::from(dir.as_ref()), ArcStr::from(specifier));
+    if let Some(resolved_id) = self.resolve_cache.lock().unwrap().get(&key) {
+      return Ok(resolved_id.clone());
+    }
+
+    let resolved_id = self.resolver.resolve_id(specifier, Some(importer), false)?;
+    self.resolve_cache.lock().unwrap().insert(key, resolved_id.clone());
+    Ok(resolved_id)
+  }
+
+  /// Declarations are only bundled from the TypeScript modules of the project, anything else is
+  /// kept as an external import, including specifiers that can't be resolved, e.g. `@types` packages.
+  pub fn resolve_dts_import(&self, specifier: &str, importer: &str) -> Option<ResolvedId> {
+    let resolved_id = self.resolve_import(specifier, importer).ok()?;
+    let is_bundled = !resolved_id.is_external
+      && !resolved_id.id.contains("/node_modules/")
+      && [".ts", ".mts", ".cts"].iter().any(|ext| resolved_id.id.ends_with(ext));
+    is_bundled.then_some(resolved_id)
+  }
 }
 
 pub struct ModuleTask {
@@ -50,43 +100,77 @@ impl ModuleTask {
   }
 
   async fn run_inner(&mut self) -> BuildResult<()> {
-    let (source, module_type) = self.load_source().map_err(|err| {
-      anyhow::anyhow!(
-        "Could not load {}{} - {}.",
-        self.resolved_id.id,
-        self.owner.as_ref().map(|owner| format!(" (imported by {})", owner)).unwrap_or_default(),
-        err,
-      )
-    })?;
-
-    let mut warnings = vec![];
-
     let id = ModuleId::new(&self.resolved_id.id);
     let stable_id = id.stabilize(&self.ctx.options.cwd);
     let repr_name = Path::new(self.resolved_id.id.as_str()).representative_file_name();
     let repr_name = legitimize_identifier_name(&repr_name).into_owned();
-    let CreateEcmaViewReturn { ecma_view, ecma_related, raw_import_records } = create_ecma_view(
-      &mut CreateModuleContext {
-        module_idx: self.idx,
-        repr_name: &repr_name,
-        stable_id: &stable_id,
-        module_type: module_type.clone(),
-        warnings: &mut warnings,
-      },
-      source,
-    )
-    .await?;
-
-    let resolved_deps = raw_import_records
-      .iter()
-      .map(|import_record| {
-        let specifier = import_record.specifier.as_str();
-        if specifier == RUNTIME_MODULE_ID {
-          return Ok(ResolvedId { id: specifier.into(), is_external: false });
-        }
-        self.ctx.resolver.resolve_id(specifier, Some(&self.resolved_id.id), false)
-      })
-      .collect::<BuildResult<IndexVec<_, _>>>()?;
+
+    // Reading, parsing, scanning and resolving are blocking or CPU-bound, they run on the rayon pool
+    // so they don't block the threads of the async runtime.
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let ctx = Arc::clone(&self.ctx);
+    let (idx, importer, owner) = (self.idx, self.resolved_id.id.clone(), self.owner.clone());
+    let task_stable_id = stable_id.clone();
+    rayon::spawn(move || {
+      // A panic would abort the whole process on the rayon pool, it fails the build instead.
+      let ret = panic::catch_unwind(AssertUnwindSafe(|| {
+        let (source, module_type) = load_source(&ctx, &importer).map_err(|err| {
+          anyhow::anyhow!(
+            "Could not load {importer}{} - {err}.",
+            owner.as_ref().map(|owner| format!(" (imported by {owner})")).unwrap_or_default(),
+          )
+        })?;
+        let mut warnings = vec![];
+        create_ecma_view(
+          &mut CreateModuleContext {
+            id: &importer,
+            module_idx: idx,
+            repr_name: &repr_name,
+            stable_id: &task_stable_id,
+            module_type: module_type.clone(),
+            warnings: &mut warnings,
+            cache: ctx.cache.as_ref(),
+            fs: &ctx.fs,
+            dts: ctx.options.dts,
+          },
+          source,
+        )
+        .and_then(|ret| {
+          let resolved_deps = ret
+            .raw_import_records
+            .par_iter()
+            .map(|import_record| ctx.resolve_import(import_record.specifier.as_str(), &importer))
+            .collect::<BuildResult<Vec<_>>>()?;
+          let resolved_dts_deps = ret.ecma_view.dts.as_ref().map_or(vec![], |dts| {
+            dts
+              .specifiers
+              .par_iter()
+              .map(|specifier| ctx.resolve_dts_import(specifier, &importer))
+              .collect()
+          });
+          Ok((ret, IndexVec::from_vec(resolved_deps), resolved_dts_deps))
+        })
+        .map(|ret| (ret, module_type, warnings))
+      }))
+      .unwrap_or_else(|payload| {
+        let message = payload
+          .downcast_ref::<&str>()
+          .map(ToString::to_string)
+          .or_else(|| payload.downcast_ref::<String>().cloned())
+          .unwrap_or_default();
+        Err(anyhow::anyhow!("Panicked while scanning {importer}: {message}"))?
+      });
+      let _ = tx.send(ret);
+    });
+    let (
+      (
+        CreateEcmaViewReturn { ecma_view, ecma_related, raw_import_records },
+        resolved_deps,
+        resolved_dts_deps,
+      ),
+      module_type,
+      warnings,
+    ) = rx.await.map_err(|_| anyhow::anyhow!("Failed to scan {}.", self.resolved_id.id))??;
 
     let result = ModuleLoaderMsg::NormalModuleDone(Box::new(NormalModuleTaskResult {
       module: Module::Normal(Box::new(NormalModule {
@@ -95,11 +179,12 @@ impl ModuleTask {
         stable_id,
         ecma_view,
         exec_order: u32::MAX,
-        module_type: module_type.clone(),
+        module_type,
         is_user_defined_entry: self.is_user_defined_entry,
       })),
       ecma_related: Some(ecma_related),
       resolved_deps,
+      resolved_dts_deps,
       raw_import_records,
       warnings,
     }));
@@ -108,18 +193,26 @@ impl ModuleTask {
 
     Ok(())
   }
+}
 
-  pub fn load_source(&self) -> anyhow::Result<(String, ModuleType)> {
-    let fs: &dyn FileSystem = &self.ctx.fs;
-    let id = &self.resolved_id.id;
-
-    let content = fs.read_to_string(Path::new(id.as_str()))?;
-    let final_type = match id.rsplit('.').next().filter(|ext| ext != id) {
-      Some("js" | "cjs" | "mjs") => ModuleType::Js,
-      Some("ts" | "cts" | "mts") => ModuleType::Ts,
-      _ => ModuleType::Js,
-    };
+fn load_source(ctx: &TaskContext, id: &str) -> anyhow::Result<(String, ModuleType)> {
+  let fs: &dyn FileSystem = &ctx.fs;
 
-    Ok((content, final_type))
+  if let Some((html_id, index)) = parse_html_proxy_id(id) {
+    let html = fs.read_to_string(Path::new(html_id))?;
+    return Ok((extract_inline_script(&html, html_id, index)?, ModuleType::Js));
   }
+
+  let content = match ctx.virtual_modules.get(id) {
+    Some(content) => content.clone(),
+    None => fs.read_to_string(Path::new(id))?,
+  };
+  let final_type = match id.rsplit('.').next().filter(|ext| *ext != id) {
+    Some("js" | "cjs" | "mjs") => ModuleType::Js,
+    Some("ts" | "cts" | "mts") => ModuleType::Ts,
+    Some("html" | "htm") => ModuleType::Html,
+    _ => ModuleType::Js,
+  };
+
+  Ok((content, final_type))
 }
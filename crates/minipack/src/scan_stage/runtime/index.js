@@ -44,3 +44,62 @@ var __toESM = (mod, isNodeMode, target) => (
 );
 var __toCommonJS = mod =>
   __copyProps(__defProp({}, '__esModule', { value: true }), mod);
+var __hot = id => {
+  var hmr = (globalThis.__minipack_hmr__ ??= { modules: new Map(), data: new Map() });
+  var hot = hmr.modules.get(id);
+  if (!hot) {
+    if (!hmr.data.has(id)) hmr.data.set(id, {});
+    hot = {
+      data: hmr.data.get(id),
+      acceptCallbacks: [],
+      disposeCallbacks: [],
+      accept(deps, callback) {
+        if (typeof deps === 'function' || !deps) {
+          hot.acceptCallbacks.push({ deps: [id], self: true, callback: deps });
+        } else {
+          hot.acceptCallbacks.push({
+            deps: Array.isArray(deps) ? deps : [deps],
+            self: false,
+            isArray: Array.isArray(deps),
+            callback,
+          });
+        }
+      },
+      dispose(callback) {
+        hot.disposeCallbacks.push(callback);
+      },
+      invalidate() {
+        hmr.reload ? hmr.reload() : location.reload();
+      },
+    };
+    hmr.modules.set(id, hot);
+  }
+  return hot;
+};
+var __hmrRegister = (id, ns) => {
+  var hmr = (globalThis.__minipack_hmr__ ??= { modules: new Map(), data: new Map() });
+  (hmr.exports ??= new Map()).set(id, ns);
+};
+var __glob = (map, path) => {
+  var fn = __hasOwnProp.call(map, path) && map[path];
+  return fn
+    ? fn()
+    : Promise.reject(new Error('Unknown variable dynamic import: ' + path));
+};
+var __preload = (load, deps, base) => {
+  var urls = deps.map(dep => new URL(dep, base).href);
+  if (typeof document === 'undefined') {
+    return Promise.all(urls.map(url => import(url))).then(load, load);
+  }
+  var preloaded = __preload.urls || (__preload.urls = new Set());
+  urls.forEach(url => {
+    if (preloaded.has(url)) return;
+    preloaded.add(url);
+    var link = document.createElement('link');
+    link.rel = 'modulepreload';
+    link.href = url;
+    document.head.appendChild(link);
+  });
+  return load();
+};
+var __esm = (fn, res) => () => (fn && (res = fn(fn = 0)), res);
@@ -5,6 +5,7 @@ use oxc::ast_visit::VisitMut;
 use oxc::semantic::SemanticBuilder;
 use oxc::span::SourceType;
 use oxc_index::IndexVec;
+use rustc_hash::FxHashMap;
 use tokio::sync::mpsc::Sender;
 
 use minipack_common::{
@@ -16,6 +17,8 @@ use minipack_ecmascript::{EcmaAst, EcmaCompiler};
 
 use super::ast_scanner::{AstScanResult, AstScanner, PreProcessor};
 
+pub const RUNTIME_SOURCE: &str = include_str!("./runtime/index.js");
+
 pub struct RuntimeModuleTask {
   idx: ModuleIdx,
   tx: Sender<ModuleLoaderMsg>,
@@ -33,7 +36,7 @@ impl RuntimeModuleTask {
   }
 
   fn run_inner(&mut self) -> BuildResult<()> {
-    let source = arcstr::literal!(include_str!("./runtime/index.js"));
+    let source = arcstr::literal!(RUNTIME_SOURCE);
     let (ast, scan_result) = self.make_ecma_ast(&source)?;
 
     let AstScanResult {
@@ -65,7 +68,10 @@ impl RuntimeModuleTask {
         ecma_ast_idx: None,
         meta: EcmaViewMeta::empty(),
         import_records: IndexVec::default(),
+        import_globs: FxHashMap::default(),
+        hmr_accepted_deps: vec![],
         side_effects: DeterminedSideEffects::Analyzed(false),
+        dts: None,
       },
     };
 
@@ -0,0 +1,562 @@
+use std::{path::PathBuf, sync::Mutex};
+
+use arcstr::ArcStr;
+use minipack_common::{ModuleDts, ModuleType, NormalizedBundlerOptions};
+use minipack_ecmascript::{EcmaAst, EcmaCompiler};
+use minipack_error::BuildResult;
+use minipack_fs::{FileSystem, OsFileSystem};
+use oxc::{
+  allocator::Vec as ArenaVec,
+  ast::{
+    AstKind, AstType,
+    ast::{Comment, CommentContent, CommentKind, CommentNewlines, CommentPosition, Program},
+  },
+  ast_visit::{Visit, VisitMut},
+  codegen::{Codegen, CodegenOptions, LegalComment},
+  semantic::{Scoping, SemanticBuilder},
+  span::Span,
+};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use sugar_path::SugarPath;
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::utils::{ecma_source_type, parse_to_ecma_ast};
+
+use super::{
+  ast_scanner::{AstScanResult, AstScanner},
+  loaders::ecmascript::CreateModuleContext,
+};
+
+/// Entries that no build has used for this many builds are deleted.
+const MAX_IDLE_BUILDS: u32 = 10;
+
+const INDEX_FILENAME: &str = "index";
+
+/// A persistent cache of parsed and transformed modules, keyed by their source, everything else the
+/// transforming depends on and the build of minipack.
+///
+/// The ast can't be serialized, so the transformed ast is stored as the code printed from it, with
+/// what printing loses: the spans of its nodes in the original source and its comments. A hit only
+/// parses that code and puts them back, the transformer, the dead code elimination and the extra
+/// semantic analyses are skipped. The `Scoping` and the scan result are built from the restored ast
+/// like on a miss, so their ids always match it.
+pub struct ScanCache {
+  fs: OsFileSystem,
+  dir: PathBuf,
+  /// Keys of the entries read or written by this build, they are kept by `evict`.
+  used_keys: Mutex<Vec<String>>,
+}
+
+impl ScanCache {
+  pub fn new(fs: OsFileSystem, options: &NormalizedBundlerOptions) -> Option<Self> {
+    let dir = options.cache_dir.clone()?;
+    Some(Self { fs, dir, used_keys: Mutex::default() })
+  }
+
+  pub fn parse_and_scan(
+    &self,
+    ctx: &CreateModuleContext<'_>,
+    source: &ArcStr,
+  ) -> BuildResult<(EcmaAst, AstScanResult, Option<ModuleDts>)> {
+    let key = Self::key(ctx, source);
+    let (ast, scoping, dts) = match self.read(&key, source, &ctx.module_type) {
+      Some(restored) => restored,
+      None => {
+        let (ast, scoping, dts) =
+          parse_to_ecma_ast(source.clone(), ctx.stable_id.as_path(), &ctx.module_type, ctx.dts)?;
+        self.write(key, &CachedModule::new(ast.program(), dts.as_ref()));
+        (ast, scoping, dts)
+      }
+    };
+
+    let scan_result =
+      AstScanner::new(ctx.module_idx, scoping, ctx.repr_name).scan(ast.program())?;
+    Ok((ast, scan_result, dts))
+  }
+
+  /// Restore the ast of an entry. Entries whose code isn't parsed into the ast it was printed from
+  /// are missed, so they are written again, which is harmless.
+  fn read(
+    &self,
+    key: &str,
+    source: &ArcStr,
+    module_type: &ModuleType,
+  ) -> Option<(EcmaAst, Scoping, Option<ModuleDts>)> {
+    let bytes = self.fs.read(&self.dir.join(key)).ok()?;
+    let CachedModule { code, node_types, spans, comments, dts } =
+      bincode::deserialize(&bytes).ok()?;
+
+    let mut ast =
+      EcmaCompiler::parse_printed(source.clone(), &code, ecma_source_type(module_type)).ok()?;
+    let scoping = ast.program.with_mut(|fields| {
+      let mut restore =
+        RestoreSpans { node_types: node_types.iter(), spans: spans.iter(), matches: true };
+      restore.visit_program(fields.program);
+      if !restore.matches || restore.node_types.next().is_some() || restore.spans.next().is_some() {
+        return None;
+      }
+      fields.program.comments =
+        ArenaVec::from_iter_in(comments.iter().map(CachedComment::to_comment), fields.allocator);
+      Some(SemanticBuilder::new().build(fields.program).semantic.into_scoping())
+    })?;
+
+    self.used_keys.lock().unwrap().push(key.to_string());
+    let dts = dts.map(|(code, specifiers)| ModuleDts { code, specifiers, deps: vec![] });
+    Some((ast, scoping, dts))
+  }
+
+  fn write(&self, key: String, cached: &CachedModule) {
+    let Ok(bytes) = bincode::serialize(cached) else { return };
+    // Entries are renamed into place, so other builds never read a partially written one.
+    // Failing to write the cache should never fail the build.
+    let path = self.dir.join(&key);
+    let tmp_path = self.dir.join(format!("{key}.{}.tmp", std::process::id()));
+    let written = self
+      .fs
+      .create_dir_all(&self.dir)
+      .and_then(|()| self.fs.write(&tmp_path, &bytes))
+      .and_then(|()| self.fs.rename(&tmp_path, &path));
+    if written.is_ok() {
+      self.used_keys.lock().unwrap().push(key);
+    }
+  }
+
+  /// Delete the entries that weren't used by the last `MAX_IDLE_BUILDS` builds, e.g. the ones of
+  /// modules that have changed since, or of a previous version of minipack. The last build that
+  /// used each entry is stored in the index file of the cache.
+  pub fn evict(&self) {
+    let index_path = self.dir.join(INDEX_FILENAME);
+    let mut index = self
+      .fs
+      .read(&index_path)
+      .ok()
+      .and_then(|bytes| bincode::deserialize::<CacheIndex>(&bytes).ok())
+      .unwrap_or_default();
+    index.builds += 1;
+    let build = index.builds;
+
+    let Ok(paths) = self.fs.read_dir(&self.dir) else { return };
+    let mut last_used = FxHashMap::default();
+    for path in paths {
+      let Some(name) = path.file_name().and_then(|name| name.to_str()) else { continue };
+      if name != INDEX_FILENAME {
+        // Files the index doesn't know about, e.g. entries written by another build running at the
+        // same time, are kept as long as the ones written by this build.
+        last_used.insert(name.to_string(), index.last_used.get(name).copied().unwrap_or(build));
+      }
+    }
+    for key in self.used_keys.lock().unwrap().drain(..) {
+      last_used.insert(key, build);
+    }
+    // Entries that can't be deleted stay in the index, deleting them is tried again by the next build.
+    last_used.retain(|name, last_used| {
+      build.saturating_sub(*last_used) < MAX_IDLE_BUILDS
+        || self.fs.remove_file(&self.dir.join(name)).is_err()
+    });
+    index.last_used = last_used;
+
+    if let Ok(bytes) = bincode::serialize(&index) {
+      let tmp_path = self.dir.join(format!("{INDEX_FILENAME}.{}.tmp", std::process::id()));
+      let _ =
+        self.fs.write(&tmp_path, &bytes).and_then(|()| self.fs.rename(&tmp_path, &index_path));
+    }
+  }
+
+  /// Everything the parsing and transforming depends on is part of the key. The context is
+  /// destructured so that a new field has to be hashed here or explicitly ignored.
+  fn key(ctx: &CreateModuleContext<'_>, source: &str) -> String {
+    let CreateModuleContext {
+      // Only used to expand import globs, which happens after the cache.
+      id: _,
+      stable_id,
+      // Only used by the scanner, which runs on a hit too.
+      repr_name: _,
+      module_idx: _,
+      module_type,
+      warnings: _,
+      cache: _,
+      fs: _,
+      dts,
+    } = ctx;
+
+    let mut hasher = Xxh3::new();
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    hasher.update(env!("MINIPACK_SCAN_FINGERPRINT").as_bytes());
+    hasher.update(&stable_id.len().to_le_bytes());
+    hasher.update(stable_id.as_bytes());
+    hasher.update(&[u8::from(*dts)]);
+    hasher.update(&[match module_type {
+      ModuleType::Js => 0,
+      ModuleType::Ts => 1,
+      ModuleType::Html => 2,
+      ModuleType::Empty => 3,
+    }]);
+    hasher.update(source.as_bytes());
+    format!("{:032x}", hasher.digest128())
+  }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct CacheIndex {
+  builds: u32,
+  /// The last build that used each entry.
+  last_used: FxHashMap<String, u32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedModule {
+  /// The transformed ast printed without comments. Pure annotations are kept, they are flags of
+  /// the nodes that the parser sets.
+  code: String,
+  /// `AstType` of each node in visiting order, to tell whether the code is parsed into the same ast.
+  node_types: Vec<u8>,
+  /// Every span of the ast in visiting order.
+  spans: Vec<(u32, u32)>,
+  comments: Vec<CachedComment>,
+  /// Code and specifiers of the declarations with the `dts` option.
+  dts: Option<(String, Vec<String>)>,
+}
+
+impl CachedModule {
+  fn new(program: &Program, dts: Option<&ModuleDts>) -> Self {
+    let options = CodegenOptions {
+      comments: false,
+      annotation_comments: true,
+      legal_comments: LegalComment::None,
+      ..CodegenOptions::default()
+    };
+    let mut nodes = CollectNodes::default();
+    nodes.visit_program(program);
+    Self {
+      code: Codegen::new().with_options(options).build(program).code,
+      node_types: nodes.node_types,
+      spans: nodes.spans,
+      comments: program.comments.iter().map(CachedComment::new).collect(),
+      dts: dts.map(|dts| (dts.code.clone(), dts.specifiers.clone())),
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedComment {
+  span: (u32, u32),
+  attached_to: u32,
+  kind: u8,
+  position: u8,
+  newlines: u8,
+  content: u8,
+}
+
+impl CachedComment {
+  fn new(comment: &Comment) -> Self {
+    Self {
+      span: (comment.span.start, comment.span.end),
+      attached_to: comment.attached_to,
+      kind: comment.kind as u8,
+      position: comment.position as u8,
+      newlines: comment.newlines.bits(),
+      content: comment.content as u8,
+    }
+  }
+
+  fn to_comment(&self) -> Comment {
+    let (start, end) = self.span;
+    Comment {
+      span: Span::new(start, end),
+      attached_to: self.attached_to,
+      kind: if self.kind == CommentKind::Block as u8 {
+        CommentKind::Block
+      } else {
+        CommentKind::Line
+      },
+      position: if self.position == CommentPosition::Trailing as u8 {
+        CommentPosition::Trailing
+      } else {
+        CommentPosition::Leading
+      },
+      newlines: CommentNewlines::from_bits_retain(self.newlines),
+      content: [
+        CommentContent::Legal,
+        CommentContent::Jsdoc,
+        CommentContent::JsdocLegal,
+        CommentContent::Pure,
+        CommentContent::NoSideEffects,
+        CommentContent::Webpack,
+        CommentContent::Vite,
+        CommentContent::CoverageIgnore,
+      ]
+      .into_iter()
+      .find(|content| *content as u8 == self.content)
+      .unwrap_or(CommentContent::None),
+    }
+  }
+}
+
+#[derive(Default)]
+struct CollectNodes {
+  node_types: Vec<u8>,
+  spans: Vec<(u32, u32)>,
+}
+
+impl<'a> Visit<'a> for CollectNodes {
+  fn enter_node(&mut self, kind: AstKind<'a>) {
+    self.node_types.push(kind.ty() as u8);
+  }
+
+  fn visit_span(&mut self, span: &Span) {
+    self.spans.push((span.start, span.end));
+  }
+}
+
+/// Puts the spans of the cached ast back in the same order they were collected by `CollectNodes`,
+/// as long as the nodes are the same.
+struct RestoreSpans<'c> {
+  node_types: std::slice::Iter<'c, u8>,
+  spans: std::slice::Iter<'c, (u32, u32)>,
+  matches: bool,
+}
+
+impl VisitMut<'_> for RestoreSpans<'_> {
+  fn enter_node(&mut self, ty: AstType) {
+    self.matches &= self.node_types.next() == Some(&(ty as u8));
+  }
+
+  fn visit_span(&mut self, span: &mut Span) {
+    match self.spans.next() {
+      Some(&(start, end)) => *span = Span::new(start, end),
+      None => self.matches = false,
+    }
+  }
+}
+
+#[test]
+fn test_scan_cache_hit() {
+  use minipack_common::ModuleIdx;
+  use oxc::{ast::ast::IdentifierReference, semantic::ReferenceId};
+
+  let dir = std::env::temp_dir().join(format!("minipack-scan-cache-hit-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+  let cache = ScanCache { fs: OsFileSystem, dir: dir.clone(), used_keys: Mutex::default() };
+  let source = ArcStr::from(
+    "/*! legal */\nimport { a } from './a';\n// The answer.\nexport const answer: number = (a + 1) * 2;\nclass Counter<T> {\n  count = 0;\n  add(x: T) {\n    return `${x}${this.count++}`;\n  }\n}\nfunction f(x) {\n  let y = x;\n  return y + answer;\n}\nconst counter = /* @__PURE__ */ new Counter();\nconsole.log(f(1), counter, import(/* @vite-ignore */ './b'));\n",
+  );
+  let mut warnings = vec![];
+  let ctx = CreateModuleContext {
+    id: "/app/main.ts",
+    stable_id: "main.ts",
+    repr_name: "main",
+    module_idx: ModuleIdx::from_usize(1),
+    module_type: ModuleType::Ts,
+    warnings: &mut warnings,
+    cache: Some(&cache),
+    fs: &OsFileSystem,
+    dts: false,
+  };
+  let key = ScanCache::key(&ctx, &source);
+
+  assert!(cache.read(&key, &source, &ModuleType::Ts).is_none());
+  let (ast, missed, _) = cache.parse_and_scan(&ctx, &source).unwrap();
+  let (restored_ast, _, _) =
+    cache.read(&key, &source, &ModuleType::Ts).expect("the printed code should be restored");
+  let (_, hit, _) = cache.parse_and_scan(&ctx, &source).unwrap();
+
+  // The restored ast has the spans and comments of the transformed one, so it's printed the same.
+  let (mut nodes, mut restored_nodes) = (CollectNodes::default(), CollectNodes::default());
+  nodes.visit_program(ast.program());
+  restored_nodes.visit_program(restored_ast.program());
+  assert_eq!(restored_nodes.node_types, nodes.node_types);
+  assert_eq!(restored_nodes.spans, nodes.spans);
+  assert_eq!(restored_ast.program().comments, ast.program().comments);
+  assert_eq!(restored_ast.program().source_text, &*source);
+  assert_eq!(EcmaCompiler::print(&restored_ast).code, EcmaCompiler::print(&ast).code);
+  assert!(EcmaCompiler::print(&restored_ast).code.contains("/* @__PURE__ */ new Counter()"));
+
+  // Ids are the same as the ones of the transformed ast.
+  let (restored, missed_scoping) = (&hit.symbols.ast_scopes, &missed.symbols.ast_scopes);
+  assert_eq!(
+    restored.symbol_names().collect::<Vec<_>>(),
+    missed_scoping.symbol_names().collect::<Vec<_>>()
+  );
+  assert_eq!(restored.scopes_len(), missed_scoping.scopes_len());
+  let references = |program: &Program| {
+    #[derive(Default)]
+    struct References(Vec<ReferenceId>);
+    impl<'a> Visit<'a> for References {
+      fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+        self.0.extend(ident.reference_id.get());
+      }
+    }
+    let mut references = References::default();
+    references.visit_program(program);
+    references.0
+  };
+  let reference_ids = references(ast.program());
+  assert_eq!(references(restored_ast.program()), reference_ids);
+  for id in reference_ids {
+    assert_eq!(restored.symbol_id_for(id), missed_scoping.symbol_id_for(id));
+  }
+  let export = &hit.named_exports[&minipack_utils::rstr::Rstr::new("answer")];
+  assert_eq!(export.span, missed.named_exports[&minipack_utils::rstr::Rstr::new("answer")].span);
+  assert_eq!(&source[export.span.start as usize..export.span.end as usize], "answer: number");
+
+  let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_scan_cache_invalidation() {
+  use minipack_common::ModuleIdx;
+
+  let dir =
+    std::env::temp_dir().join(format!("minipack-scan-cache-invalidation-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+  let cache = ScanCache { fs: OsFileSystem, dir: dir.clone(), used_keys: Mutex::default() };
+  let is_hit = |source: &str, stable_id: &str, dts: bool| {
+    let source = ArcStr::from(source);
+    let mut warnings = vec![];
+    let ctx = CreateModuleContext {
+      id: "/app/main.ts",
+      stable_id,
+      repr_name: "main",
+      module_idx: ModuleIdx::from_usize(1),
+      module_type: ModuleType::Ts,
+      warnings: &mut warnings,
+      cache: Some(&cache),
+      fs: &OsFileSystem,
+      dts,
+    };
+    let is_hit = cache.read(&ScanCache::key(&ctx, &source), &source, &ModuleType::Ts).is_some();
+    cache.parse_and_scan(&ctx, &source).unwrap();
+    is_hit
+  };
+
+  assert!(!is_hit("export const a = 1;", "main.ts", false));
+  assert!(is_hit("export const a = 1;", "main.ts", false));
+  assert!(!is_hit("export const a = 2;", "main.ts", false), "the source has changed");
+  assert!(!is_hit("export const a = 1;", "src/main.ts", false), "the stable id has changed");
+  assert!(!is_hit("export const a = 1;", "main.ts", true), "declarations are generated");
+  assert!(is_hit("export const a = 1;", "main.ts", true));
+
+  let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_scan_cache_eviction() {
+  use minipack_common::ModuleIdx;
+
+  let dir =
+    std::env::temp_dir().join(format!("minipack-scan-cache-eviction-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+  let cache = ScanCache { fs: OsFileSystem, dir: dir.clone(), used_keys: Mutex::default() };
+  let scan = |source: &str| {
+    let mut warnings = vec![];
+    let ctx = CreateModuleContext {
+      id: "/app/main.js",
+      stable_id: "main.js",
+      repr_name: "main",
+      module_idx: ModuleIdx::from_usize(1),
+      module_type: ModuleType::Js,
+      warnings: &mut warnings,
+      cache: Some(&cache),
+      fs: &OsFileSystem,
+      dts: false,
+    };
+    cache.parse_and_scan(&ctx, &ArcStr::from(source)).unwrap();
+  };
+  let entries = || std::fs::read_dir(&dir).unwrap().count() - /* index */ 1;
+
+  scan("export const a = 1;");
+  cache.evict();
+  for _ in 1..MAX_IDLE_BUILDS {
+    scan("export const a = 2;");
+    cache.evict();
+  }
+  assert_eq!(entries(), 2, "the first entry is used by one of the last builds");
+
+  scan("export const a = 2;");
+  cache.evict();
+  assert_eq!(entries(), 1, "the first entry isn't used by any of the last builds");
+
+  let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_scan_cache_output() {
+  use std::path::Path;
+
+  use minipack_common::{BundlerOptions, InputItem};
+
+  use crate::Bundler;
+
+  let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/scan_cache");
+  let dir = std::env::temp_dir().join(format!("minipack-scan-cache-output-{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+
+  let mut outputs = vec![];
+  for cache_dir in [None, Some(&dir), Some(&dir)] {
+    let mut bundler = Bundler::new(BundlerOptions {
+      cwd: Some(cwd.clone()),
+      input: Some(vec![InputItem { name: None, import: "./main.ts".to_string() }]),
+      cache_dir: cache_dir.map(|dir| dir.to_string_lossy().into_owned()),
+      ..BundlerOptions::default()
+    })
+    .unwrap();
+    let output = bundler.build(false).await.unwrap();
+    outputs.push(
+      output.assets.into_iter().map(|asset| (asset.filename, asset.content)).collect::<Vec<_>>(),
+    );
+  }
+
+  // Builds with a cold and a warm cache are the same as a build without it.
+  assert_eq!(outputs[0], outputs[1]);
+  assert_eq!(outputs[0], outputs[2]);
+  assert!(outputs[2].iter().any(|(_, content)| content.contains("count++")));
+  // `main.ts`, `greet.ts`, `counter.js`, `unused.js` and `lazy.js`, but not the runtime.
+  assert_eq!(std::fs::read_dir(&dir).unwrap().count() - /* index */ 1, 5);
+
+  let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_scan_cache_edit() {
+  use std::path::Path;
+
+  use minipack_common::{BundlerOptions, InputItem};
+
+  use crate::Bundler;
+
+  let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/scan_cache");
+  let root = std::env::temp_dir().join(format!("minipack-scan-cache-edit-{}", std::process::id()));
+  let (cwd, dir) = (root.join("app"), root.join("cache"));
+  let _ = std::fs::remove_dir_all(&root);
+  std::fs::create_dir_all(&cwd).unwrap();
+  for entry in std::fs::read_dir(&fixture).unwrap() {
+    let path = entry.unwrap().path();
+    std::fs::copy(&path, cwd.join(path.file_name().unwrap())).unwrap();
+  }
+
+  let build = |cache_dir: Option<&Path>| {
+    let options = BundlerOptions {
+      cwd: Some(cwd.clone()),
+      input: Some(vec![InputItem { name: None, import: "./main.ts".to_string() }]),
+      cache_dir: cache_dir.map(|dir| dir.to_string_lossy().into_owned()),
+      ..BundlerOptions::default()
+    };
+    async move {
+      let output = Bundler::new(options).unwrap().build(false).await.unwrap();
+      output.assets.into_iter().map(|asset| (asset.filename, asset.content)).collect::<Vec<_>>()
+    }
+  };
+
+  build(Some(&dir)).await;
+  let greet = std::fs::read_to_string(cwd.join("greet.ts")).unwrap();
+  std::fs::write(cwd.join("greet.ts"), greet.replace("hello", "goodbye")).unwrap();
+
+  // The edited module misses the cache, the others hit it, and the output is the same as without it.
+  let output = build(Some(&dir)).await;
+  assert_eq!(output, build(None).await);
+  let content = output.iter().map(|(_, content)| content.to_string()).collect::<String>();
+  assert!(content.contains("goodbye") && !content.contains("hello"));
+
+  let _ = std::fs::remove_dir_all(&root);
+}
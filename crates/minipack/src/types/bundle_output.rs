@@ -1,7 +1,12 @@
-use minipack_common::OutputAsset;
+use minipack_common::{ModuleInfo, OutputAsset, UnusedCodeReport, WriteReport};
 
 #[derive(Default)]
 pub struct BundleOutput {
   pub assets: Vec<OutputAsset>,
   pub warnings: Vec<anyhow::Error>,
+  pub modules: Vec<ModuleInfo>,
+  /// Unused code found by tree shaking, if `unused_code` is enabled.
+  pub unused_code: Option<UnusedCodeReport>,
+  /// Files written, skipped and deleted, if the build was written to disk.
+  pub write_report: Option<WriteReport>,
 }
@@ -12,13 +12,20 @@ pub struct GenerateOutput {
   pub warnings: Vec<anyhow::Error>,
 }
 
+/// The code of a module printed from its finalized AST.
+pub struct ModuleCodegenOutput {
+  pub code: String,
+  /// Legal comments moved out of the code with `eof` or `linked` legal comments.
+  pub legal_comments: Vec<String>,
+}
+
 pub struct GenerateContext<'a> {
   pub chunk: &'a Chunk,
   pub chunk_idx: ChunkIdx,
   pub chunk_graph: &'a ChunkGraph,
   pub link_stage_output: &'a LinkStageOutput,
   pub options: &'a NormalizedBundlerOptions,
-  pub module_id_to_codegen_ret: Vec<Option<String>>,
+  pub module_id_to_codegen_ret: Vec<Option<ModuleCodegenOutput>>,
   pub warnings: Vec<anyhow::Error>,
 }
 
@@ -32,7 +39,9 @@ impl GenerateContext<'_> {
   ) -> String {
     let symbol_db = &self.link_stage_output.symbol_ref_db;
     let canonical_ref = symbol_db.canonical_ref_for(symbol_ref);
-    if let OutputFormat::Cjs = self.options.format {
+    let is_external_runtime = self.options.runtime.is_external()
+      && canonical_ref.owner == self.link_stage_output.runtime_module.idx;
+    if matches!(self.options.format, OutputFormat::Cjs) && !is_external_runtime {
       let canonical_symbol = symbol_db.get(canonical_ref);
       let chunk_idx_of_canonical_symbol = canonical_symbol.chunk_id.unwrap_or_else(|| {
         // Scoped symbols don't get assigned a `ChunkId`. There are skipped for performance reason, because they are surely
@@ -56,6 +65,17 @@ impl GenerateContext<'_> {
     symbol_db.canonical_name_for(canonical_ref, canonical_names).to_string()
   }
 
+  /// Helpers imported from the external runtime by the chunk, as `(imported, local)` names.
+  pub fn runtime_imports(&self) -> impl Iterator<Item = (&str, &str)> {
+    let symbol_db = &self.link_stage_output.symbol_ref_db;
+    self.chunk.imports_from_runtime.iter().map(|symbol_ref| {
+      (
+        symbol_ref.name(symbol_db),
+        symbol_db.canonical_name_for(*symbol_ref, &self.chunk.canonical_names),
+      )
+    })
+  }
+
   pub fn renderable_ecma_modules(&self) -> impl Iterator<Item = &NormalModule> {
     self.chunk.modules.iter().copied().filter_map(move |id| {
       let Module::Normal(module) = &self.link_stage_output.module_table[id] else { return None };
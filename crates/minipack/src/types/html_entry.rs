@@ -0,0 +1,26 @@
+use std::ops::Range;
+
+use arcstr::ArcStr;
+use minipack_common::ModuleIdx;
+
+/// A html file passed as an input. Its module scripts are bundled as user-defined entries and
+/// the html is emitted with the script tags pointing to the generated chunks.
+#[derive(Debug)]
+pub struct HtmlEntry {
+  /// Absolute path of the html file.
+  pub id: ArcStr,
+  pub source: String,
+  /// The byte range of each `<script type="module">` tag and the entry module it refers to.
+  pub scripts: Vec<(Range<usize>, ModuleIdx)>,
+  /// Local stylesheets of `<link rel="stylesheet">` tags, which are copied to the output directory.
+  pub stylesheets: Vec<HtmlStylesheet>,
+}
+
+#[derive(Debug)]
+pub struct HtmlStylesheet {
+  /// The byte range of the `href` value.
+  pub href_range: Range<usize>,
+  /// Absolute path of the stylesheet.
+  pub id: String,
+  pub content: String,
+}
@@ -33,6 +33,19 @@ pub struct LinkingMetadata {
   // Import records corresponding to 'export * from 'external-module'` statements.
   // These external exports are handled differently as their contents are unknown internally.
   pub star_exports_from_external_modules: Vec<ImportRecordIdx>,
+
+  // Whether the module uses top-level await or statically imports a module which does, directly or
+  // transitively. Such modules can't be evaluated synchronously.
+  pub is_tla_or_contains_tla_dependency: bool,
+
+  // `init_xxx` of the module when it's wrapped in `var init_xxx = __esm(() => { ... })` to keep
+  // the order of execution with `strict_execution_order`.
+  pub wrapper_ref: Option<SymbolRef>,
+
+  // Whether the module is kept with its namespace object, which is registered by
+  // `__hmrRegister(stable_id, ns)` so that hmr updates can reuse the module instead of running it
+  // again.
+  pub registers_hmr_exports: bool,
 }
 
 impl LinkingMetadata {
@@ -41,4 +54,10 @@ impl LinkingMetadata {
   pub fn canonical_exports(&self) -> impl Iterator<Item = (&Rstr, SymbolRef)> {
     self.sorted_resolved_exports.iter().map(|name| (name, self.resolved_exports[name]))
   }
+
+  /// Whether the chunk of the module, as a dynamic entry, may import other chunks, which are then
+  /// preloaded along with it. The chunk of the runtime alone isn't worth preloading.
+  pub fn may_have_preload_deps(&self, runtime_idx: ModuleIdx) -> bool {
+    self.dependencies.iter().any(|&dep| dep != runtime_idx)
+  }
 }
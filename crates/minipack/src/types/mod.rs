@@ -1,5 +1,6 @@
 pub mod bundle_output;
 pub mod generator;
+pub mod html_entry;
 pub mod linking_metadata;
 
 use std::sync::Arc;
@@ -0,0 +1,645 @@
+use arcstr::ArcStr;
+use minipack_common::{
+  EcmaView, EcmaViewMeta, ExternalModule, LocalExport, Module, ModuleDts, ModuleId, ModuleIdx,
+  ModuleType, NormalModule, OutputFormat, Specifier, SymbolOrMemberExprRef, SymbolRef, SymbolRefDb,
+  SymbolRefDbForModule, TypeOnlyModule, side_effects::DeterminedSideEffects,
+};
+use minipack_ecmascript::{BindingIdentifierExt, EcmaAst, EcmaCompiler};
+use minipack_error::BuildResult;
+use minipack_utils::{
+  concat_string,
+  indexmap::FxIndexMap,
+  path_ext::PathExt,
+  rayon::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator},
+  rstr::{Rstr, ToRstr},
+};
+use oxc::{
+  ast::ast::{self, ExportDefaultDeclarationKind, Statement},
+  ast_visit::{Visit, walk},
+  semantic::{ScopeId, Scoping, SemanticBuilder},
+  span::{GetSpan, SourceType, Span},
+};
+use oxc_index::IndexVec;
+use rustc_hash::{FxHashMap, FxHashSet};
+use sugar_path::SugarPath;
+
+use crate::{
+  link_stage::bind_imports_and_exports,
+  scan_stage::ast_scanner::AstScanner,
+  types::{IndexModules, LinkingMetadataVec, linking_metadata::LinkingMetadata},
+  utils::{ecmascript::legitimize_identifier_name, renamer::Renamer},
+};
+
+/// The declarations of the `dts` option, scanned into a module table of their own and linked by the
+/// linker like the code. Modules that are only imported for their types are part of it, although
+/// they aren't part of the module graph.
+pub struct DtsGraph {
+  module_table: IndexModules,
+  asts: IndexVec<ModuleIdx, Option<EcmaAst>>,
+  symbol_ref_db: SymbolRefDb,
+  metadata: LinkingMetadataVec,
+  /// Bindings of `import type` and `export type ... from`, imports of external modules stay type-only.
+  type_only_imports: FxHashSet<SymbolRef>,
+  ids: FxHashMap<ArcStr, ModuleIdx>,
+}
+
+impl DtsGraph {
+  pub fn link(
+    module_table: &IndexModules,
+    type_only_modules: &[TypeOnlyModule],
+  ) -> BuildResult<Self> {
+    let sources = module_table
+      .iter()
+      .filter_map(|module| {
+        let module = module.as_normal()?;
+        Some((ArcStr::from(&*module.id), module.stable_id.as_str(), module.dts.as_ref()?))
+      })
+      .chain(
+        type_only_modules
+          .iter()
+          .map(|module| (module.id.clone(), module.stable_id.as_str(), &module.dts)),
+      )
+      .collect::<Vec<_>>();
+    let ids = sources
+      .iter()
+      .enumerate()
+      .map(|(idx, (id, ..))| (id.clone(), ModuleIdx::from_usize(idx)))
+      .collect::<FxHashMap<_, _>>();
+
+    let scanned = sources
+      .par_iter()
+      .enumerate()
+      .map(|(idx, (_, stable_id, dts))| scan_dts(ModuleIdx::from_usize(idx), stable_id, dts))
+      .collect::<BuildResult<Vec<_>>>()?;
+
+    // Declarations that aren't bundled are imported from external modules, one for each specifier.
+    let mut externals = FxIndexMap::<Rstr, ModuleIdx>::default();
+    let mut symbol_ref_db = SymbolRefDb::default();
+    let mut table = IndexModules::with_capacity(sources.len());
+    let mut asts = IndexVec::with_capacity(sources.len());
+    let mut type_only_imports = FxHashSet::default();
+    for ((id, stable_id, dts), scanned) in sources.iter().zip(scanned) {
+      let ScannedDts { ast, scan_result, type_only } = scanned;
+      let idx = scan_result.symbols.owner;
+      let deps =
+        dts.specifiers.iter().map(String::as_str).zip(&dts.deps).collect::<FxHashMap<_, _>>();
+      let import_records = scan_result
+        .import_records
+        .into_iter()
+        .map(|rec| {
+          let state = match deps.get(rec.specifier.as_str()) {
+            Some(Some(dep)) if ids.contains_key(dep) => ids[dep],
+            _ => {
+              let next_idx = ModuleIdx::from_usize(sources.len() + externals.len());
+              *externals.entry(rec.specifier.clone()).or_insert(next_idx)
+            }
+          };
+          rec.into_resolved(state)
+        })
+        .collect();
+      type_only_imports.extend(type_only);
+      symbol_ref_db.store_local_db(idx, scan_result.symbols);
+      asts.push(Some(ast));
+      table.push(Module::Normal(Box::new(NormalModule {
+        id: ModuleId::new(id.clone()),
+        idx,
+        stable_id: (*stable_id).to_string(),
+        exec_order: u32::MAX,
+        module_type: ModuleType::Ts,
+        is_user_defined_entry: false,
+        ecma_view: EcmaView {
+          source: dts.code.as_str().into(),
+          ecma_ast_idx: None,
+          named_imports: scan_result.named_imports,
+          named_exports: scan_result.named_exports,
+          stmt_infos: scan_result.stmt_infos,
+          imports: scan_result.imports,
+          default_export_ref: scan_result.default_export_ref,
+          namespace_object_ref: scan_result.namespace_object_ref,
+          import_records,
+          import_globs: FxHashMap::default(),
+          hmr_accepted_deps: vec![],
+          dts: None,
+          side_effects: DeterminedSideEffects::Analyzed(false),
+          meta: {
+            let mut meta = EcmaViewMeta::default();
+            meta.set(EcmaViewMeta::HAS_STAR_EXPORT, scan_result.has_star_exports);
+            meta
+          },
+        },
+      })));
+    }
+    for (specifier, idx) in externals {
+      symbol_ref_db
+        .store_local_db(idx, SymbolRefDbForModule::new(idx, Scoping::default(), ScopeId::new(0)));
+      let name = legitimize_identifier_name(specifier.as_str());
+      let namespace_ref = symbol_ref_db.create_facade_root_symbol_ref(idx, &name);
+      table.push(Module::External(Box::new(ExternalModule::new(
+        idx,
+        specifier.as_str().into(),
+        namespace_ref,
+      ))));
+      asts.push(None);
+    }
+
+    let mut metadata = table.iter().map(|_| LinkingMetadata::default()).collect();
+    // Imports of external modules are kept as imports, like in esm output.
+    let errors =
+      bind_imports_and_exports(&table, &mut metadata, &mut symbol_ref_db, OutputFormat::Esm);
+    if !errors.is_empty() {
+      Err(errors)?;
+    }
+
+    Ok(Self { module_table: table, asts, symbol_ref_db, metadata, type_only_imports, ids })
+  }
+
+  /// Bundle the declarations of the modules reachable from `entry` into a single `.d.ts` file.
+  ///
+  /// Imports of bundled modules are replaced with the bindings they resolve to, top-level names are
+  /// deconflicted across modules and only the exports of the entry are kept. Imports of external
+  /// modules are hoisted to the top of the file.
+  pub fn bundle(&self, entry: &str) -> String {
+    let entry = self.ids[entry];
+    let mut modules = vec![];
+    self.collect_modules(entry, &mut FxHashSet::default(), &mut modules);
+
+    let namespaces = self.referenced_namespaces(entry, &modules);
+    let mut renamer = Renamer::new(Some(entry), &self.symbol_ref_db, OutputFormat::Esm);
+    for module in &modules {
+      for name in self.symbol_ref_db[module.idx]
+        .as_ref()
+        .unwrap()
+        .ast_scopes
+        .root_unresolved_references()
+        .keys()
+      {
+        renamer.reserve(name.to_rstr());
+      }
+    }
+    let external_imports = self.external_imports(&modules);
+    for (_, imports) in &external_imports {
+      for import in imports {
+        renamer.add_symbol_in_root_scope(import.local);
+      }
+    }
+    for module in modules.iter().rev() {
+      for stmt_info in module.stmt_infos.iter() {
+        for symbol_ref in &stmt_info.declared_symbols {
+          renamer.add_symbol_in_root_scope(*symbol_ref);
+        }
+      }
+    }
+    for namespace in &namespaces {
+      renamer.add_symbol_in_root_scope(
+        self.module_table[*namespace].as_normal().unwrap().namespace_object_ref,
+      );
+    }
+    let canonical_names = renamer.canonical_names;
+    let name_of = |symbol_ref: SymbolRef| -> &str {
+      self.symbol_ref_db.canonical_name_for(symbol_ref, &canonical_names)
+    };
+
+    let mut code = String::new();
+    for (external, imports) in &external_imports {
+      let source = &self.module_table[*external].as_external().unwrap().name;
+      code.push_str(&render_external_imports(source, imports, &name_of));
+    }
+    for module in &modules {
+      let mut renderer = DtsRenderer { graph: self, module, name_of: &name_of, edits: vec![] };
+      renderer.visit_program(self.asts[module.idx].as_ref().unwrap().program());
+      code.push_str(&renderer.render());
+    }
+    for namespace in namespaces {
+      let module = self.module_table[namespace].as_normal().unwrap();
+      code.push_str(&concat_string!(
+        "declare namespace ",
+        name_of(module.namespace_object_ref),
+        " {\n  export { ",
+        self.render_export_specifiers(namespace, &name_of).join(", "),
+        " };\n}\n"
+      ));
+    }
+
+    let specifiers = self.render_export_specifiers(entry, &name_of);
+    let mut exports = String::new();
+    if !specifiers.is_empty() {
+      exports.push_str(&concat_string!("export { ", specifiers.join(", "), " };\n"));
+    }
+    for source in self.external_star_exports(entry) {
+      exports.push_str(&concat_string!("export * from ", quote(source), ";\n"));
+    }
+    if exports.is_empty() {
+      // Keep the file a module rather than a script with global declarations.
+      exports.push_str("export {};\n");
+    }
+    code.push_str(&exports);
+    code
+  }
+
+  /// Dependencies come before their importers, like the execution order of the code.
+  fn collect_modules<'a>(
+    &'a self,
+    idx: ModuleIdx,
+    visited: &mut FxHashSet<ModuleIdx>,
+    modules: &mut Vec<&'a NormalModule>,
+  ) {
+    if !visited.insert(idx) {
+      return;
+    }
+    let Module::Normal(module) = &self.module_table[idx] else {
+      return;
+    };
+    for rec in &module.import_records {
+      self.collect_modules(rec.state, visited, modules);
+    }
+    modules.push(module);
+  }
+
+  fn namespace_owner(&self, symbol_ref: SymbolRef) -> Option<ModuleIdx> {
+    let canonical_ref = self.symbol_ref_db.canonical_ref_for(symbol_ref);
+    self.module_table[canonical_ref.owner]
+      .as_normal()
+      .filter(|module| module.namespace_object_ref == canonical_ref)
+      .map(|module| module.idx)
+  }
+
+  /// Modules whose namespace objects are referenced, e.g. `ns` of `import * as ns from './types'`
+  /// that isn't only used as `ns.Foo`. They are declared as namespaces.
+  fn referenced_namespaces(&self, entry: ModuleIdx, modules: &[&NormalModule]) -> Vec<ModuleIdx> {
+    let mut queue = vec![];
+    for module in modules {
+      let resolved_member_expr_refs = &self.metadata[module.idx].resolved_member_expr_refs;
+      for stmt_info in module.stmt_infos.iter() {
+        for symbol_ref in &stmt_info.referenced_symbols {
+          let symbol_ref = match symbol_ref {
+            SymbolOrMemberExprRef::Symbol(symbol_ref) => *symbol_ref,
+            SymbolOrMemberExprRef::MemberExpr(expr) => {
+              match resolved_member_expr_refs.get(&expr.span) {
+                Some((Some(symbol_ref), _)) => *symbol_ref,
+                _ => expr.object_ref,
+              }
+            }
+          };
+          queue.extend(self.namespace_owner(symbol_ref));
+        }
+      }
+    }
+    queue.extend(
+      self.metadata[entry]
+        .canonical_exports()
+        .filter_map(|(_, symbol_ref)| self.namespace_owner(symbol_ref)),
+    );
+
+    let mut namespaces = vec![];
+    let mut visited = FxHashSet::default();
+    while let Some(idx) = queue.pop() {
+      if !visited.insert(idx) {
+        continue;
+      }
+      namespaces.push(idx);
+      queue.extend(
+        self.metadata[idx]
+          .canonical_exports()
+          .filter_map(|(_, symbol_ref)| self.namespace_owner(symbol_ref)),
+      );
+    }
+    namespaces.sort_unstable();
+    namespaces
+  }
+
+  /// Named and namespace imports of external modules, in the order they are imported.
+  fn external_imports(
+    &self,
+    modules: &[&NormalModule],
+  ) -> FxIndexMap<ModuleIdx, Vec<ExternalImport>> {
+    let mut imports = FxIndexMap::<ModuleIdx, Vec<ExternalImport>>::default();
+    for module in modules {
+      let mut named_imports = module.named_imports.values().collect::<Vec<_>>();
+      named_imports.sort_unstable_by_key(|named_import| named_import.imported_span.start);
+      for named_import in named_imports {
+        let external = module.import_records[named_import.record_id].state;
+        if !self.module_table[external].is_external() {
+          continue;
+        }
+        let local = self.symbol_ref_db.canonical_ref_for(named_import.imported_as);
+        let is_type_only = self.type_only_imports.contains(&named_import.imported_as);
+        let imports = imports.entry(external).or_default();
+        match imports.iter_mut().find(|import| import.local == local) {
+          Some(import) => import.is_type_only &= is_type_only,
+          None => imports.push(ExternalImport {
+            imported: named_import.imported.clone(),
+            local,
+            is_type_only,
+          }),
+        }
+      }
+    }
+    imports
+  }
+
+  fn render_export_specifiers<'a>(
+    &self,
+    idx: ModuleIdx,
+    name_of: &impl Fn(SymbolRef) -> &'a str,
+  ) -> Vec<String> {
+    self.metadata[idx]
+      .canonical_exports()
+      .map(|(name, symbol_ref)| {
+        let local = name_of(symbol_ref);
+        if local == name.as_str() { local.to_string() } else { concat_string!(local, " as ", name) }
+      })
+      .collect()
+  }
+
+  /// `export * from 'external'` of the entry and the modules it re-exports everything from.
+  fn external_star_exports(&self, entry: ModuleIdx) -> Vec<&str> {
+    let mut sources = vec![];
+    let mut queue = vec![entry];
+    let mut visited = FxHashSet::default();
+    while let Some(idx) = queue.pop() {
+      let Module::Normal(module) = &self.module_table[idx] else {
+        continue;
+      };
+      if !visited.insert(idx) {
+        continue;
+      }
+      for rec_idx in module.star_exports_from_external_modules(&self.module_table) {
+        sources.push(module.import_records[rec_idx].specifier.as_str());
+      }
+      queue.extend(module.star_export_module_ids());
+    }
+    sources
+  }
+}
+
+struct ScannedDts {
+  ast: EcmaAst,
+  scan_result: crate::scan_stage::ast_scanner::AstScanResult,
+  type_only: Vec<SymbolRef>,
+}
+
+fn scan_dts(idx: ModuleIdx, stable_id: &str, dts: &ModuleDts) -> BuildResult<ScannedDts> {
+  let fail =
+    |reason: &str| anyhow::anyhow!("Failed to bundle declarations of {stable_id} - {reason}.");
+  // Not parsed as `.d.ts`, the semantic builder wouldn't declare any symbols of it.
+  let ast = EcmaCompiler::parse(dts.code.as_str(), SourceType::ts())?;
+  let program = ast.program();
+  let scoping = SemanticBuilder::new().build(program).semantic.into_scoping();
+  let repr_name = stable_id.as_path().representative_file_name();
+  let mut scan_result =
+    AstScanner::new(idx, scoping, &legitimize_identifier_name(&repr_name)).scan(program)?;
+  if !scan_result.errors.is_empty() {
+    Err(std::mem::take(&mut scan_result.errors))?;
+  }
+
+  let mut type_only = vec![];
+  for stmt in &program.body {
+    match stmt {
+      Statement::ImportDeclaration(decl) => {
+        for specifier in decl.specifiers.iter().flatten() {
+          let is_type_only = decl.import_kind.is_type()
+            || matches!(specifier, ast::ImportDeclarationSpecifier::ImportSpecifier(specifier)
+              if specifier.import_kind.is_type());
+          if is_type_only {
+            type_only.push((idx, specifier.local().expect_symbol_id()).into());
+          }
+        }
+      }
+      Statement::ExportNamedDeclaration(decl) if decl.source.is_some() => {
+        for specifier in &decl.specifiers {
+          if decl.export_kind.is_type() || specifier.export_kind.is_type() {
+            let exported = specifier.exported.name();
+            type_only.push(scan_result.named_exports[&exported.to_rstr()].referenced);
+          }
+        }
+      }
+      // `export default foo` exports `foo` itself, declarations aren't values that are copied.
+      Statement::ExportDefaultDeclaration(decl) => {
+        if let ExportDefaultDeclarationKind::Identifier(ident) = &decl.declaration {
+          let Some(symbol_id) = scan_result.symbols.get_root_binding(&ident.name) else {
+            Err(fail(&format!("{:?} is exported but not declared", ident.name.as_str())))?
+          };
+          scan_result.named_exports.insert(
+            "default".into(),
+            LocalExport { referenced: (idx, symbol_id).into(), span: ident.span },
+          );
+        } else if decl.declaration.is_expression() {
+          Err(fail("default exports must be declarations or identifiers"))?;
+        }
+      }
+      Statement::TSExportAssignment(_) => Err(fail("`export =` is not supported"))?,
+      _ => {}
+    }
+  }
+
+  Ok(ScannedDts { ast, scan_result, type_only })
+}
+
+fn quote(source: &str) -> String {
+  serde_json::to_string(source).expect("strings should be serializable")
+}
+
+struct ExternalImport {
+  imported: Specifier,
+  local: SymbolRef,
+  is_type_only: bool,
+}
+
+/// Imports of an external module stay type-only when all of the bindings are only imported for
+/// their types.
+fn render_external_imports<'a>(
+  source: &str,
+  imports: &[ExternalImport],
+  name_of: &impl Fn(SymbolRef) -> &'a str,
+) -> String {
+  let mut code = String::new();
+  let mut specifiers = vec![];
+  for import in imports {
+    let local = name_of(import.local);
+    match &import.imported {
+      Specifier::Star => {
+        let keyword = if import.is_type_only { "import type * as " } else { "import * as " };
+        code.push_str(&concat_string!(keyword, local, " from ", quote(source), ";\n"));
+      }
+      Specifier::Literal(imported) => {
+        let specifier = if imported.as_str() == local {
+          local.to_string()
+        } else {
+          concat_string!(imported, " as ", local)
+        };
+        specifiers.push((specifier, import.is_type_only));
+      }
+    }
+  }
+  if !specifiers.is_empty() {
+    let is_type_only = specifiers.iter().all(|(_, is_type_only)| *is_type_only);
+    let specifiers = specifiers
+      .into_iter()
+      .map(
+        |(specifier, is_type)| {
+          if is_type && !is_type_only { concat_string!("type ", specifier) } else { specifier }
+        },
+      )
+      .collect::<Vec<_>>();
+    let keyword = if is_type_only { "import type { " } else { "import { " };
+    code.push_str(&concat_string!(
+      keyword,
+      specifiers.join(", "),
+      " } from ",
+      quote(source),
+      ";\n"
+    ));
+  }
+  code
+}
+
+/// Renders the declarations of a module with the names they are given in the bundle. Import and
+/// export declarations are removed, the exports of the entry are rendered at the end.
+struct DtsRenderer<'a, 'name, F: Fn(SymbolRef) -> &'name str> {
+  graph: &'a DtsGraph,
+  module: &'a NormalModule,
+  name_of: &'a F,
+  edits: Vec<(Span, String)>,
+}
+
+impl<'name, F: Fn(SymbolRef) -> &'name str> DtsRenderer<'_, 'name, F> {
+  fn scopes(&self) -> &Scoping {
+    &self.graph.symbol_ref_db[self.module.idx].as_ref().unwrap().ast_scopes
+  }
+
+  fn rename(&mut self, span: Span, name: &str, symbol_id: Option<oxc::semantic::SymbolId>) {
+    let Some(symbol_id) = symbol_id else {
+      return;
+    };
+    if self.scopes().symbol_scope_id(symbol_id) != self.scopes().root_scope_id() {
+      return;
+    }
+    let canonical_name = (self.name_of)((self.module.idx, symbol_id).into());
+    if canonical_name != name {
+      self.edits.push((span, canonical_name.to_string()));
+    }
+  }
+
+  /// `export default function () {}` is named after the default export of the module.
+  fn declare_default_export(
+    &mut self,
+    decl: &ast::ExportDefaultDeclaration,
+    keyword: &str,
+    start: u32,
+    declare: bool,
+    is_anonymous: bool,
+  ) {
+    self.edits.push((
+      Span::new(decl.span.start, start),
+      if declare { String::new() } else { "declare ".to_string() },
+    ));
+    if is_anonymous {
+      let code = self.module.source.as_str();
+      #[allow(clippy::cast_possible_truncation)]
+      let keyword_end = code[start as usize..]
+        .find(keyword)
+        .map_or(start, |offset| start + (offset + keyword.len()) as u32);
+      let name = (self.name_of)(self.module.default_export_ref);
+      self.edits.push((Span::new(keyword_end, keyword_end), concat_string!(" ", name)));
+    }
+  }
+
+  fn render(mut self) -> String {
+    // A removed statement contains the identifiers in it, which are skipped.
+    self.edits.sort_unstable_by_key(|(span, _)| (span.start, std::cmp::Reverse(span.end)));
+    let code = self.module.source.as_str();
+    let mut rendered = String::with_capacity(code.len());
+    let mut last_end = 0;
+    for (span, replacement) in &self.edits {
+      if span.start < last_end {
+        continue;
+      }
+      rendered.push_str(&code[last_end as usize..span.start as usize]);
+      rendered.push_str(replacement);
+      last_end = span.end;
+    }
+    rendered.push_str(&code[last_end as usize..]);
+
+    rendered.lines().filter(|line| !line.trim().is_empty()).fold(String::new(), |mut acc, line| {
+      acc.push_str(line);
+      acc.push('\n');
+      acc
+    })
+  }
+}
+
+impl<'ast, 'name, F: Fn(SymbolRef) -> &'name str> Visit<'ast> for DtsRenderer<'_, 'name, F> {
+  fn visit_program(&mut self, program: &ast::Program<'ast>) {
+    for stmt in &program.body {
+      match stmt {
+        Statement::ImportDeclaration(_)
+        | Statement::ExportAllDeclaration(_)
+        | Statement::TSNamespaceExportDeclaration(_) => {
+          self.edits.push((stmt.span(), String::new()))
+        }
+        Statement::ExportNamedDeclaration(decl) => match &decl.declaration {
+          Some(declaration) if decl.source.is_none() => {
+            self.edits.push((Span::new(decl.span.start, declaration.span().start), String::new()));
+            self.visit_declaration(declaration);
+          }
+          _ => self.edits.push((decl.span, String::new())),
+        },
+        Statement::ExportDefaultDeclaration(decl) => match &decl.declaration {
+          ExportDefaultDeclarationKind::FunctionDeclaration(func) => {
+            self.declare_default_export(
+              decl,
+              "function",
+              func.span.start,
+              func.declare,
+              func.id.is_none(),
+            );
+            self.visit_function(func, oxc::semantic::ScopeFlags::empty());
+          }
+          ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+            self.declare_default_export(
+              decl,
+              "class",
+              class.span.start,
+              class.declare,
+              class.id.is_none(),
+            );
+            self.visit_class(class);
+          }
+          ExportDefaultDeclarationKind::TSInterfaceDeclaration(interface) => {
+            self.edits.push((Span::new(decl.span.start, interface.span.start), String::new()));
+            self.visit_ts_interface_declaration(interface);
+          }
+          _ => self.edits.push((decl.span, String::new())),
+        },
+        _ => self.visit_statement(stmt),
+      }
+    }
+  }
+
+  fn visit_binding_identifier(&mut self, ident: &ast::BindingIdentifier<'ast>) {
+    self.rename(ident.span, &ident.name, ident.symbol_id.get());
+  }
+
+  fn visit_identifier_reference(&mut self, ident: &ast::IdentifierReference<'ast>) {
+    let symbol_id = ident
+      .reference_id
+      .get()
+      .and_then(|reference_id| self.scopes().get_reference(reference_id).symbol_id());
+    self.rename(ident.span, &ident.name, symbol_id);
+  }
+
+  /// `ns.Foo` of `import * as ns from './types'` is the binding `Foo` resolves to.
+  fn visit_ts_qualified_name(&mut self, name: &ast::TSQualifiedName<'ast>) {
+    let resolved = &self.graph.metadata[self.module.idx].resolved_member_expr_refs;
+    if let Some((Some(symbol_ref), props)) = resolved.get(&name.span) {
+      let mut replacement = (self.name_of)(*symbol_ref).to_string();
+      for prop in props {
+        replacement.push('.');
+        replacement.push_str(prop);
+      }
+      self.edits.push((name.span, replacement));
+      return;
+    }
+    walk::walk_ts_qualified_name(self, name);
+  }
+}
@@ -52,6 +52,11 @@ pub fn deconflict_chunk_symbols(
     }
   });
 
+  // Helpers of the external runtime keep their names unless they conflict.
+  chunk.imports_from_runtime.iter().for_each(|symbol_ref| {
+    renamer.add_symbol_in_root_scope(*symbol_ref);
+  });
+
   // Imported symbols are declared in this chunk's scope via import statements.
   chunk.imports_from_other_chunks.iter().flat_map(|(_, items)| items.iter()).for_each(|item| {
     renamer.add_symbol_in_root_scope(item.import_ref);
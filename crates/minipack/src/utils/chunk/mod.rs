@@ -1,3 +1,5 @@
 pub mod deconflict_chunk_symbols;
 pub mod finalize_chunks;
 pub mod render_chunk_exports;
+pub mod render_legal_comments;
+pub mod render_wrapped_entry_init;
@@ -1,6 +1,9 @@
 use std::borrow::Cow;
 
-use minipack_common::{Chunk, ChunkKind, ModuleIdx, OutputFormat, SymbolRef, SymbolRefDb};
+use minipack_common::{
+  Chunk, ChunkKind, EsModuleFlag, GeneratedCodeOptions, ModuleIdx, NormalModule, OutputExports,
+  OutputFormat, SymbolRef, SymbolRefDb,
+};
 use minipack_utils::{
   concat_string,
   ecmascript::{property_access_str, to_module_import_export_name},
@@ -9,8 +12,13 @@ use minipack_utils::{
 };
 
 use crate::{link_stage::LinkStageOutput, types::generator::GenerateContext};
+use minipack_error::BuildResult;
 
-pub fn render_chunk_exports(ctx: &GenerateContext<'_>) -> Option<String> {
+/// `export_mode` is the resolved `exports` option of CJS entry chunks.
+pub fn render_chunk_exports(
+  ctx: &GenerateContext<'_>,
+  export_mode: Option<OutputExports>,
+) -> Option<String> {
   let GenerateContext { chunk, link_stage_output, options, .. } = ctx;
   let export_items = get_export_items(chunk, link_stage_output).into_iter().collect::<Vec<_>>();
 
@@ -30,7 +38,8 @@ pub fn render_chunk_exports(ctx: &GenerateContext<'_>) -> Option<String> {
             let canonical_ns_name = &chunk.canonical_names[&ns_alias.namespace_ref];
             let property_name = &ns_alias.property_name;
             s.push_str(&concat_string!(
-              "var ",
+              options.generated_code.binding_keyword(),
+              " ",
               canonical_name,
               " = ",
               canonical_ns_name,
@@ -54,6 +63,8 @@ pub fn render_chunk_exports(ctx: &GenerateContext<'_>) -> Option<String> {
       s.push_str(&concat_string!("export { ", rendered_items.join(", "), " };"));
       Some(s)
     }
+    // Exports of IIFE chunks are reported by `render_iife`.
+    OutputFormat::Iife => None,
     OutputFormat::Cjs => {
       let mut s = String::new();
       match chunk.kind {
@@ -61,71 +72,47 @@ pub fn render_chunk_exports(ctx: &GenerateContext<'_>) -> Option<String> {
           let module =
             &link_stage_output.module_table[module].as_normal().expect("should be normal module");
 
-          let rendered_items = export_items
-            .into_iter()
-            .map(|(exported_name, export_ref)| {
-              let canonical_ref = link_stage_output.symbol_ref_db.canonical_ref_for(export_ref);
-              let symbol = link_stage_output.symbol_ref_db.get(canonical_ref);
-              let mut canonical_name = Cow::Borrowed(&chunk.canonical_names[&canonical_ref]);
-              let exported_value = if let Some(ns_alias) = &symbol.namespace_alias {
-                let canonical_ns_name = &chunk.canonical_names[&ns_alias.namespace_ref];
-                let property_name = &ns_alias.property_name;
-                Cow::Owned(property_access_str(canonical_ns_name, property_name).into())
-              } else if link_stage_output.module_table[canonical_ref.owner].is_external() {
-                let namespace = &chunk.canonical_names[&canonical_ref];
-                Cow::Owned(namespace.as_str().into())
-              } else {
-                let cur_chunk_idx = ctx.chunk_idx;
-                let canonical_ref_owner_chunk_idx =
-                  link_stage_output.symbol_ref_db.get(canonical_ref).chunk_id.unwrap();
-                let is_this_symbol_point_to_other_chunk =
-                  cur_chunk_idx != canonical_ref_owner_chunk_idx;
-                if is_this_symbol_point_to_other_chunk {
-                  let require_binding = &ctx.chunk.require_binding_names_for_other_chunks
-                    [&canonical_ref_owner_chunk_idx];
-                  canonical_name = Cow::Owned(Rstr::new(&concat_string!(
-                    require_binding,
-                    ".",
-                    canonical_name.as_str()
-                  )));
-                };
-                canonical_name.clone()
-              };
-
-              if must_keep_live_binding(export_ref, &link_stage_output.symbol_ref_db) {
-                render_object_define_property(&exported_name, &exported_value)
-              } else {
-                concat_string!(
-                  property_access_str("exports", exported_name.as_str()),
-                  " = ",
-                  exported_value.as_str()
-                )
+          match export_mode.unwrap_or_default() {
+            OutputExports::None => {}
+            OutputExports::Default => {
+              if let Some((_, export_ref)) = export_items.first() {
+                let exported_value = render_cjs_export_value(ctx, *export_ref);
+                s.push_str(&concat_string!("module.exports = ", exported_value, ";"));
               }
-            })
-            .collect::<Vec<_>>();
-          s.push_str(&rendered_items.join("\n"));
-
-          let meta = &ctx.link_stage_output.metadata[module.idx];
-          let external_modules = meta
-            .star_exports_from_external_modules
-            .iter()
-            .map(|rec_idx| module.ecma_view.import_records[*rec_idx].state)
-            .collect::<FxIndexSet<ModuleIdx>>();
-          external_modules.iter().for_each(|idx| {
-          let external = &ctx.link_stage_output.module_table[*idx].as_external().expect("Should be external module here");
-          let binding_ref_name =
-          &ctx.chunk.canonical_names[&external.namespace_ref];
-            let import_stmt =
-"Object.keys($NAME).forEach(function (k) {
-  if (k !== 'default' && !Object.prototype.hasOwnProperty.call(exports, k)) Object.defineProperty(exports, k, {
-    enumerable: true,
-    get: function () { return $NAME[k]; }
-  });
-});\n".replace("$NAME", binding_ref_name);
+            }
+            OutputExports::Auto | OutputExports::Named => {
+              let has_default_export =
+                export_items.iter().any(|(name, _)| name.as_str() == "default");
+              if matches!(options.es_module, EsModuleFlag::Always)
+                || (matches!(options.es_module, EsModuleFlag::IfDefaultProp) && has_default_export)
+              {
+                s.push_str("Object.defineProperty(exports, '__esModule', { value: true });\n");
+              }
+
+              let rendered_items = export_items
+                .into_iter()
+                .map(|(exported_name, export_ref)| {
+                  let exported_value = render_cjs_export_value(ctx, export_ref);
+                  if must_keep_live_binding(export_ref, &link_stage_output.symbol_ref_db) {
+                    render_object_define_property(
+                      &exported_name,
+                      &exported_value,
+                      &options.generated_code,
+                    )
+                  } else {
+                    concat_string!(
+                      property_access_str("exports", exported_name.as_str()),
+                      " = ",
+                      exported_value
+                    )
+                  }
+                })
+                .collect::<Vec<_>>();
+              s.push_str(&rendered_items.join("\n"));
 
-          s.push_str(&format!("\nvar {} = require(\"{}\");\n", binding_ref_name, &external.name));
-          s.push_str(&import_stmt);
-        });
+              s.push_str(&render_external_star_exports(ctx, module));
+            }
+          }
         }
         ChunkKind::Common => {
           export_items.into_iter().for_each(|(exported_name, export_ref)| {
@@ -139,9 +126,14 @@ pub fn render_chunk_exports(ctx: &GenerateContext<'_>) -> Option<String> {
               s.push_str(&render_object_define_property(
                 &exported_name,
                 &concat_string!(canonical_ns_name, ".", property_name),
+                &options.generated_code,
               ));
             } else {
-              s.push_str(&render_object_define_property(&exported_name, canonical_name));
+              s.push_str(&render_object_define_property(
+                &exported_name,
+                canonical_name,
+                &options.generated_code,
+              ));
             };
           });
         }
@@ -155,8 +147,127 @@ pub fn render_chunk_exports(ctx: &GenerateContext<'_>) -> Option<String> {
   }
 }
 
+/// Re-exports the external modules an entry module star-exports from, e.g. `export * from 'foo'`.
+fn render_external_star_exports(ctx: &GenerateContext<'_>, module: &NormalModule) -> String {
+  let options = ctx.options;
+  let meta = &ctx.link_stage_output.metadata[module.idx];
+  let external_modules = meta
+    .star_exports_from_external_modules
+    .iter()
+    .map(|rec_idx| module.ecma_view.import_records[*rec_idx].state)
+    .collect::<FxIndexSet<ModuleIdx>>();
+  let mut s = String::new();
+  external_modules.iter().for_each(|idx| {
+    let external = &ctx.link_stage_output.module_table[*idx]
+      .as_external()
+      .expect("Should be external module here");
+    let binding_ref_name = &ctx.chunk.canonical_names[&external.namespace_ref];
+    let import_stmt = if options.generated_code.arrow_functions {
+      "Object.keys($NAME).forEach((k) => {
+  if (k !== 'default' && !Object.prototype.hasOwnProperty.call(exports, k)) Object.defineProperty(exports, k, {
+    enumerable: true,
+    get: () => $NAME[k]
+  });
+});\n"
+    } else {
+      "Object.keys($NAME).forEach(function (k) {
+  if (k !== 'default' && !Object.prototype.hasOwnProperty.call(exports, k)) Object.defineProperty(exports, k, {
+    enumerable: true,
+    get: function () { return $NAME[k]; }
+  });
+});\n"
+    }
+    .replace("$NAME", binding_ref_name);
+
+    s.push_str(&format!(
+      "\n{} {} = require(\"{}\");\n",
+      options.generated_code.binding_keyword(),
+      binding_ref_name,
+      &external.name
+    ));
+    s.push_str(&import_stmt);
+  });
+  s
+}
+
+/// Resolves the `exports` option for a CJS entry chunk, and reports the exports it can't expose.
+pub fn determine_export_mode(
+  ctx: &mut GenerateContext<'_>,
+  module: &NormalModule,
+) -> BuildResult<OutputExports> {
+  let meta = &ctx.link_stage_output.metadata[module.idx];
+  let export_names = meta.canonical_exports().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+  let has_default_export = export_names.contains(&"default");
+  let has_named_exports = export_names.iter().any(|name| *name != "default")
+    || !meta.star_exports_from_external_modules.is_empty();
+
+  match ctx.options.exports {
+    OutputExports::Auto => {
+      if !has_named_exports {
+        return Ok(if has_default_export { OutputExports::Default } else { OutputExports::None });
+      }
+      if has_default_export {
+        ctx.warnings.push(anyhow::anyhow!(
+          r#"Entry module "{}" is using named and default exports together. Consumers of the "cjs" output have to use `require(...).default` to access the default export. Set `exports` to "named" to disable this warning."#,
+          module.stable_id
+        ));
+      }
+      Ok(OutputExports::Named)
+    }
+    OutputExports::Default if has_named_exports => Err(anyhow::anyhow!(
+      r#"Entry module "{}" has named exports, which can't be exposed with `exports` set to "default"."#,
+      module.stable_id
+    ))?,
+    OutputExports::None if has_default_export || has_named_exports => Err(anyhow::anyhow!(
+      r#"Entry module "{}" has exports, which can't be exposed with `exports` set to "none"."#,
+      module.stable_id
+    ))?,
+    mode => Ok(mode),
+  }
+}
+
+/// The value of an export of a CJS entry chunk, which might come from a namespace object, an
+/// external module or another chunk.
+fn render_cjs_export_value(ctx: &GenerateContext<'_>, export_ref: SymbolRef) -> String {
+  let GenerateContext { chunk, link_stage_output, .. } = ctx;
+  let canonical_ref = link_stage_output.symbol_ref_db.canonical_ref_for(export_ref);
+  let symbol = link_stage_output.symbol_ref_db.get(canonical_ref);
+  let canonical_name = &chunk.canonical_names[&canonical_ref];
+  if let Some(ns_alias) = &symbol.namespace_alias {
+    let canonical_ns_name = &chunk.canonical_names[&ns_alias.namespace_ref];
+    property_access_str(canonical_ns_name, &ns_alias.property_name)
+  } else if link_stage_output.module_table[canonical_ref.owner].is_external() {
+    canonical_name.to_string()
+  } else {
+    let canonical_ref_owner_chunk_idx = symbol.chunk_id.unwrap();
+    if ctx.chunk_idx == canonical_ref_owner_chunk_idx {
+      canonical_name.to_string()
+    } else {
+      let require_binding =
+        &chunk.require_binding_names_for_other_chunks[&canonical_ref_owner_chunk_idx];
+      concat_string!(require_binding, ".", canonical_name)
+    }
+  }
+}
+
 #[inline]
-pub fn render_object_define_property(key: &str, value: &str) -> String {
+pub fn render_object_define_property(
+  key: &str,
+  value: &str,
+  generated_code: &GeneratedCodeOptions,
+) -> String {
+  if generated_code.arrow_functions {
+    return concat_string!(
+      "Object.defineProperty(exports, '",
+      key,
+      "', {
+  enumerable: true,
+  get: () => ",
+      value,
+      "
+});"
+    );
+  }
   concat_string!(
     "Object.defineProperty(exports, '",
     key,
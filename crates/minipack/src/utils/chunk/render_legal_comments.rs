@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use minipack_common::{AssetIdx, LegalComments};
+use minipack_utils::concat_string;
+use oxc_index::IndexVec;
+
+use crate::types::IndexInstantiatedChunks;
+
+/// With `eof`, legal comments are appended to the end of each chunk. With `linked`, the chunk
+/// references `<chunk>.LEGAL.txt` instead, whose content is returned for each chunk.
+///
+/// This runs after minification, which would remove them, and before hashing, so `[hash]` covers
+/// them as well.
+pub fn render_legal_comments(
+  instantiated_chunks: &mut IndexInstantiatedChunks,
+  mode: LegalComments,
+) -> IndexVec<AssetIdx, Option<String>> {
+  instantiated_chunks
+    .iter_mut()
+    .map(|chunk| {
+      let legal_comments = std::mem::take(&mut chunk.legal_comments);
+      if legal_comments.is_empty() {
+        return None;
+      }
+      if !chunk.content.ends_with('\n') {
+        chunk.content.push('\n');
+      }
+      match mode {
+        LegalComments::Eof => {
+          for comment in legal_comments {
+            chunk.content.push_str(&comment);
+            chunk.content.push('\n');
+          }
+          None
+        }
+        LegalComments::Linked => {
+          let filename = Path::new(chunk.preliminary_filename.as_str())
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+          chunk.content.push_str(&concat_string!(
+            "/*! For license information please see ",
+            filename,
+            ".LEGAL.txt */\n"
+          ));
+          Some(concat_string!(legal_comments.join("\n\n"), "\n"))
+        }
+        LegalComments::None | LegalComments::Inline => None,
+      }
+    })
+    .collect()
+}
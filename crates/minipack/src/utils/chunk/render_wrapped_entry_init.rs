@@ -0,0 +1,21 @@
+use minipack_utils::concat_string;
+
+use crate::types::generator::GenerateContext;
+
+/// A wrapped entry module is only executed by `init_foo()`, which is called at the end of its entry
+/// chunk, after all the modules of the chunk are declared.
+pub fn render_wrapped_entry_init(ctx: &GenerateContext<'_>) -> Option<String> {
+  let entry_idx = ctx.chunk.entry_module_idx()?;
+  let linking_info = &ctx.link_stage_output.metadata[entry_idx];
+  let wrapper_ref = linking_info.wrapper_ref?;
+  let init_fn_name = ctx.finalized_string_pattern_for_symbol_ref(
+    wrapper_ref,
+    ctx.chunk_idx,
+    &ctx.chunk.canonical_names,
+  );
+  Some(if linking_info.is_tla_or_contains_tla_dependency {
+    concat_string!("await ", init_fn_name, "();\n")
+  } else {
+    concat_string!(init_fn_name, "();\n")
+  })
+}
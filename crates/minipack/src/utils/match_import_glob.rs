@@ -0,0 +1,49 @@
+use std::path::{Path, PathBuf};
+
+use minipack_fs::FileSystem;
+use sugar_path::SugarPath;
+
+/// Files matched by the glob of an `import()` with a variable path, as specifiers relative to the
+/// importer, e.g. `./locales/en.js` for `./locales/*.js`. The importer itself is never matched.
+pub fn match_import_glob(fs: &dyn FileSystem, importer: &Path, pattern: &str) -> Vec<String> {
+  let Some(importer_dir) = importer.parent() else {
+    return vec![];
+  };
+  // Only the directory before the first wildcard needs to be walked, as deep as the pattern goes.
+  let static_len = pattern.find('*').unwrap_or(pattern.len());
+  let base_len = pattern[..static_len].rfind('/').unwrap_or(0);
+  let depth = pattern[base_len + 1..].matches('/').count();
+
+  let mut files = vec![];
+  collect_files(fs, &importer_dir.join(&pattern[..base_len]).normalize(), depth, &mut files);
+
+  let mut specifiers = files
+    .into_iter()
+    .filter(|file| file != importer)
+    .filter_map(|file| {
+      let relative = file.relative(importer_dir).to_slash_lossy().into_owned();
+      let specifier = if relative.starts_with("../") { relative } else { format!("./{relative}") };
+      glob_match::glob_match(pattern, &specifier).then_some(specifier)
+    })
+    .collect::<Vec<_>>();
+  specifiers.sort_unstable();
+  specifiers
+}
+
+fn collect_files(fs: &dyn FileSystem, dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+  let Ok(entries) = fs.read_dir(dir) else {
+    return;
+  };
+  for path in entries {
+    let Ok(metadata) = fs.metadata(&path) else {
+      continue;
+    };
+    if metadata.is_dir() {
+      if depth > 0 && path.file_name().is_some_and(|name| name != "node_modules") {
+        collect_files(fs, &path, depth - 1, files);
+      }
+    } else if depth == 0 {
+      files.push(path);
+    }
+  }
+}
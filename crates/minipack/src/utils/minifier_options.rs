@@ -0,0 +1,29 @@
+use minipack_common::MinifyOptions;
+use oxc::minifier::{
+  CompressOptions, CompressOptionsKeepNames, MangleOptions, MangleOptionsKeepNames, MinifierOptions,
+};
+
+/// The minifier runs on the finalized AST of each module, before the chunks are rendered and their
+/// content hashes computed. Top-level names are shared by the modules of a chunk, they are left to
+/// the renamer and never mangled.
+pub fn minifier_options(options: &MinifyOptions) -> MinifierOptions {
+  MinifierOptions {
+    mangle: options.mangle.then(|| MangleOptions {
+      top_level: false,
+      keep_names: MangleOptionsKeepNames {
+        function: options.keep_names,
+        class: options.keep_names,
+      },
+      ..MangleOptions::default()
+    }),
+    compress: options.compress.then_some(CompressOptions {
+      target: options.target,
+      keep_names: CompressOptionsKeepNames {
+        function: options.keep_names,
+        class: options.keep_names,
+      },
+      drop_debugger: options.drop_debugger,
+      drop_console: options.drop_console,
+    }),
+  }
+}
@@ -1,9 +1,16 @@
+pub mod bundle_dts;
 pub mod chunk;
 pub mod ecmascript;
 pub mod renamer;
 
+mod match_import_glob;
+mod minifier_options;
 mod normalize_bundler_options;
 mod parse_to_ecma_ast;
+mod write_output;
 
+pub use match_import_glob::match_import_glob;
+pub use minifier_options::minifier_options;
 pub use normalize_bundler_options::normalize_bundler_options;
-pub use parse_to_ecma_ast::parse_to_ecma_ast;
+pub use parse_to_ecma_ast::{ecma_source_type, generate_dts, parse_to_ecma_ast, to_ecma_source};
+pub use write_output::write_output;
@@ -1,29 +1,96 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
-use minipack_common::{BundlerOptions, NormalizedBundlerOptions, OutputFormat, Platform};
+use minipack_common::{
+  BundlerOptions, MinifyOptions, NormalizedBundlerOptions, OutputFormat, Platform,
+};
+use minipack_error::BuildResult;
+
+pub fn normalize_bundler_options(
+  raw_options: BundlerOptions,
+) -> BuildResult<Arc<NormalizedBundlerOptions>> {
+  if raw_options.dir.is_some() && raw_options.file.is_some() {
+    Err(anyhow::anyhow!(
+      "Invalid value for `options.file` - `options.file` and `options.dir` can't be used together."
+    ))?;
+  }
 
-pub fn normalize_bundler_options(raw_options: BundlerOptions) -> Arc<NormalizedBundlerOptions> {
   let cwd =
     raw_options.cwd.unwrap_or_else(|| std::env::current_dir().expect("Failed to get current dir"));
 
-  let dir = raw_options.dir.unwrap_or_else(|| "dist".to_string());
+  let cache_dir = raw_options.cache_dir.map(|dir| cwd.join(dir));
+  // With `file`, the chunk is written to the directory of `file` under its file name.
+  let file_dir = raw_options.file.as_ref().map(|file| {
+    Path::new(file).parent().map(|parent| parent.to_string_lossy().into_owned()).unwrap_or_default()
+  });
+  let dir = raw_options.dir.or(file_dir).unwrap_or_else(|| "dist".to_string());
+  let entry_filenames = raw_options
+    .file
+    .as_ref()
+    .and_then(|file| Path::new(file).file_name())
+    .map(|file_name| file_name.to_string_lossy().into_owned())
+    .or(raw_options.entry_filenames)
+    .unwrap_or_else(|| "[name].js".to_string());
   let format = raw_options.format.unwrap_or_default();
+  let keep_names = raw_options.keep_names.unwrap_or_default();
   let platform = raw_options.platform.unwrap_or(match format {
     OutputFormat::Cjs => Platform::Node,
-    OutputFormat::Esm => Platform::Browser,
+    OutputFormat::Esm | OutputFormat::Iife => Platform::Browser,
   });
 
-  Arc::new(NormalizedBundlerOptions {
+  Ok(Arc::new(NormalizedBundlerOptions {
     // --- Input
     cwd,
     input: raw_options.input.unwrap_or_default(),
+    stdin: raw_options.stdin,
     platform,
     // --- Output
     dir,
+    file: raw_options.file,
+    empty_out_dir: raw_options.empty_out_dir.unwrap_or_default(),
+    stdout: raw_options.stdout.unwrap_or_default(),
     format,
-    entry_filenames: raw_options.entry_filenames.unwrap_or_else(|| "[name].js".to_string()),
+    entry_filenames,
     chunk_filenames: raw_options.chunk_filenames.unwrap_or_else(|| "[name]-[hash].js".to_string()),
+    inline_dynamic_imports: raw_options.inline_dynamic_imports.unwrap_or_default(),
+    strict_execution_order: raw_options.strict_execution_order.unwrap_or_default(),
+    manifest: raw_options.manifest,
+    integrity: raw_options.integrity,
+    legal_comments: raw_options.legal_comments.unwrap_or_default(),
+    exports: raw_options.exports.unwrap_or_default(),
+    es_module: raw_options.es_module.unwrap_or_default(),
+    interop: raw_options.interop.unwrap_or_default(),
+    dts: raw_options.dts.unwrap_or_default(),
+    size_limits: raw_options.size_limits.unwrap_or_default(),
+    runtime: raw_options.runtime.unwrap_or_default(),
+    keep_names,
+    generated_code: raw_options.generated_code.unwrap_or_default(),
+    hoist_transitive_imports: raw_options.hoist_transitive_imports.unwrap_or(true),
+    preload_dynamic_deps: raw_options.preload_dynamic_deps.unwrap_or_default(),
     // --- Enhance
-    minify: raw_options.minify.unwrap_or_default(),
-  })
+    minify: raw_options
+      .minify
+      .map(|minify| MinifyOptions { keep_names: minify.keep_names || keep_names, ..minify }),
+    cache_dir,
+    unused_code: raw_options.unused_code,
+    hmr: raw_options.hmr.unwrap_or_default(),
+    hmr_reused_modules: raw_options.hmr_reused_modules.unwrap_or_default(),
+    ci: raw_options
+      .ci
+      .unwrap_or_else(|| std::env::var("CI").is_ok_and(|ci| !ci.is_empty() && ci != "false")),
+  }))
+}
+
+#[test]
+fn test_dir_and_file_are_exclusive() {
+  let options = |dir: Option<&str>, file: Option<&str>| {
+    normalize_bundler_options(BundlerOptions {
+      dir: dir.map(ToString::to_string),
+      file: file.map(ToString::to_string),
+      ..BundlerOptions::default()
+    })
+  };
+
+  assert!(options(Some("dist"), Some("dist/main.js")).is_err());
+  assert_eq!(options(None, Some("out/main.js")).unwrap().dir, "out");
+  assert_eq!(options(Some("out"), None).unwrap().dir, "out");
 }
@@ -1,11 +1,15 @@
 use std::path::Path;
 
 use arcstr::ArcStr;
-use minipack_common::ModuleType;
+use minipack_common::{ModuleDts, ModuleType};
 use minipack_ecmascript::{EcmaAst, EcmaCompiler};
 use minipack_error::BuildResult;
+use minipack_utils::concat_string;
 use oxc::{
+  ast::ast::{Program, Statement},
   ast_visit::VisitMut as _,
+  codegen::Codegen,
+  isolated_declarations::{IsolatedDeclarations, IsolatedDeclarationsOptions},
   minifier::{CompressOptions, Compressor},
   semantic::{Scoping, SemanticBuilder},
   span::SourceType as OxcSourceType,
@@ -14,18 +18,34 @@ use oxc::{
 
 use crate::scan_stage::ast_scanner::PreProcessor;
 
+/// The source that is parsed for a module of `module_type`.
+pub fn to_ecma_source(source: String, module_type: &ModuleType) -> ArcStr {
+  match module_type {
+    ModuleType::Empty => ArcStr::new(),
+    // Html files imported by modules are their source text, html entries don't get here.
+    ModuleType::Html => {
+      let text = serde_json::to_string(&source).expect("string should be serializable");
+      concat_string!("export default ", text, ";").into()
+    }
+    _ => source.into(),
+  }
+}
+
+/// The source type a module of `module_type` is parsed with.
+pub fn ecma_source_type(module_type: &ModuleType) -> OxcSourceType {
+  let default = OxcSourceType::default().with_module(true);
+  if let ModuleType::Ts = module_type { default.with_typescript(true) } else { default }
+}
+
+/// Parse and transform a module into JavaScript. With `dts`, the declarations of TypeScript modules
+/// are generated before their types are stripped.
 pub fn parse_to_ecma_ast(
-  source: String,
+  source: ArcStr,
   source_path: &Path,
   module_type: &ModuleType,
-) -> BuildResult<(EcmaAst, Scoping)> {
-  let source = if *module_type == ModuleType::Empty { ArcStr::new() } else { source.into() };
-  let oxc_source_type = {
-    let default = OxcSourceType::default().with_module(true);
-    if let ModuleType::Ts = module_type { default.with_typescript(true) } else { default }
-  };
-
-  let mut ast = EcmaCompiler::parse(&source, oxc_source_type)?;
+  dts: bool,
+) -> BuildResult<(EcmaAst, Scoping, Option<ModuleDts>)> {
+  let mut ast = EcmaCompiler::parse(&source, ecma_source_type(module_type))?;
 
   let semantic_ret = ast.program.with_mut(|fields| SemanticBuilder::new().build(fields.program));
   if !semantic_ret.errors.is_empty() {
@@ -35,6 +55,12 @@ pub fn parse_to_ecma_ast(
   let stats = semantic_ret.semantic.stats();
   let mut scoping = semantic_ret.semantic.into_scoping();
 
+  let module_dts = if dts && matches!(module_type, ModuleType::Ts) {
+    Some(isolated_declarations(&mut ast, source_path)?)
+  } else {
+    None
+  };
+
   if matches!(module_type, ModuleType::Ts) {
     let transformer_return = ast.program.with_mut(|fields| {
       Transformer::new(fields.allocator, source_path, &TransformOptions::from(ESTarget::ESNext))
@@ -60,5 +86,46 @@ pub fn parse_to_ecma_ast(
     SemanticBuilder::new().with_stats(stats).build(fields.program).semantic.into_scoping()
   });
 
-  Ok((ast, scoping))
+  Ok((ast, scoping, module_dts))
+}
+
+/// Generate the declarations of a module that is only imported for its types, it isn't transformed
+/// into JavaScript since it isn't part of the module graph.
+pub fn generate_dts(source: ArcStr, source_path: &Path) -> BuildResult<ModuleDts> {
+  let source_type = OxcSourceType::default().with_module(true).with_typescript(true);
+  let mut ast = EcmaCompiler::parse(&source, source_type)?;
+  isolated_declarations(&mut ast, source_path)
+}
+
+fn isolated_declarations(ast: &mut EcmaAst, source_path: &Path) -> BuildResult<ModuleDts> {
+  let ret = ast.program.with_mut(|fields| {
+    IsolatedDeclarations::new(fields.allocator, IsolatedDeclarationsOptions::default())
+      .build(fields.program)
+  });
+  if !ret.errors.is_empty() {
+    Err(anyhow::anyhow!(
+      "Failed to generate declarations of {}, got: {:?}",
+      source_path.display(),
+      ret.errors
+    ))?;
+  }
+  Ok(ModuleDts {
+    code: Codegen::new().build(&ret.program).code,
+    specifiers: collect_dts_specifiers(&ret.program),
+    deps: vec![],
+  })
+}
+
+fn collect_dts_specifiers(program: &Program) -> Vec<String> {
+  program
+    .body
+    .iter()
+    .filter_map(|stmt| match stmt {
+      Statement::ImportDeclaration(decl) => Some(&decl.source),
+      Statement::ExportNamedDeclaration(decl) => decl.source.as_ref(),
+      Statement::ExportAllDeclaration(decl) => Some(&decl.source),
+      _ => None,
+    })
+    .map(|source| source.value.to_string())
+    .collect()
 }
@@ -3,10 +3,27 @@ use minipack_common::{
 };
 use minipack_utils::concat_string;
 use minipack_utils::rstr::{Rstr, ToRstr};
+use oxc::semantic::NodeId;
 use oxc::syntax::keyword::{GLOBAL_OBJECTS, RESERVED_KEYWORDS};
 use rustc_hash::{FxHashMap, FxHashSet};
 use std::collections::hash_map::Entry;
 
+/// Names that symbols are never given, they are renamed instead, e.g. keywords and globals.
+pub fn reserved_names(format: OutputFormat) -> impl Iterator<Item = &'static str> {
+  let manual_reserved: &[&'static str] = if matches!(format, OutputFormat::Cjs) {
+    &["module", "require", "__filename", "__dirname", "exports"]
+  } else {
+    &[]
+  };
+
+  manual_reserved
+    .iter()
+    .chain(["Object", "Promise"].iter())
+    .chain(RESERVED_KEYWORDS.iter())
+    .chain(GLOBAL_OBJECTS.iter())
+    .copied()
+}
+
 /// Manages symbol renaming across modules to prevent name collisions.
 /// It tracks used names and generates unique names for symbols.
 #[derive(Debug)]
@@ -37,19 +54,7 @@ impl<'name> Renamer<'name> {
     symbol_db: &'name SymbolRefDb,
     format: OutputFormat,
   ) -> Self {
-    let manual_reserved = if matches!(format, OutputFormat::Cjs) {
-      vec!["module", "require", "__filename", "__dirname", "exports"]
-    } else {
-      vec![]
-    };
-
-    let used_canonical_names = manual_reserved
-      .iter()
-      .chain(["Object", "Promise"].iter())
-      .chain(RESERVED_KEYWORDS.iter())
-      .chain(GLOBAL_OBJECTS.iter())
-      .map(|s| (Rstr::new(s), 0))
-      .collect();
+    let used_canonical_names = reserved_names(format).map(|s| (Rstr::new(s), 0)).collect();
 
     let module_used_names = base_module_index
       .map(|index| {
@@ -145,7 +150,11 @@ impl<'name> Renamer<'name> {
       scoping
         .symbol_ids()
         .zip(scoping.symbol_names())
-        .filter(|(symbol_id, _)| !root_symbol_ids.contains(symbol_id))
+        // Facade symbols aren't declared in the source code, they are renamed by themselves.
+        .filter(|(symbol_id, _)| {
+          !root_symbol_ids.contains(symbol_id)
+            && scoping.symbol_declaration(*symbol_id) != NodeId::DUMMY
+        })
         .map(|(_, name)| name)
         .collect::<FxHashSet<&str>>()
     }
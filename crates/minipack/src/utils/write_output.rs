@@ -0,0 +1,121 @@
+use std::{
+  io,
+  path::{Path, PathBuf},
+};
+
+use minipack_common::{NormalizedBundlerOptions, OutputAsset, WriteReport};
+use minipack_error::BuildResult;
+use minipack_fs::FileSystem;
+use rustc_hash::FxHashSet;
+use sugar_path::SugarPath;
+
+/// Writes the assets to the output directory. Files whose content is unchanged are left untouched,
+/// so that watchers of the output directory aren't triggered for nothing.
+pub fn write_output(
+  fs: &dyn FileSystem,
+  options: &NormalizedBundlerOptions,
+  assets: &[OutputAsset],
+) -> BuildResult<WriteReport> {
+  let dist = options.cwd.join(&options.dir).normalize();
+  if options.empty_out_dir {
+    // Only the output directory of the project is emptied, not one that happens to be anywhere on
+    // the disk, e.g. a typo of `dir` like `../`.
+    let cwd = options.cwd.normalize();
+    if dist == cwd || !dist.starts_with(&cwd) {
+      return Err(
+        anyhow::anyhow!(
+          "Invalid value for `options.empty_out_dir` - the output directory {:?} isn't inside the working directory {:?}.",
+          dist,
+          cwd
+        )
+        .into(),
+      );
+    }
+  }
+
+  fs.create_dir_all(&dist)
+    .map_err(|err| anyhow::anyhow!("Couldn't create output directory: {:?}", dist).context(err))?;
+
+  let mut report = WriteReport::default();
+
+  if options.empty_out_dir {
+    let emitted = assets.iter().map(|asset| dist.join(&asset.filename)).collect::<FxHashSet<_>>();
+    remove_stale_files(fs, &dist, &dist, &emitted, &mut report.deleted)
+      .map_err(|err| anyhow::anyhow!("Couldn't empty output directory: {:?}", dist).context(err))?;
+    report.deleted.sort_unstable();
+  }
+
+  for asset in assets {
+    let filename = dist.join(&asset.filename);
+    if fs.read(&filename).is_ok_and(|existing| existing == asset.content.as_bytes()) {
+      report.skipped.push(asset.filename.clone());
+      continue;
+    }
+    if let Some(parent) = filename.parent() {
+      fs.create_dir_all(parent)
+        .map_err(|err| anyhow::anyhow!("Couldn't create directory {parent:?}").context(err))?;
+    }
+    write_atomically(fs, &filename, asset.content.as_bytes())
+      .map_err(|err| anyhow::anyhow!("Failed to write file {filename:?}").context(err))?;
+    report.written.push(asset.filename.clone());
+  }
+
+  Ok(report)
+}
+
+/// Writes to a temporary file next to the target and renames it, so that readers never see a
+/// partially written file.
+fn write_atomically(fs: &dyn FileSystem, filename: &Path, content: &[u8]) -> io::Result<()> {
+  let file_name = filename.file_name().unwrap_or_default().to_string_lossy();
+  let temp = filename.with_file_name(format!(".{file_name}.{}.tmp", std::process::id()));
+  fs.write(&temp, content)?;
+  fs.rename(&temp, filename).inspect_err(|_| {
+    let _ = fs.remove_file(&temp);
+  })
+}
+
+/// Removes the files under `dir` that aren't emitted, and the directories left empty. Returns
+/// whether `dir` is empty afterwards.
+fn remove_stale_files(
+  fs: &dyn FileSystem,
+  dist: &Path,
+  dir: &Path,
+  emitted: &FxHashSet<PathBuf>,
+  deleted: &mut Vec<String>,
+) -> io::Result<bool> {
+  let mut is_empty = true;
+  for path in fs.read_dir(dir)? {
+    if fs.symlink_metadata(&path)?.is_dir() {
+      if remove_stale_files(fs, dist, &path, emitted, deleted)? {
+        fs.remove_dir(&path)?;
+      } else {
+        is_empty = false;
+      }
+    } else if emitted.contains(&path) {
+      is_empty = false;
+    } else {
+      fs.remove_file(&path)?;
+      deleted.push(path.relative(dist).to_slash_lossy().into_owned());
+    }
+  }
+  Ok(is_empty)
+}
+
+#[test]
+fn test_empty_out_dir_outside_of_cwd() {
+  use minipack_common::BundlerOptions;
+  use minipack_fs::OsFileSystem;
+
+  let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/unused_code");
+  for dir in ["..", "."] {
+    let options = super::normalize_bundler_options(BundlerOptions {
+      cwd: Some(cwd.clone()),
+      dir: Some(dir.to_string()),
+      empty_out_dir: Some(true),
+      ..BundlerOptions::default()
+    })
+    .unwrap();
+    // Nothing is written or removed.
+    assert!(write_output(&OsFileSystem, &options, &[]).is_err());
+  }
+}
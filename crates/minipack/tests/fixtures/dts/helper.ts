@@ -0,0 +1,7 @@
+interface Shape {
+  name: string;
+}
+
+export function helper(shape: Shape): string {
+  return shape.name;
+}
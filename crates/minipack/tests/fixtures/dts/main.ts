@@ -0,0 +1,16 @@
+import type { Shape } from './shapes';
+import * as t from './types';
+import type { Options } from 'external-lib';
+
+export { helper } from './helper';
+export type { Options };
+
+export function area(shape: Shape, unit: t.Unit): number {
+  return unit === 'cm' ? shape.size : 0;
+}
+
+export const config: t.Config = { name: 'main' };
+
+export default class {
+  size = 1;
+}
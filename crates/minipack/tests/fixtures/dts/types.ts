@@ -0,0 +1,5 @@
+export type Unit = 'cm' | 'px';
+
+export interface Config {
+  name: string;
+}
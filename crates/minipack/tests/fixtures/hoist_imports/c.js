@@ -0,0 +1,3 @@
+import { d } from './d.js';
+
+export const c = `c${d}`;
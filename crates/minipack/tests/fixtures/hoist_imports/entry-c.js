@@ -0,0 +1,3 @@
+import { c } from './c.js';
+
+console.log('entry-c', c);
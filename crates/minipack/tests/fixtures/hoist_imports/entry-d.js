@@ -0,0 +1,3 @@
+import { d } from './d.js';
+
+console.log('entry-d', d);
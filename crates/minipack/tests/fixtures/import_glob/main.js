@@ -0,0 +1,7 @@
+export function loadLocale(lang) {
+  return import(`./locales/${lang}.js`);
+}
+
+export function loadFallback(lang) {
+  return import('./locales/' + lang + '.js');
+}
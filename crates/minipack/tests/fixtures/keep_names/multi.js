@@ -0,0 +1,4 @@
+import { foo as imported } from './foo.js';
+
+function foo() {}
+console.log(imported, foo);
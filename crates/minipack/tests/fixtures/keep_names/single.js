@@ -0,0 +1,10 @@
+export function foo() {}
+export class Bar {}
+export const baz = () => {};
+
+// Shadowing `foo` doesn't rename the declaration of the entry.
+function inner() {
+  let foo = 1;
+  return foo;
+}
+console.log(inner());
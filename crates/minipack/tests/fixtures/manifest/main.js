@@ -0,0 +1,4 @@
+/*! main v1.0.0 | MIT */
+const worker = new Worker(new URL('./worker.js', import.meta.url), { type: 'module' });
+worker.postMessage('ping');
+import('./lazy.js').then(({ lazy }) => lazy());
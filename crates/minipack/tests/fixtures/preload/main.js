@@ -0,0 +1,3 @@
+import("./page.js");
+import("./other.js");
+import("./leaf.js");
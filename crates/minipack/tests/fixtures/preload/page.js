@@ -0,0 +1,2 @@
+import { shared } from "./shared.js";
+console.log(shared, "page");
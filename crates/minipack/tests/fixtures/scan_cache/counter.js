@@ -0,0 +1,9 @@
+import { unused } from './unused.js';
+
+export let count = 0;
+
+export function increment() {
+  count++;
+}
+
+increment();
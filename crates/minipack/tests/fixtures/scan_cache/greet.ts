@@ -0,0 +1,10 @@
+interface Options {
+  loud: boolean;
+}
+
+const name = 'greet';
+
+export function greet(who: string, options: Options = { loud: false }): string {
+  const message = `hello ${who} from ${name}`;
+  return options.loud ? message.toUpperCase() : message;
+}
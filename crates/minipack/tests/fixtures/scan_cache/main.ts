@@ -0,0 +1,7 @@
+import { greet } from './greet.ts';
+import { count } from './counter.js';
+
+const name: string = 'world';
+console.log(greet(name), count);
+
+export const lazy = () => import('./lazy.js');
@@ -0,0 +1,5 @@
+import { b } from './b.js';
+
+console.log('a', b());
+
+export const a = 'a';
@@ -0,0 +1,11 @@
+import { a } from './a.js';
+
+console.log('b');
+
+export function b() {
+  return 'b';
+}
+
+export function getA() {
+  return a;
+}
@@ -0,0 +1,5 @@
+import './setup.js';
+import { shared } from './shared.js';
+import { a } from './a.js';
+
+console.log('entry-a', shared, a);
@@ -0,0 +1,3 @@
+console.log('shared');
+
+export const shared = 'shared';
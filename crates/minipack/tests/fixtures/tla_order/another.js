@@ -0,0 +1,3 @@
+import { second } from './second.js';
+
+console.log(second);
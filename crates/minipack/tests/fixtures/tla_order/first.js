@@ -0,0 +1 @@
+export const first = await Promise.resolve('first');
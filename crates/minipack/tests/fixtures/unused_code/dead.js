@@ -0,0 +1 @@
+export const dead = true;
@@ -0,0 +1,11 @@
+export function helper(value) {
+  return `${value}!`;
+}
+
+export function format(value) {
+  return helper(value);
+}
+
+export function unused() {
+  return 'unused';
+}
@@ -0,0 +1,4 @@
+import { format } from './lib.js';
+import * as shapes from './shapes.js';
+
+console.log(format(shapes.square(2)), shapes);
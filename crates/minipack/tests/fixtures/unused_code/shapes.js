@@ -0,0 +1,7 @@
+export function square(size) {
+  return size * size;
+}
+
+export function circle(radius) {
+  return Math.PI * radius * radius;
+}
@@ -0,0 +1,3 @@
+export function reply(message) {
+  return `${message} from the worker`;
+}
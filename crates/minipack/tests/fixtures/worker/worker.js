@@ -0,0 +1,3 @@
+import { reply } from './reply.js';
+
+self.onmessage = (event) => self.postMessage(reply(event.data));
@@ -1,15 +1,33 @@
 use std::path::PathBuf;
 
 use clap::Args;
+use minipack::{ESTarget, SizeLimit};
 
-use crate::types::{output_format::OutputFormat, platform::Platform};
+use crate::types::{
+  es_module_flag::EsModuleFlag, graph_direction::GraphDirection, graph_format::GraphFormat,
+  integrity_algorithm::IntegrityAlgorithm, interop::Interop, legal_comments::LegalComments,
+  output_exports::OutputExports, output_format::OutputFormat, platform::Platform, runtime::Runtime,
+  size_limit::parse_size_limit,
+};
 
 #[derive(Args)]
 pub struct InputArgs {
+  /// Entry file(s), `-` reads the entry module from stdin like `--stdin`
+  #[clap(value_name = "ENTRY")]
+  pub entries: Vec<PathBuf>,
+
   /// Entry file(s)
   #[clap(long, action = clap::ArgAction::Append)]
   pub input: Option<Vec<PathBuf>>,
 
+  /// Read the entry module from stdin
+  #[clap(long)]
+  pub stdin: bool,
+
+  /// Path of the module read from stdin, used to resolve its imports and pick its loader
+  #[clap(long)]
+  pub stdin_filename: Option<String>,
+
   /// Bundler platform environment
   #[clap(long, short, long)]
   pub platform: Option<Platform>,
@@ -21,6 +39,18 @@ pub struct OutputArgs {
   #[clap(long, short = 'd')]
   pub dir: Option<String>,
 
+  /// Output file of a build with a single chunk, e.g. lib/index.cjs
+  #[clap(long, short = 'o', conflicts_with = "dir")]
+  pub file: Option<String>,
+
+  /// Remove files in the output directory that aren't part of the build, it must be inside the working directory
+  #[clap(long)]
+  pub empty_out_dir: bool,
+
+  /// Print the output to stdout instead of writing it, the build must produce a single chunk
+  #[clap(long, conflicts_with_all = ["dir", "file", "empty_out_dir"])]
+  pub stdout: bool,
+
   /// Output module format
   #[clap(long)]
   pub format: Option<OutputFormat>,
@@ -32,6 +62,82 @@ pub struct OutputArgs {
   /// Output chunk files, e.g. [name]-[hash].js
   #[clap(long)]
   pub chunk_filenames: Option<String>,
+
+  /// Inline dynamic imports into the entry chunk instead of splitting them
+  #[clap(long)]
+  pub inline_dynamic_imports: bool,
+
+  /// Keep the execution order of native ESM by lazily initializing modules when needed
+  #[clap(long)]
+  pub strict_execution_order: bool,
+
+  /// Emit a build manifest, e.g. manifest.json
+  #[clap(long)]
+  pub manifest: Option<String>,
+
+  /// Add subresource integrity hashes to the manifest
+  #[clap(long)]
+  pub integrity: Option<IntegrityAlgorithm>,
+
+  /// Where to emit legal comments, e.g. /*! ... */ or @license
+  #[clap(long)]
+  pub legal_comments: Option<LegalComments>,
+
+  /// How to expose the exports of entry chunks in cjs format
+  #[clap(long)]
+  pub exports: Option<OutputExports>,
+
+  /// When to define exports.__esModule in cjs format
+  #[clap(long)]
+  pub es_module: Option<EsModuleFlag>,
+
+  /// How to resolve default imports of external modules in cjs format
+  #[clap(long)]
+  pub interop: Option<Interop>,
+
+  /// Emit bundled TypeScript declarations of entries as [name].d.ts
+  #[clap(long)]
+  pub dts: bool,
+
+  /// Bundle the runtime helpers or import them from --runtime-specifier
+  #[clap(long)]
+  pub runtime: Option<Runtime>,
+
+  /// Where the external runtime is imported from
+  #[clap(long, default_value = "@minipack/runtime")]
+  pub runtime_specifier: String,
+
+  /// Emit the external runtime as a standalone file, e.g. runtime.js
+  #[clap(long)]
+  pub emit_runtime: Option<String>,
+
+  /// Keep the names of functions and classes renamed to avoid conflicts, also when minifying
+  #[clap(long)]
+  pub keep_names: bool,
+
+  /// Add Symbol.toStringTag of "Module" to namespace objects
+  #[clap(long)]
+  pub generated_code_symbols: bool,
+
+  /// Freeze namespace objects
+  #[clap(long)]
+  pub generated_code_freeze: bool,
+
+  /// Use const instead of var to bind imports and exports
+  #[clap(long)]
+  pub generated_code_const_bindings: bool,
+
+  /// Use arrow functions instead of function in generated code
+  #[clap(long)]
+  pub generated_code_arrow_functions: bool,
+
+  /// Only import the chunks a chunk depends on directly
+  #[clap(long)]
+  pub no_hoist_transitive_imports: bool,
+
+  /// Preload the static imports of dynamically imported esm chunks with <link rel="modulepreload">, or import them in parallel outside of browsers
+  #[clap(long)]
+  pub preload_dynamic_deps: bool,
 }
 
 #[derive(Args)]
@@ -40,7 +146,115 @@ pub struct EnhanceArgs {
   #[clap(long, short = 'm')]
   pub minify: bool,
 
+  /// Don't compress the minified code
+  #[clap(long)]
+  pub no_minify_compress: bool,
+
+  /// Don't mangle names in the minified code
+  #[clap(long)]
+  pub no_minify_mangle: bool,
+
+  /// Don't remove whitespace from the minified code
+  #[clap(long)]
+  pub no_minify_whitespace: bool,
+
+  /// Remove console.* calls when minifying
+  #[clap(long)]
+  pub drop_console: bool,
+
+  /// Remove debugger statements when minifying
+  #[clap(long)]
+  pub drop_debugger: bool,
+
+  /// Let the minifier drop the names of functions and classes
+  #[clap(long)]
+  pub no_minify_keep_names: bool,
+
+  /// Minify without syntax newer than the target, e.g. es2015
+  #[clap(long)]
+  pub minify_target: Option<ESTarget>,
+
+  /// Persist scan results in the directory to speed up later builds
+  #[clap(long)]
+  pub cache_dir: Option<String>,
+
+  /// Warn when output files exceed a size budget, e.g. "*.js=50kB:gzip"
+  #[clap(long, value_parser = parse_size_limit, action = clap::ArgAction::Append)]
+  pub size_limit: Vec<SizeLimit>,
+
+  /// Fail the build instead of warning when size budgets are exceeded. Defaults to $CI
+  #[clap(long)]
+  pub ci: bool,
+
+  /// Report unused exports and modules
+  #[clap(long)]
+  pub report_unused: bool,
+
+  /// Also report files under the directory that are never reached from any entry, e.g. src
+  #[clap(long)]
+  pub unused_source_root: Option<String>,
+
+  /// Write the unused code report as JSON, e.g. unused.json
+  #[clap(long)]
+  pub unused_report: Option<String>,
+
   /// Suppress bundling logs
   #[clap(long, short = 's')]
   pub silent: bool,
 }
+
+#[derive(Args)]
+pub struct ServeArgs {
+  #[clap(flatten)]
+  pub input: InputArgs,
+
+  /// Dev server host
+  #[clap(long, default_value = "localhost")]
+  pub host: String,
+
+  /// Dev server port
+  #[clap(long, default_value_t = 3000)]
+  pub port: u16,
+}
+
+#[derive(Args)]
+pub struct GraphArgs {
+  #[clap(flatten)]
+  pub input: InputArgs,
+
+  /// Graph output format
+  #[clap(long, default_value = "dot")]
+  pub format: GraphFormat,
+
+  /// Output module format of the build, the chunk graph depends on it
+  #[clap(long)]
+  pub output_format: Option<OutputFormat>,
+
+  /// Inline dynamic imports into the entry chunk instead of splitting them
+  #[clap(long)]
+  pub inline_dynamic_imports: bool,
+
+  /// Keep the execution order of native ESM by lazily initializing modules when needed
+  #[clap(long)]
+  pub strict_execution_order: bool,
+
+  /// Include the chunk graph, with modules grouped by chunk
+  #[clap(long)]
+  pub chunks: bool,
+
+  /// Only show the module with this stable id and its importees or importers, e.g. src/index.js
+  #[clap(long)]
+  pub focus: Option<String>,
+
+  /// Whether to follow the importees or the importers of the focused module
+  #[clap(long, default_value = "importees", requires = "focus")]
+  pub direction: GraphDirection,
+
+  /// How many edges away from the focused module to follow
+  #[clap(long, requires = "focus")]
+  pub depth: Option<usize>,
+
+  /// Write the graph to this file instead of stdout
+  #[clap(long, short = 'o')]
+  pub output: Option<PathBuf>,
+}
@@ -1,17 +1,31 @@
 mod args;
+mod render_graph;
 mod types;
 
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 use ansi_term::Colour;
-use args::{EnhanceArgs, InputArgs, OutputArgs};
-use clap::Parser;
+use args::{EnhanceArgs, GraphArgs, InputArgs, OutputArgs, ServeArgs};
+use clap::{Parser, Subcommand};
+use render_graph::render_graph;
+use types::runtime::Runtime;
 
-use minipack::{Bundler, BundlerOptions, OutputAsset};
+use minipack::{
+  Bundler, BundlerOptions, GeneratedCodeOptions, InputItem, MinifyOptions, OutputAsset,
+  OutputAssetKind, RuntimeOptions, StdinInput, UnusedCodeOptions, UnusedCodeReport, WriteReport,
+};
+use minipack_dev::{DevServer, DevServerEvent, DevServerOptions};
+use minipack_utils::{
+  compressed_size::{brotli_size, gzip_size},
+  rayon::{IntoParallelRefIterator, ParallelIterator},
+};
 
 #[derive(Parser)]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, args_conflicts_with_subcommands = true)]
 struct Commands {
+  #[command(subcommand)]
+  command: Option<Command>,
+
   #[clap(flatten)]
   input: InputArgs,
 
@@ -22,67 +36,199 @@ struct Commands {
   enhance: EnhanceArgs,
 }
 
-fn print_output_assets(outputs: Vec<OutputAsset>) {
-  let mut left = 0;
-  let mut right = 0;
-
-  let mut assets = Vec::with_capacity(outputs.len());
-
-  for output in outputs {
-    let size = format!("{:.2}", output.content.len() as f64 / 1024.0);
-
-    if size.len() > right {
-      right = size.len();
-    }
+#[derive(Subcommand)]
+enum Command {
+  /// Start a dev server with hot module replacement
+  Serve(ServeArgs),
+  /// Export the module graph as DOT, Mermaid or JSON without generating any output
+  Graph(GraphArgs),
+}
 
-    if output.filename.len() > left {
-      left = output.filename.len()
-    }
+fn print_output_assets(outputs: Vec<OutputAsset>) {
+  let mut assets = outputs
+    .par_iter()
+    .map(|output| {
+      let content = output.content.as_bytes();
+      let asset_type = match output.kind {
+        OutputAssetKind::Entry => "entry",
+        OutputAssetKind::Common => "common",
+        OutputAssetKind::Asset => "asset",
+      };
+      let sizes = [content.len(), gzip_size(content), brotli_size(content)]
+        .map(|size| format!("{:.2}", size as f64 / 1024.0));
+      (content.len(), output.filename.as_str(), asset_type, sizes)
+    })
+    .collect::<Vec<_>>();
+  // The largest files come last, so they are right above the summary.
+  assets.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
 
-    assets.push((output.filename, size, true));
-  }
+  let left = assets.iter().map(|(_, filename, _, _)| filename.len()).max().unwrap_or_default();
+  let kind = assets.iter().map(|(_, _, kind, _)| kind.len()).max().unwrap_or_default();
+  let right = [0, 1, 2].map(|i| assets.iter().map(|asset| asset.3[i].len()).max().unwrap_or(0));
 
   let dim = Colour::White.dimmed();
   let color = Colour::Cyan;
 
-  for (filename, size, is_chunk) in assets {
-    let asset_type = if is_chunk { "chunk" } else { "asset" };
-    let filename_len = filename.len();
-
+  for (_, filename, asset_type, [size, gzip, brotli]) in assets {
     println!(
-      "{}{}{:left$} {}{}{:right$}{} kB",
+      "{}{}{:left$} {}{:kind$}{}{:>size_width$} kB{}{:>gzip_width$} kB{}{:>brotli_width$} kB",
       dim.paint("<DIR>/"),
       color.paint(filename),
       "",
       dim.paint(asset_type),
-      dim.paint(" │ size: "),
       "",
+      dim.paint(" │ size: "),
       size,
-      left = left - filename_len,
-      right = right - size.len()
+      dim.paint(" │ gzip: "),
+      gzip,
+      dim.paint(" │ brotli: "),
+      brotli,
+      left = left - filename.len(),
+      kind = kind - asset_type.len(),
+      size_width = right[0],
+      gzip_width = right[1],
+      brotli_width = right[2],
     )
   }
 }
 
+fn print_write_report(report: &WriteReport) {
+  let dim = Colour::White.dimmed();
+
+  println!();
+  for file in &report.deleted {
+    println!("{} {}", dim.paint("Deleted"), Colour::Cyan.paint(file));
+  }
+  println!(
+    "{}",
+    dim.paint(format!(
+      "{} written, {} unchanged, {} deleted",
+      report.written.len(),
+      report.skipped.len(),
+      report.deleted.len()
+    ))
+  );
+}
+
+fn print_unused_code(report: &UnusedCodeReport) {
+  let dim = Colour::White.dimmed();
+  let color = Colour::Cyan;
+
+  println!();
+  if report.is_empty() {
+    println!("{}", dim.paint("No unused code found."));
+    return;
+  }
+
+  for unused_exports in &report.unused_exports {
+    println!(
+      "{} {} {}",
+      dim.paint("Unused exports in"),
+      color.paint(&unused_exports.module),
+      unused_exports.names.join(", ")
+    );
+  }
+  for module in &report.unused_modules {
+    println!("{} {}", dim.paint("Unused module"), color.paint(module));
+  }
+  for file in &report.unreachable_files {
+    println!("{} {}", dim.paint("Unreachable file"), color.paint(file));
+  }
+}
+
 #[tokio::main]
 async fn main() {
   let args = Commands::parse();
-  let InputArgs { input, platform } = args.input;
-  let input = input.map(|files| files.iter().map(|p| p.to_string_lossy().into()).collect());
 
-  let mut bundler = Bundler::new(BundlerOptions {
+  match args.command {
+    Some(Command::Serve(args)) => return serve(args).await,
+    Some(Command::Graph(args)) => return graph(args).await,
+    None => {}
+  }
+
+  let stdin = read_stdin(&args.input);
+  let input = input_items(&args.input);
+
+  let bundler = Bundler::new(BundlerOptions {
     cwd: None,
     input,
-    platform: platform.map(Into::into),
+    stdin,
+    platform: args.input.platform.map(Into::into),
     dir: args.output.dir,
+    file: args.output.file,
+    empty_out_dir: Some(args.output.empty_out_dir),
+    stdout: Some(args.output.stdout),
     format: args.output.format.map(Into::into),
     entry_filenames: args.output.entry_filenames,
     chunk_filenames: args.output.chunk_filenames,
-    minify: Some(args.enhance.minify),
+    inline_dynamic_imports: Some(args.output.inline_dynamic_imports),
+    strict_execution_order: Some(args.output.strict_execution_order),
+    manifest: args.output.manifest,
+    integrity: args.output.integrity.map(Into::into),
+    legal_comments: args.output.legal_comments.map(Into::into),
+    exports: args.output.exports.map(Into::into),
+    es_module: args.output.es_module.map(Into::into),
+    interop: args.output.interop.map(Into::into),
+    dts: Some(args.output.dts),
+    size_limits: Some(args.enhance.size_limit),
+    runtime: args.output.runtime.map(|runtime| match runtime {
+      Runtime::Inline => RuntimeOptions::Inline,
+      Runtime::External => RuntimeOptions::External {
+        specifier: args.output.runtime_specifier,
+        filename: args.output.emit_runtime,
+      },
+    }),
+    keep_names: Some(args.output.keep_names),
+    generated_code: Some(GeneratedCodeOptions {
+      symbols: args.output.generated_code_symbols,
+      freeze: args.output.generated_code_freeze,
+      const_bindings: args.output.generated_code_const_bindings,
+      arrow_functions: args.output.generated_code_arrow_functions,
+    }),
+    hoist_transitive_imports: Some(!args.output.no_hoist_transitive_imports),
+    preload_dynamic_deps: Some(args.output.preload_dynamic_deps),
+    minify: args.enhance.minify.then(|| MinifyOptions {
+      compress: !args.enhance.no_minify_compress,
+      mangle: !args.enhance.no_minify_mangle,
+      whitespace: !args.enhance.no_minify_whitespace,
+      drop_console: args.enhance.drop_console,
+      drop_debugger: args.enhance.drop_debugger,
+      keep_names: !args.enhance.no_minify_keep_names,
+      target: args.enhance.minify_target.unwrap_or_default(),
+    }),
+    cache_dir: args.enhance.cache_dir,
+    unused_code: (args.enhance.report_unused
+      || args.enhance.unused_source_root.is_some()
+      || args.enhance.unused_report.is_some())
+    .then_some(UnusedCodeOptions {
+      source_root: args.enhance.unused_source_root,
+      filename: args.enhance.unused_report,
+    }),
+    hmr: None,
+    hmr_reused_modules: None,
+    ci: args.enhance.ci.then_some(true),
   });
 
   let start = Instant::now();
-  match bundler.build(true).await {
+  let output = match bundler {
+    Ok(mut bundler) => bundler.build(true).await,
+    Err(errors) => Err(errors),
+  };
+  match output {
+    // Only the code goes to stdout so that it can be piped, everything else goes to stderr.
+    Ok(output) if args.output.stdout => {
+      if !args.enhance.silent {
+        for warning in output.warnings {
+          eprintln!("{} {}", Colour::Yellow.paint("Warning:"), warning);
+        }
+      }
+      // The bundler makes sure that there is a single chunk.
+      if let Some(chunk) =
+        output.assets.iter().find(|asset| !matches!(asset.kind, OutputAssetKind::Asset))
+      {
+        print!("{}", chunk.content);
+      }
+    }
     Ok(output) => {
       if !args.enhance.silent {
         // Print warnings
@@ -94,6 +240,14 @@ async fn main() {
         if !output.assets.is_empty() {
           print_output_assets(output.assets);
         }
+
+        if let Some(report) = output.write_report {
+          print_write_report(&report);
+        }
+
+        if let Some(report) = output.unused_code {
+          print_unused_code(&report);
+        }
       }
 
       let elapsed = format!("{:.2} ms", start.elapsed().as_secs_f64() * 1000.0);
@@ -101,8 +255,128 @@ async fn main() {
     }
     Err(errors) => {
       for error in &*errors {
-        println!("{} {}", Colour::Red.paint("Error:"), error);
+        eprintln!("{} {}", Colour::Red.paint("Error:"), error);
       }
+      std::process::exit(1);
+    }
+  }
+}
+
+fn reads_stdin(args: &InputArgs) -> bool {
+  args.stdin || args.entries.iter().any(|entry| entry.as_os_str() == "-")
+}
+
+/// The entries given as positional arguments and with `--input`, except `-` for stdin.
+fn input_items(args: &InputArgs) -> Option<Vec<InputItem>> {
+  let items = args
+    .entries
+    .iter()
+    .filter(|entry| entry.as_os_str() != "-")
+    .chain(args.input.iter().flatten())
+    .map(|entry| entry.to_string_lossy().into())
+    .collect::<Vec<_>>();
+  (!items.is_empty()).then_some(items)
+}
+
+/// The entry read from stdin with `-` or `--stdin`.
+fn read_stdin(args: &InputArgs) -> Option<StdinInput> {
+  if !reads_stdin(args) {
+    return None;
+  }
+  match std::io::read_to_string(std::io::stdin()) {
+    Ok(contents) => Some(StdinInput { contents, filename: args.stdin_filename.clone() }),
+    Err(err) => {
+      eprintln!("{} Failed to read stdin: {}", Colour::Red.paint("Error:"), err);
+      std::process::exit(1);
     }
   }
 }
+
+async fn graph(args: GraphArgs) {
+  let stdin = read_stdin(&args.input);
+
+  let bundler = Bundler::new(BundlerOptions {
+    input: input_items(&args.input),
+    stdin,
+    platform: args.input.platform.map(Into::into),
+    // The chunk graph depends on these, so they are the same as for the build.
+    format: args.output_format.map(Into::into),
+    inline_dynamic_imports: Some(args.inline_dynamic_imports),
+    strict_execution_order: Some(args.strict_execution_order),
+    ..Default::default()
+  });
+
+  let graph = match bundler {
+    Ok(mut bundler) => bundler.graph(args.chunks).await,
+    Err(errors) => Err(errors),
+  };
+  let graph = match graph {
+    Ok(graph) => graph,
+    Err(errors) => {
+      for error in &*errors {
+        eprintln!("{} {}", Colour::Red.paint("Error:"), error);
+      }
+      std::process::exit(1);
+    }
+  };
+
+  let graph = match &args.focus {
+    Some(module) => graph.focus(module, args.direction.into(), args.depth).unwrap_or_else(|| {
+      eprintln!("{} Module {:?} is not in the graph.", Colour::Red.paint("Error:"), module);
+      std::process::exit(1);
+    }),
+    None => graph,
+  };
+
+  let content = render_graph(&graph, &args.format);
+  match args.output {
+    Some(path) => {
+      if let Err(err) = std::fs::write(&path, content) {
+        eprintln!("{} Failed to write {}: {}", Colour::Red.paint("Error:"), path.display(), err);
+        std::process::exit(1);
+      }
+    }
+    None => print!("{content}"),
+  }
+}
+
+fn print_dev_event(event: DevServerEvent) {
+  match event {
+    DevServerEvent::Listening { url } => {
+      println!("\n  {} {}\n", Colour::Green.paint("➜ Local:"), Colour::Cyan.paint(url));
+    }
+    DevServerEvent::Built { elapsed, warnings } => {
+      for warning in warnings {
+        println!("{} {}", Colour::Yellow.paint("Warning:"), warning);
+      }
+      let elapsed = format!("{:.2} ms", elapsed.as_secs_f64() * 1000.0);
+      println!("{} Built in {}", Colour::Green.paint("✔"), Colour::White.bold().paint(elapsed));
+    }
+    DevServerEvent::FullReload => println!("{} page reload", Colour::Green.paint("hmr")),
+    DevServerEvent::Update { accepted } => {
+      println!("{} update {}", Colour::Green.paint("hmr"), accepted);
+    }
+    DevServerEvent::Error(err) => eprintln!("{} {}", Colour::Red.paint("Error:"), err),
+  }
+}
+
+async fn serve(args: ServeArgs) {
+  // The dev server rebuilds when the entry files change, which stdin can't.
+  if reads_stdin(&args.input) {
+    eprintln!("{} `serve` can't read the entry module from stdin.", Colour::Red.paint("Error:"));
+    std::process::exit(1);
+  }
+
+  let server = DevServer::new(
+    BundlerOptions {
+      input: input_items(&args.input),
+      platform: args.input.platform.map(Into::into),
+      ..Default::default()
+    },
+    DevServerOptions { host: args.host, port: args.port, on_event: Arc::new(print_dev_event) },
+  );
+
+  if let Err(err) = server.serve().await {
+    println!("{} {}", Colour::Red.paint("Error:"), err);
+  }
+}
@@ -0,0 +1,153 @@
+use std::fmt::Write as _;
+
+use minipack::{ModuleGraph, ModuleGraphEdgeKind};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::types::graph_format::GraphFormat;
+
+pub fn render_graph(graph: &ModuleGraph, format: &GraphFormat) -> String {
+  match format {
+    GraphFormat::Dot => to_dot(graph),
+    GraphFormat::Mermaid => to_mermaid(graph),
+    GraphFormat::Json => {
+      serde_json::to_string_pretty(graph).expect("graph should be serializable") + "\n"
+    }
+  }
+}
+
+/// Graphviz DOT. Entries are bold, externals and dynamic imports are dashed, and chunks are
+/// clusters of their modules.
+fn to_dot(graph: &ModuleGraph) -> String {
+  let mut s = String::from("digraph modules {\n  rankdir=LR;\n  node [shape=box];\n");
+
+  if let Some(chunks) = &graph.chunks {
+    s.push_str("  compound=true;\n");
+    for (index, chunk) in chunks.iter().enumerate() {
+      let _ = writeln!(s, "  subgraph cluster_{index} {{\n    label={};", dot_id(&chunk.name));
+      for module in &chunk.modules {
+        let _ = writeln!(s, "    {};", dot_id(module));
+      }
+      s.push_str("  }\n");
+    }
+  }
+
+  for node in &graph.modules {
+    let style = match (node.is_entry, node.is_external) {
+      (true, _) => " [style=bold]",
+      (_, true) => " [style=dashed]",
+      _ => "",
+    };
+    let _ = writeln!(s, "  {}{style};", dot_id(&node.id));
+  }
+
+  for edge in &graph.edges {
+    let style = match edge.kind {
+      ModuleGraphEdgeKind::Static => "",
+      ModuleGraphEdgeKind::Dynamic => " [style=dashed]",
+    };
+    let _ = writeln!(s, "  {} -> {}{style};", dot_id(&edge.from), dot_id(&edge.to));
+  }
+
+  // Edges between clusters have to connect nodes, they are clipped at the borders of the clusters.
+  if let Some(chunks) = &graph.chunks {
+    for (index, chunk) in chunks.iter().enumerate() {
+      let Some(from) = chunk.modules.first() else {
+        continue;
+      };
+      let imports = chunk.imports.iter().map(|importee| (importee, ""));
+      let dynamic_imports =
+        chunk.dynamic_imports.iter().map(|importee| (importee, ", style=dashed"));
+      for (importee, style) in imports.chain(dynamic_imports) {
+        let Some(to) = chunks[*importee].modules.first() else {
+          continue;
+        };
+        let _ = writeln!(
+          s,
+          "  {} -> {} [ltail=cluster_{index}, lhead=cluster_{importee}, color=gray{style}];",
+          dot_id(from),
+          dot_id(to)
+        );
+      }
+    }
+  }
+
+  s.push_str("}\n");
+  s
+}
+
+/// Mermaid flowchart. Entries are bold, externals and dynamic imports are dashed, and chunks are
+/// subgraphs of their modules.
+fn to_mermaid(graph: &ModuleGraph) -> String {
+  let node_ids = graph
+    .modules
+    .iter()
+    .enumerate()
+    .map(|(index, node)| (node.id.as_str(), index))
+    .collect::<FxHashMap<_, _>>();
+  let node_decl = |index: usize| {
+    let node = &graph.modules[index];
+    let class = match (node.is_entry, node.is_external) {
+      (true, _) => ":::entry",
+      (_, true) => ":::external",
+      _ => "",
+    };
+    format!("m{index}[\"{}\"]{class}", mermaid_label(&node.id))
+  };
+
+  let mut s = String::from("flowchart LR\n");
+  s.push_str("  classDef entry stroke-width:3px\n");
+  s.push_str("  classDef external stroke-dasharray:5 5\n");
+
+  // Nodes belong to the subgraph they are declared in first.
+  let mut declared = FxHashSet::default();
+  if let Some(chunks) = &graph.chunks {
+    for (index, chunk) in chunks.iter().enumerate() {
+      let _ = writeln!(s, "  subgraph c{index}[\"{}\"]", mermaid_label(&chunk.name));
+      for module in &chunk.modules {
+        if let Some(&node_index) = node_ids.get(module.as_str()) {
+          declared.insert(node_index);
+          let _ = writeln!(s, "    {}", node_decl(node_index));
+        }
+      }
+      s.push_str("  end\n");
+    }
+  }
+  for index in 0..graph.modules.len() {
+    if !declared.contains(&index) {
+      let _ = writeln!(s, "  {}", node_decl(index));
+    }
+  }
+
+  for edge in &graph.edges {
+    let (Some(from), Some(to)) = (node_ids.get(edge.from.as_str()), node_ids.get(edge.to.as_str()))
+    else {
+      continue;
+    };
+    let arrow = match edge.kind {
+      ModuleGraphEdgeKind::Static => "-->",
+      ModuleGraphEdgeKind::Dynamic => "-.->",
+    };
+    let _ = writeln!(s, "  m{from} {arrow} m{to}");
+  }
+
+  if let Some(chunks) = &graph.chunks {
+    for (index, chunk) in chunks.iter().enumerate() {
+      for importee in &chunk.imports {
+        let _ = writeln!(s, "  c{index} --> c{importee}");
+      }
+      for importee in &chunk.dynamic_imports {
+        let _ = writeln!(s, "  c{index} -.-> c{importee}");
+      }
+    }
+  }
+
+  s
+}
+
+fn dot_id(id: &str) -> String {
+  format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn mermaid_label(label: &str) -> String {
+  label.replace('"', "#quot;")
+}
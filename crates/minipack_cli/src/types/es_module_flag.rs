@@ -0,0 +1,19 @@
+use clap::ValueEnum;
+
+#[derive(PartialEq, Eq, Clone, ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+pub enum EsModuleFlag {
+  Always,
+  Never,
+  IfDefaultProp,
+}
+
+impl From<EsModuleFlag> for minipack::EsModuleFlag {
+  fn from(value: EsModuleFlag) -> Self {
+    match value {
+      EsModuleFlag::Always => minipack::EsModuleFlag::Always,
+      EsModuleFlag::Never => minipack::EsModuleFlag::Never,
+      EsModuleFlag::IfDefaultProp => minipack::EsModuleFlag::IfDefaultProp,
+    }
+  }
+}
@@ -0,0 +1,17 @@
+use clap::ValueEnum;
+
+#[derive(PartialEq, Eq, Clone, ValueEnum)]
+#[clap(rename_all = "lower")]
+pub enum GraphDirection {
+  Importers,
+  Importees,
+}
+
+impl From<GraphDirection> for minipack::GraphDirection {
+  fn from(value: GraphDirection) -> Self {
+    match value {
+      GraphDirection::Importers => minipack::GraphDirection::Importers,
+      GraphDirection::Importees => minipack::GraphDirection::Importees,
+    }
+  }
+}
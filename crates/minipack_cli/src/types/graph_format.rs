@@ -0,0 +1,9 @@
+use clap::ValueEnum;
+
+#[derive(PartialEq, Eq, Clone, ValueEnum)]
+#[clap(rename_all = "lower")]
+pub enum GraphFormat {
+  Dot,
+  Mermaid,
+  Json,
+}
@@ -0,0 +1,19 @@
+use clap::ValueEnum;
+
+#[derive(PartialEq, Eq, Clone, ValueEnum)]
+#[clap(rename_all = "lower")]
+pub enum IntegrityAlgorithm {
+  Sha256,
+  Sha384,
+  Sha512,
+}
+
+impl From<IntegrityAlgorithm> for minipack::IntegrityAlgorithm {
+  fn from(value: IntegrityAlgorithm) -> Self {
+    match value {
+      IntegrityAlgorithm::Sha256 => minipack::IntegrityAlgorithm::Sha256,
+      IntegrityAlgorithm::Sha384 => minipack::IntegrityAlgorithm::Sha384,
+      IntegrityAlgorithm::Sha512 => minipack::IntegrityAlgorithm::Sha512,
+    }
+  }
+}
@@ -0,0 +1,19 @@
+use clap::ValueEnum;
+
+#[derive(PartialEq, Eq, Clone, ValueEnum)]
+#[clap(rename_all = "lower")]
+pub enum Interop {
+  Auto,
+  Node,
+  EsModule,
+}
+
+impl From<Interop> for minipack::Interop {
+  fn from(value: Interop) -> Self {
+    match value {
+      Interop::Auto => minipack::Interop::Auto,
+      Interop::Node => minipack::Interop::Node,
+      Interop::EsModule => minipack::Interop::EsModule,
+    }
+  }
+}
@@ -0,0 +1,21 @@
+use clap::ValueEnum;
+
+#[derive(PartialEq, Eq, Clone, ValueEnum)]
+#[clap(rename_all = "lower")]
+pub enum LegalComments {
+  None,
+  Inline,
+  Eof,
+  Linked,
+}
+
+impl From<LegalComments> for minipack::LegalComments {
+  fn from(value: LegalComments) -> Self {
+    match value {
+      LegalComments::None => minipack::LegalComments::None,
+      LegalComments::Inline => minipack::LegalComments::Inline,
+      LegalComments::Eof => minipack::LegalComments::Eof,
+      LegalComments::Linked => minipack::LegalComments::Linked,
+    }
+  }
+}
@@ -1,2 +1,11 @@
+pub mod es_module_flag;
+pub mod graph_direction;
+pub mod graph_format;
+pub mod integrity_algorithm;
+pub mod interop;
+pub mod legal_comments;
+pub mod output_exports;
 pub mod output_format;
 pub mod platform;
+pub mod runtime;
+pub mod size_limit;
@@ -0,0 +1,21 @@
+use clap::ValueEnum;
+
+#[derive(PartialEq, Eq, Clone, ValueEnum)]
+#[clap(rename_all = "lower")]
+pub enum OutputExports {
+  Auto,
+  Default,
+  Named,
+  None,
+}
+
+impl From<OutputExports> for minipack::OutputExports {
+  fn from(value: OutputExports) -> Self {
+    match value {
+      OutputExports::Auto => minipack::OutputExports::Auto,
+      OutputExports::Default => minipack::OutputExports::Default,
+      OutputExports::Named => minipack::OutputExports::Named,
+      OutputExports::None => minipack::OutputExports::None,
+    }
+  }
+}
@@ -5,6 +5,7 @@ use clap::ValueEnum;
 pub enum OutputFormat {
   Esm,
   Cjs,
+  Iife,
 }
 
 impl From<OutputFormat> for minipack::OutputFormat {
@@ -12,6 +13,7 @@ impl From<OutputFormat> for minipack::OutputFormat {
     match value {
       OutputFormat::Esm => minipack::OutputFormat::Esm,
       OutputFormat::Cjs => minipack::OutputFormat::Cjs,
+      OutputFormat::Iife => minipack::OutputFormat::Iife,
     }
   }
 }
@@ -0,0 +1,8 @@
+use clap::ValueEnum;
+
+#[derive(PartialEq, Eq, Clone, ValueEnum)]
+#[clap(rename_all = "lower")]
+pub enum Runtime {
+  Inline,
+  External,
+}
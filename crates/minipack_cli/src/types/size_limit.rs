@@ -0,0 +1,45 @@
+use minipack::{SizeCompression, SizeLimit};
+
+/// Parse `<glob>=<size>[:gzip|:brotli]`, e.g. `*.js=50kB:gzip`. Sizes are in bytes, or in `kB` and
+/// `MB` with a suffix.
+pub fn parse_size_limit(value: &str) -> Result<SizeLimit, String> {
+  let (pattern, budget) =
+    value.rsplit_once('=').ok_or_else(|| format!("expected <glob>=<size>, got {value:?}"))?;
+  let (size, compression) = match budget.split_once(':') {
+    Some((size, "gzip")) => (size, SizeCompression::Gzip),
+    Some((size, "brotli")) => (size, SizeCompression::Brotli),
+    Some((_, compression)) => {
+      return Err(format!("unknown compression {compression:?}, expected gzip or brotli"));
+    }
+    None => (budget, SizeCompression::None),
+  };
+  let (number, unit) = match size.trim() {
+    size if size.ends_with("kB") || size.ends_with("KB") => (&size[..size.len() - 2], 1024.0),
+    size if size.ends_with("MB") => (&size[..size.len() - 2], 1024.0 * 1024.0),
+    size => (size.strip_suffix('B').unwrap_or(size), 1.0),
+  };
+  let number = number
+    .trim()
+    .parse::<f64>()
+    .ok()
+    .filter(|number| number.is_finite() && *number >= 0.0)
+    .ok_or_else(|| format!("invalid size {size:?}"))?;
+  let max_size = (number * unit) as usize;
+  Ok(SizeLimit { pattern: pattern.to_string(), max_size, compression })
+}
+
+#[test]
+fn test_parse_size_limit() {
+  let limit = parse_size_limit("*.js=50kB:gzip").unwrap();
+  assert_eq!(limit.pattern, "*.js");
+  assert_eq!(limit.max_size, 50 * 1024);
+  assert!(matches!(limit.compression, SizeCompression::Gzip));
+
+  assert_eq!(parse_size_limit("*.css=1.5MB").unwrap().max_size, 1024 * 1024 * 3 / 2);
+  assert_eq!(parse_size_limit("a=b.js=100B:brotli").unwrap().pattern, "a=b.js");
+  assert_eq!(parse_size_limit("*.js=100").unwrap().max_size, 100);
+
+  for value in ["*.js=-1kB", "*.js=NaN", "*.js=inf", "*.js=kB", "*.js=1kB:zstd", "*.js"] {
+    assert!(parse_size_limit(value).is_err(), "{value} should be rejected");
+  }
+}
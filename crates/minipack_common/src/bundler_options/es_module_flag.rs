@@ -0,0 +1,10 @@
+/// Whether `exports.__esModule` is defined with named exports in the `cjs` output format, so that
+/// bundlers and transpilers importing the chunk take `exports.default` as the default export.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EsModuleFlag {
+  #[default]
+  Always,
+  Never,
+  /// Only if there is a default export.
+  IfDefaultProp,
+}
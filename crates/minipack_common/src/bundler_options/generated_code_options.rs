@@ -0,0 +1,21 @@
+/// Style of the code generated around modules, e.g. namespace objects and import/export glue.
+#[derive(Debug, Default, Clone, Copy)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct GeneratedCodeOptions {
+  /// Adds a `Symbol.toStringTag` of "Module" to namespace objects.
+  pub symbols: bool,
+  /// Freezes namespace objects, so that their exports can't be reassigned or extended.
+  pub freeze: bool,
+  /// Uses `const` instead of `var` to bind imports and exports.
+  pub const_bindings: bool,
+  /// Uses arrow functions instead of `function` for getters, callbacks and wrappers.
+  pub arrow_functions: bool,
+}
+
+impl GeneratedCodeOptions {
+  /// The keyword declaring bindings of the glue code.
+  #[inline]
+  pub fn binding_keyword(&self) -> &'static str {
+    if self.const_bindings { "const" } else { "var" }
+  }
+}
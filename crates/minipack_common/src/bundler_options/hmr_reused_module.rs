@@ -0,0 +1,9 @@
+/// A module of a hmr update which is already running and isn't executed again. Its importers read
+/// the exports registered by the running build instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HmrReusedModule {
+  /// Absolute path of the module.
+  pub id: String,
+  /// Names exported by the running module.
+  pub exports: Vec<String>,
+}
@@ -0,0 +1,20 @@
+use std::fmt::Display;
+
+/// Hash algorithm used for subresource integrity (SRI) values.
+#[derive(Debug, Default, Clone, Copy)]
+pub enum IntegrityAlgorithm {
+  Sha256,
+  #[default]
+  Sha384,
+  Sha512,
+}
+
+impl Display for IntegrityAlgorithm {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Sha256 => write!(f, "sha256"),
+      Self::Sha384 => write!(f, "sha384"),
+      Self::Sha512 => write!(f, "sha512"),
+    }
+  }
+}
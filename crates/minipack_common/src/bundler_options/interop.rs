@@ -0,0 +1,11 @@
+/// How the default import of an external module is resolved with the `cjs` output format.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Interop {
+  /// `module.exports`, unless the module sets `__esModule`, in which case it's `exports.default`.
+  #[default]
+  Auto,
+  /// Always `module.exports`, like importing CommonJS in Node.js.
+  Node,
+  /// Always `exports.default`, for external modules transpiled from ES modules.
+  EsModule,
+}
@@ -0,0 +1,15 @@
+/// Where legal comments are emitted, i.e. `/*! ... */` and comments containing `@license` or
+/// `@preserve`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LegalComments {
+  /// Removes legal comments.
+  None,
+  /// Keeps legal comments in place.
+  #[default]
+  Inline,
+  /// Moves legal comments to the end of each chunk.
+  Eof,
+  /// Moves legal comments of each chunk to a `<chunk>.LEGAL.txt` asset, which is referenced by a
+  /// comment at the end of the chunk.
+  Linked,
+}
@@ -0,0 +1,36 @@
+pub use oxc::transformer::ESTarget;
+
+/// Options of the minifier, which runs on the AST of each module before the content hashes of the
+/// chunks are computed.
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct MinifyOptions {
+  /// Rewrites code into shorter equivalents and removes dead code.
+  pub compress: bool,
+  /// Shortens the names of local variables.
+  pub mangle: bool,
+  /// Removes whitespace and comments.
+  pub whitespace: bool,
+  /// Removes `console.*` calls. Requires `compress`.
+  pub drop_console: bool,
+  /// Removes `debugger;` statements. Requires `compress`.
+  pub drop_debugger: bool,
+  /// Keeps the `name` property of functions and classes.
+  pub keep_names: bool,
+  /// The minifier doesn't use syntax newer than the target.
+  pub target: ESTarget,
+}
+
+impl Default for MinifyOptions {
+  fn default() -> Self {
+    Self {
+      compress: true,
+      mangle: true,
+      whitespace: true,
+      drop_console: false,
+      drop_debugger: false,
+      keep_names: true,
+      target: ESTarget::ESNext,
+    }
+  }
+}
@@ -1,27 +1,101 @@
+pub mod es_module_flag;
 pub mod filename_template;
+pub mod generated_code_options;
+pub mod hmr_reused_module;
 pub mod input_item;
+pub mod integrity_algorithm;
+pub mod interop;
+pub mod legal_comments;
+pub mod minify_options;
 pub mod module_type;
 pub mod normalized_bundler_options;
+pub mod output_exports;
 pub mod output_format;
 pub mod platform;
+pub mod runtime_options;
+pub mod size_limit;
+pub mod stdin_input;
+pub mod unused_code_options;
 
 use std::path::PathBuf;
 
-use crate::{InputItem, OutputFormat, Platform};
+use crate::{
+  EsModuleFlag, GeneratedCodeOptions, HmrReusedModule, InputItem, IntegrityAlgorithm, Interop,
+  LegalComments, MinifyOptions, OutputExports, OutputFormat, Platform, RuntimeOptions, SizeLimit,
+  StdinInput, UnusedCodeOptions,
+};
 
 #[derive(Default, Debug, Clone)]
 pub struct BundlerOptions {
   // --- Input
   pub cwd: Option<PathBuf>,
   pub input: Option<Vec<InputItem>>,
+  /// An extra entry module whose source is given in memory, e.g. read from stdin.
+  pub stdin: Option<StdinInput>,
   pub platform: Option<Platform>,
 
   // --- Output
   pub dir: Option<String>,
+  /// Writes the only chunk of the build to this path, relative to `cwd`, instead of a file in `dir`.
+  /// Assets and source maps are written next to it. Can't be used with `dir`.
+  pub file: Option<String>,
+  /// Removes files in the output directory that aren't part of the build when writing it. The
+  /// output directory must be inside `cwd`.
+  pub empty_out_dir: Option<bool>,
+  /// Returns the only chunk of the build without writing anything, e.g. to print it. An error is
+  /// reported if the build produces several chunks.
+  pub stdout: Option<bool>,
   pub format: Option<OutputFormat>,
   pub entry_filenames: Option<String>,
   pub chunk_filenames: Option<String>,
+  pub inline_dynamic_imports: Option<bool>,
+  /// Wraps modules in lazy initializers when scope hoisting can't keep the order of execution of
+  /// native ESM, e.g. with circular imports or modules shared by several chunks.
+  pub strict_execution_order: Option<bool>,
+  /// Filename of the build manifest, relative to `dir`. No manifest is emitted if it's `None`.
+  pub manifest: Option<String>,
+  /// Adds a subresource integrity hash of each chunk to the manifest.
+  pub integrity: Option<IntegrityAlgorithm>,
+  /// Where legal comments are emitted. Defaults to keeping them in place.
+  pub legal_comments: Option<LegalComments>,
+  /// How the exports of entry chunks are exposed with the `cjs` format.
+  pub exports: Option<OutputExports>,
+  /// Whether `exports.__esModule` is defined with the `cjs` format.
+  pub es_module: Option<EsModuleFlag>,
+  /// How default imports of external modules are resolved with the `cjs` format.
+  pub interop: Option<Interop>,
+  /// Emits the declarations of TypeScript entries as `[name].d.ts`, bundled like the code. Every
+  /// TypeScript module must follow the rules of `isolatedDeclarations`.
+  pub dts: Option<bool>,
+  /// Size budgets of output files. Exceeding one is reported as a warning, or as an error with `ci`.
+  pub size_limits: Option<Vec<SizeLimit>>,
+  /// Where the runtime helpers come from. Defaults to bundling them.
+  pub runtime: Option<RuntimeOptions>,
+  /// Keeps the `name` property of functions and classes that are renamed to avoid conflicts, and
+  /// makes the minifier keep it too.
+  pub keep_names: Option<bool>,
+  /// Style of the code generated around modules, e.g. whether namespace objects are frozen.
+  pub generated_code: Option<GeneratedCodeOptions>,
+  /// Adds imports of the chunks a chunk depends on transitively, so they're fetched in parallel
+  /// instead of one level at a time. Defaults to `true`.
+  pub hoist_transitive_imports: Option<bool>,
+  /// Preloads the static imports of dynamically imported chunks with `<link rel="modulepreload">`,
+  /// or imports them in parallel outside of browsers. Only applies to the `esm` format.
+  pub preload_dynamic_deps: Option<bool>,
 
   // --- Enhance
-  pub minify: Option<bool>,
+  /// Minifies the output with the options. No minification is done if it's `None`.
+  pub minify: Option<MinifyOptions>,
+  /// Directory to persist scan results across builds, relative to `cwd`. No cache is used if it's `None`.
+  pub cache_dir: Option<String>,
+  /// Reports unused exports, modules and source files. No report is made if it's `None`.
+  pub unused_code: Option<UnusedCodeOptions>,
+  /// Enables `import.meta.hot` for hot module replacement. Used by the dev server.
+  pub hmr: Option<bool>,
+  /// Modules of a hmr update which aren't executed again, the dev server passes the ones that the
+  /// changed modules don't depend on.
+  pub hmr_reused_modules: Option<Vec<HmrReusedModule>>,
+  /// Reports problems that would otherwise be warnings as errors, e.g. exceeded size limits.
+  /// Defaults to whether the `CI` environment variable is set.
+  pub ci: Option<bool>,
 }
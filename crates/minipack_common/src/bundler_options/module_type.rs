@@ -2,5 +2,6 @@
 pub enum ModuleType {
   Js,
   Ts,
+  Html,
   Empty,
 }
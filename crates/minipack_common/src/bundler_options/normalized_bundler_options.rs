@@ -1,22 +1,51 @@
 use std::path::PathBuf;
 
-use crate::{InputItem, OutputFormat, Platform};
+use crate::{
+  EsModuleFlag, GeneratedCodeOptions, HmrReusedModule, InputItem, IntegrityAlgorithm, Interop,
+  LegalComments, MinifyOptions, OutputExports, OutputFormat, Platform, RuntimeOptions, SizeLimit,
+  StdinInput, UnusedCodeOptions,
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct NormalizedBundlerOptions {
   // --- Input
   pub cwd: PathBuf,
   pub input: Vec<InputItem>,
+  pub stdin: Option<StdinInput>,
   pub platform: Platform,
 
   // --- Output
   pub dir: String,
+  pub file: Option<String>,
+  pub empty_out_dir: bool,
+  pub stdout: bool,
   pub format: OutputFormat,
   pub entry_filenames: String,
   pub chunk_filenames: String,
+  pub inline_dynamic_imports: bool,
+  pub strict_execution_order: bool,
+  pub manifest: Option<String>,
+  pub integrity: Option<IntegrityAlgorithm>,
+  pub legal_comments: LegalComments,
+  pub exports: OutputExports,
+  pub es_module: EsModuleFlag,
+  pub interop: Interop,
+  pub dts: bool,
+  pub size_limits: Vec<SizeLimit>,
+  pub runtime: RuntimeOptions,
+  pub keep_names: bool,
+  pub generated_code: GeneratedCodeOptions,
+  pub hoist_transitive_imports: bool,
+  pub preload_dynamic_deps: bool,
 
   // --- Enhance
-  pub minify: bool,
+  pub minify: Option<MinifyOptions>,
+  pub cache_dir: Option<PathBuf>,
+  pub unused_code: Option<UnusedCodeOptions>,
+  pub hmr: bool,
+  pub hmr_reused_modules: Vec<HmrReusedModule>,
+  pub ci: bool,
 }
 
 impl NormalizedBundlerOptions {
@@ -24,4 +53,10 @@ impl NormalizedBundlerOptions {
   pub fn is_esm_format_with_node_platform(&self) -> bool {
     matches!(self.format, OutputFormat::Esm) && matches!(self.platform, Platform::Node)
   }
+
+  /// Chunks can only be preloaded, or imported in parallel outside of browsers, in ESM.
+  #[inline]
+  pub fn preloads_dynamic_deps(&self) -> bool {
+    self.preload_dynamic_deps && matches!(self.format, OutputFormat::Esm)
+  }
 }
@@ -0,0 +1,14 @@
+/// How the exports of an entry chunk are exposed with the `cjs` output format.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputExports {
+  /// `default` if the default export is the only export, `none` if there are no exports, and
+  /// `named` otherwise.
+  #[default]
+  Auto,
+  /// `module.exports = value`, for entries which only have a default export.
+  Default,
+  /// `exports.foo = foo`, where the default export is `exports.default`.
+  Named,
+  /// No exports, for entries which don't export anything.
+  None,
+}
@@ -5,6 +5,8 @@ pub enum OutputFormat {
   #[default]
   Esm,
   Cjs,
+  /// A single self-executing script without imports or exports, e.g. for classic workers.
+  Iife,
 }
 
 impl OutputFormat {
@@ -19,6 +21,7 @@ impl Display for OutputFormat {
     match self {
       Self::Esm => write!(f, "esm"),
       Self::Cjs => write!(f, "cjs"),
+      Self::Iife => write!(f, "iife"),
     }
   }
 }
@@ -0,0 +1,22 @@
+/// Where the runtime helpers of the output come from, e.g. `__toESM` and `__export`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum RuntimeOptions {
+  /// Bundles the helpers like any other module, into the chunk that first needs them.
+  #[default]
+  Inline,
+  /// Imports the helpers from the specifier, e.g. `@minipack/runtime`, so that separate builds on
+  /// the same page share them. Each chunk imports only the helpers it uses.
+  External {
+    specifier: String,
+    /// Filename of a standalone module exporting every helper, relative to `dir`. Nothing is
+    /// emitted if it's `None`.
+    filename: Option<String>,
+  },
+}
+
+impl RuntimeOptions {
+  #[inline]
+  pub fn is_external(&self) -> bool {
+    matches!(self, Self::External { .. })
+  }
+}
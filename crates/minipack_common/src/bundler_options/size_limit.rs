@@ -0,0 +1,18 @@
+/// A size budget of the output files matching a glob.
+#[derive(Debug, Clone)]
+pub struct SizeLimit {
+  /// Glob matched against filenames relative to `dir`, e.g. `*.js` or `assets/**/*.css`.
+  pub pattern: String,
+  /// The maximum size in bytes.
+  pub max_size: usize,
+  /// Which size of the files is compared with `max_size`.
+  pub compression: SizeCompression,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub enum SizeCompression {
+  #[default]
+  None,
+  Gzip,
+  Brotli,
+}
@@ -0,0 +1,25 @@
+use std::path::Path;
+
+use sugar_path::SugarPath;
+
+/// An entry module whose source is given in memory instead of being read from disk.
+#[derive(Debug, Default, Clone)]
+pub struct StdinInput {
+  pub contents: String,
+  /// Path of the module, relative to `cwd`. Its imports are resolved from its directory and its
+  /// extension picks the loader. Defaults to `stdin.js`.
+  pub filename: Option<String>,
+}
+
+impl StdinInput {
+  /// Id of the module, which doesn't have to exist on disk.
+  pub fn id(&self, cwd: &Path) -> String {
+    cwd.join(self.filename.as_deref().unwrap_or("stdin.js")).normalize().to_string_lossy().into()
+  }
+
+  /// Name of the entry chunk, the file stem of `filename`.
+  pub fn name(&self) -> String {
+    let filename = self.filename.as_deref().unwrap_or("stdin.js");
+    Path::new(filename).file_stem().map_or("stdin".into(), |stem| stem.to_string_lossy().into())
+  }
+}
@@ -0,0 +1,9 @@
+/// Options of the unused code report, which lists code that can be deleted from the project.
+#[derive(Debug, Default, Clone)]
+pub struct UnusedCodeOptions {
+  /// Source files under the directory, relative to `cwd`, that are never reached from any entry are
+  /// reported. Unreachable files aren't reported if it's `None`.
+  pub source_root: Option<String>,
+  /// Filename of the JSON report, relative to `dir`. The report is only returned if it's `None`.
+  pub filename: Option<String>,
+}
@@ -36,6 +36,8 @@ pub struct Chunk {
   // Only meaningful for cjs format
   pub require_binding_names_for_other_chunks: FxHashMap<ChunkIdx, String>,
   pub imports_from_external_modules: Vec<(ModuleIdx, Vec<NamedImport>)>,
+  // Only meaningful with the external runtime, sorted by name
+  pub imports_from_runtime: Vec<SymbolRef>,
   // meaningless if the chunk is an entrypoint
   pub exports_to_other_chunks: FxHashMap<SymbolRef, Rstr>,
 }
@@ -6,8 +6,8 @@ use oxc_index::IndexVec;
 use rustc_hash::FxHashMap;
 
 use crate::{
-  EcmaAstIdx, ImportRecordIdx, LocalExport, NamedImport, ResolvedImportRecord, StmtInfos,
-  SymbolRef, side_effects::DeterminedSideEffects,
+  EcmaAstIdx, ImportRecordIdx, LocalExport, ModuleDts, NamedImport, ResolvedImportRecord,
+  StmtInfos, SymbolRef, side_effects::DeterminedSideEffects,
 };
 
 bitflags! {
@@ -15,6 +15,12 @@ bitflags! {
     pub struct EcmaViewMeta: u8 {
         const INCLUDED = 1;
         const HAS_STAR_EXPORT = 1 << 1;
+        /// The module references `import.meta.hot`.
+        const HAS_IMPORT_META_HOT = 1 << 2;
+        /// The module calls `import.meta.hot.accept()` without dependencies.
+        const HMR_SELF_ACCEPTING = 1 << 3;
+        /// The module uses `await` at the top level.
+        const HAS_TOP_LEVEL_AWAIT = 1 << 4;
     }
 }
 
@@ -28,6 +34,21 @@ impl EcmaViewMeta {
   pub fn has_star_export(&self) -> bool {
     self.contains(Self::HAS_STAR_EXPORT)
   }
+
+  #[inline]
+  pub fn has_import_meta_hot(&self) -> bool {
+    self.contains(Self::HAS_IMPORT_META_HOT)
+  }
+
+  #[inline]
+  pub fn is_hmr_self_accepting(&self) -> bool {
+    self.contains(Self::HMR_SELF_ACCEPTING)
+  }
+
+  #[inline]
+  pub fn has_top_level_await(&self) -> bool {
+    self.contains(Self::HAS_TOP_LEVEL_AWAIT)
+  }
 }
 
 #[derive(Debug)]
@@ -43,4 +64,10 @@ pub struct EcmaView {
   pub named_exports: FxHashMap<Rstr, LocalExport>,
   pub imports: FxHashMap<Span, ImportRecordIdx>,
   pub import_records: IndexVec<ImportRecordIdx, ResolvedImportRecord>,
+  /// Import records of the files matched by each `import()` with a variable path.
+  pub import_globs: FxHashMap<Span, Vec<ImportRecordIdx>>,
+  /// Import records accepted by `import.meta.hot.accept(deps, cb)`.
+  pub hmr_accepted_deps: Vec<ImportRecordIdx>,
+  /// Declarations of the module with the `dts` option, if it's a TypeScript module.
+  pub dts: Option<ModuleDts>,
 }
@@ -1 +1,2 @@
 pub mod ecma_view;
+pub mod module_dts;
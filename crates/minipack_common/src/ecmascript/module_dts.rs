@@ -0,0 +1,23 @@
+use arcstr::ArcStr;
+
+/// Declarations of a TypeScript module generated by the isolated declarations transform, which are
+/// bundled into `.d.ts` files of entry chunks with the `dts` option.
+#[derive(Debug, Clone)]
+pub struct ModuleDts {
+  pub code: String,
+  /// Specifiers of the import and export declarations in `code`. They are resolved separately from
+  /// the import records of the module, since type-only imports are erased from the module itself.
+  pub specifiers: Vec<String>,
+  /// Resolved ids of `specifiers`. `None` if the declarations are imported from an external module,
+  /// which is kept as an import in the bundled declarations.
+  pub deps: Vec<Option<ArcStr>>,
+}
+
+/// A module that is only imported for its types. It isn't part of the module graph, only its
+/// declarations are loaded for the `dts` option.
+#[derive(Debug)]
+pub struct TypeOnlyModule {
+  pub id: ArcStr,
+  pub stable_id: String,
+  pub dts: ModuleDts,
+}
@@ -6,14 +6,33 @@ mod module_loader;
 mod types;
 
 pub use bundler_options::{
-  BundlerOptions, filename_template::FilenameTemplate, input_item::InputItem,
-  module_type::ModuleType, normalized_bundler_options::NormalizedBundlerOptions,
-  output_format::OutputFormat, platform::Platform,
+  BundlerOptions,
+  es_module_flag::EsModuleFlag,
+  filename_template::FilenameTemplate,
+  generated_code_options::GeneratedCodeOptions,
+  hmr_reused_module::HmrReusedModule,
+  input_item::InputItem,
+  integrity_algorithm::IntegrityAlgorithm,
+  interop::Interop,
+  legal_comments::LegalComments,
+  minify_options::{ESTarget, MinifyOptions},
+  module_type::ModuleType,
+  normalized_bundler_options::NormalizedBundlerOptions,
+  output_exports::OutputExports,
+  output_format::OutputFormat,
+  platform::Platform,
+  runtime_options::RuntimeOptions,
+  size_limit::{SizeCompression, SizeLimit},
+  stdin_input::StdinInput,
+  unused_code_options::UnusedCodeOptions,
 };
 
 pub use crate::{
   chunk::{Chunk, CrossChunkImportItem, PreliminaryFilename},
-  ecmascript::ecma_view::{EcmaView, EcmaViewMeta},
+  ecmascript::{
+    ecma_view::{EcmaView, EcmaViewMeta},
+    module_dts::{ModuleDts, TypeOnlyModule},
+  },
   module::{Module, external_module::ExternalModule, normal_module::NormalModule},
   module_loader::{
     ModuleLoaderMsg,
@@ -30,11 +49,16 @@ pub use crate::{
     importer_record::ImporterRecord,
     instantiated_chunk::InstantiatedChunk,
     member_expr_ref::MemberExprRef,
+    module_graph::{
+      ChunkGraphNode, GraphDirection, ModuleGraph, ModuleGraphEdge, ModuleGraphEdgeKind,
+      ModuleGraphNode,
+    },
     module_id::ModuleId,
+    module_info::ModuleInfo,
     named_export::LocalExport,
     named_import::{NamedImport, Specifier},
     namespace_alias::NamespaceAlias,
-    output_asset::OutputAsset,
+    output_asset::{OutputAsset, OutputAssetKind},
     raw_idx::{AssetIdx, ChunkIdx, EcmaAstIdx, ImportRecordIdx, ModuleIdx, StmtInfoIdx},
     rendered_module::RenderedModule,
     resolved_request_info::ResolvedId,
@@ -45,5 +69,7 @@ pub use crate::{
     symbol_or_member_expr_ref::SymbolOrMemberExprRef,
     symbol_ref::SymbolRef,
     symbol_ref_db::{GetLocalDb, SymbolRefDb, SymbolRefDbForModule, SymbolRefFlags},
+    unused_code_report::{UnusedCodeReport, UnusedExports},
+    write_report::WriteReport,
   },
 };
@@ -22,6 +22,11 @@ impl RuntimeModuleBrief {
     Self { idx, name_to_symbol }
   }
 
+  /// Names of all helpers, in no particular order.
+  pub fn names(&self) -> impl Iterator<Item = &str> {
+    self.name_to_symbol.keys().map(CompactStr::as_str)
+  }
+
   pub fn resolve_symbol(&self, name: &str) -> SymbolRef {
     SymbolRef::from((self.idx, self.name_to_symbol[name]))
   }
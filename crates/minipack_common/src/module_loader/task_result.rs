@@ -7,6 +7,8 @@ pub struct NormalModuleTaskResult {
   pub module: Module,
   pub ecma_related: Option<EcmaRelated>,
   pub resolved_deps: IndexVec<ImportRecordIdx, ResolvedId>,
+  /// Resolved specifiers of `ModuleDts`, `None` for the ones kept as external imports.
+  pub resolved_dts_deps: Vec<Option<ResolvedId>>,
   pub raw_import_records: IndexVec<ImportRecordIdx, RawImportRecord>,
   pub warnings: Vec<anyhow::Error>,
 }
@@ -13,4 +13,6 @@ pub struct EntryPoint {
 pub enum EntryPointKind {
   UserDefined,
   DynamicImport,
+  /// Workers are bundled by a build of their own, so they don't take part in linking.
+  Worker,
 }
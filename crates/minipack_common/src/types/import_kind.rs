@@ -2,6 +2,8 @@
 pub enum ImportKind {
   Import,
   DynamicImport,
+  /// `new Worker(new URL('./worker.js', import.meta.url))`
+  Worker,
 }
 
 impl ImportKind {
@@ -14,4 +16,9 @@ impl ImportKind {
   pub fn is_dynamic(&self) -> bool {
     matches!(self, Self::DynamicImport)
   }
+
+  #[inline]
+  pub fn is_worker(&self) -> bool {
+    matches!(self, Self::Worker)
+  }
 }
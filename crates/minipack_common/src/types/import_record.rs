@@ -15,6 +15,10 @@ bitflags::bitflags! {
     const IS_PLAIN_IMPORT = 1;
     /// `export * from 'mod'` only
     const IS_EXPORT_STAR = 1 << 1;
+    /// `new Worker(url, { type: 'module' })`
+    const IS_MODULE_WORKER = 1 << 2;
+    /// A file matched by `import()` with a variable path
+    const IS_IMPORT_GLOB = 1 << 3;
   }
 }
 
@@ -1,13 +1,19 @@
-use crate::{OutputAsset, PreliminaryFilename};
+use crate::{ChunkIdx, OutputAsset, OutputAssetKind, PreliminaryFilename};
 
 #[derive(Debug)]
 pub struct InstantiatedChunk {
+  /// The chunk this asset is rendered from.
+  pub chunk_idx: ChunkIdx,
   pub content: String,
   pub preliminary_filename: PreliminaryFilename,
+  /// Legal comments of the modules in the chunk, which are emitted separately from the code with
+  /// `eof` or `linked` legal comments.
+  pub legal_comments: Vec<String>,
+  pub kind: OutputAssetKind,
 }
 
 impl InstantiatedChunk {
   pub fn finalize(self, filename: String) -> OutputAsset {
-    OutputAsset { filename, content: self.content }
+    OutputAsset { filename, content: self.content, kind: self.kind }
   }
 }
@@ -6,7 +6,9 @@ pub mod import_record;
 pub mod importer_record;
 pub mod instantiated_chunk;
 pub mod member_expr_ref;
+pub mod module_graph;
 pub mod module_id;
+pub mod module_info;
 pub mod named_export;
 pub mod named_import;
 pub mod namespace_alias;
@@ -21,3 +23,5 @@ pub mod stmt_info;
 pub mod symbol_or_member_expr_ref;
 pub mod symbol_ref;
 pub mod symbol_ref_db;
+pub mod unused_code_report;
+pub mod write_report;
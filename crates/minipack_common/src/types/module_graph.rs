@@ -0,0 +1,147 @@
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+
+/// The module graph of a build, with its chunk graph if it was asked for. Modules are referred to
+/// by their stable ids.
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleGraph {
+  pub modules: Vec<ModuleGraphNode>,
+  pub edges: Vec<ModuleGraphEdge>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub chunks: Option<Vec<ChunkGraphNode>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleGraphNode {
+  pub id: String,
+  pub is_entry: bool,
+  pub is_external: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleGraphEdge {
+  pub from: String,
+  pub to: String,
+  pub kind: ModuleGraphEdgeKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModuleGraphEdgeKind {
+  /// `import`, `export ... from` and `require()`.
+  Static,
+  /// `import()` and workers.
+  Dynamic,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkGraphNode {
+  pub name: String,
+  pub modules: Vec<String>,
+  /// Indices of the chunks imported statically by the chunk.
+  pub imports: Vec<usize>,
+  /// Indices of the chunks imported by `import()` in the chunk.
+  pub dynamic_imports: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphDirection {
+  /// Modules that import the module, directly or transitively.
+  Importers,
+  /// Modules imported by the module, directly or transitively.
+  Importees,
+}
+
+impl ModuleGraph {
+  /// Keeps the modules within `depth` edges of `module` in the direction, and the edges between
+  /// them. Returns `None` if there is no such module.
+  pub fn focus(
+    &self,
+    module: &str,
+    direction: GraphDirection,
+    depth: Option<usize>,
+  ) -> Option<Self> {
+    if !self.modules.iter().any(|node| node.id == module) {
+      return None;
+    }
+
+    let mut kept = FxHashSet::from_iter([module]);
+    let mut frontier = FxHashSet::from_iter([module]);
+    let mut level = 0;
+    while !frontier.is_empty() && depth.is_none_or(|depth| level < depth) {
+      level += 1;
+      let mut next_frontier = FxHashSet::default();
+      for edge in &self.edges {
+        let (near, far) = match direction {
+          GraphDirection::Importees => (edge.from.as_str(), edge.to.as_str()),
+          GraphDirection::Importers => (edge.to.as_str(), edge.from.as_str()),
+        };
+        if frontier.contains(near) && kept.insert(far) {
+          next_frontier.insert(far);
+        }
+      }
+      frontier = next_frontier;
+    }
+
+    let chunks = self.chunks.as_ref().map(|chunks| {
+      // Chunks left without modules are dropped, so the indices of the others change.
+      let mut new_indices = vec![None; chunks.len()];
+      let mut focused_chunks = vec![];
+      for (index, chunk) in chunks.iter().enumerate() {
+        let modules =
+          chunk.modules.iter().filter(|id| kept.contains(id.as_str())).cloned().collect::<Vec<_>>();
+        if !modules.is_empty() {
+          new_indices[index] = Some(focused_chunks.len());
+          focused_chunks.push(ChunkGraphNode { modules, ..chunk.clone() });
+        }
+      }
+      for chunk in &mut focused_chunks {
+        chunk.imports = chunk.imports.iter().filter_map(|index| new_indices[*index]).collect();
+        chunk.dynamic_imports =
+          chunk.dynamic_imports.iter().filter_map(|index| new_indices[*index]).collect();
+      }
+      focused_chunks
+    });
+
+    Some(Self {
+      modules: self
+        .modules
+        .iter()
+        .filter(|node| kept.contains(node.id.as_str()))
+        .cloned()
+        .collect(),
+      edges: self
+        .edges
+        .iter()
+        .filter(|edge| kept.contains(edge.from.as_str()) && kept.contains(edge.to.as_str()))
+        .cloned()
+        .collect(),
+      chunks,
+    })
+  }
+}
+
+#[test]
+fn focus_on_importers() {
+  let node = |id: &str| ModuleGraphNode { id: id.to_string(), is_entry: false, is_external: false };
+  let edge = |from: &str, to: &str| ModuleGraphEdge {
+    from: from.to_string(),
+    to: to.to_string(),
+    kind: ModuleGraphEdgeKind::Static,
+  };
+  let graph = ModuleGraph {
+    modules: vec![node("a.js"), node("b.js"), node("c.js"), node("d.js")],
+    edges: vec![edge("a.js", "b.js"), edge("b.js", "c.js"), edge("c.js", "d.js")],
+    chunks: None,
+  };
+
+  let focused = graph.focus("c.js", GraphDirection::Importers, Some(1)).unwrap();
+  let ids = focused.modules.iter().map(|node| node.id.as_str()).collect::<Vec<_>>();
+  assert_eq!(ids, ["b.js", "c.js"]);
+  assert_eq!(focused.edges.len(), 1);
+
+  assert!(graph.focus("e.js", GraphDirection::Importees, None).is_none());
+}
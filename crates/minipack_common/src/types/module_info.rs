@@ -0,0 +1,17 @@
+/// A snapshot of a module in the module graph, exposed for tools built on top of the bundler.
+#[derive(Debug, Clone)]
+pub struct ModuleInfo {
+  pub id: String,
+  pub stable_id: String,
+  pub is_entry: bool,
+  /// Names exported by this module, including the ones of `export * from`.
+  pub exports: Vec<String>,
+  /// Ids of the modules imported statically by this module.
+  pub imported_ids: Vec<String>,
+  /// Ids of the modules imported by `import()` in this module.
+  pub dynamically_imported_ids: Vec<String>,
+  /// The module accepts its own updates via `import.meta.hot.accept()`.
+  pub hmr_self_accepting: bool,
+  /// Dependencies accepted via `import.meta.hot.accept(deps, cb)`, as pairs of specifier and id.
+  pub hmr_accepted_deps: Vec<(String, String)>,
+}
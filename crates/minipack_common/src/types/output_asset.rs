@@ -1,4 +1,15 @@
 pub struct OutputAsset {
   pub filename: String,
   pub content: String,
+  pub kind: OutputAssetKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputAssetKind {
+  /// A chunk of an entry point, either user-defined or dynamically imported.
+  Entry,
+  /// A chunk of modules shared by several entry points.
+  Common,
+  /// Anything else, e.g. html entries, the manifest or legal comments.
+  Asset,
 }
@@ -1,6 +1,6 @@
 use arcstr::ArcStr;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ResolvedId {
   pub id: ArcStr,
   pub is_external: bool,
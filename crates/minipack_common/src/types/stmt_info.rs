@@ -4,6 +4,7 @@ use rustc_hash::FxHashMap;
 use crate::{ImportRecordIdx, StmtInfoIdx, SymbolOrMemberExprRef, SymbolRef};
 
 #[derive(Default, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct StmtInfo {
   /// The index of this statement in the module body.
   ///
@@ -19,6 +20,11 @@ pub struct StmtInfo {
   pub referenced_symbols: Vec<SymbolOrMemberExprRef>,
   pub side_effect: bool,
   pub is_included: bool,
+  /// `import.meta.hot` is used in this statement, which relies on the `__hot` runtime helper.
+  pub has_import_meta_hot: bool,
+  /// A function or class is declared by this statement, including anonymous ones named after the
+  /// binding they are assigned to. `keep_names` relies on the `__name` runtime helper for them.
+  pub declares_function_or_class: bool,
   pub import_records: Vec<ImportRecordIdx>,
 }
 
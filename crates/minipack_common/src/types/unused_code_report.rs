@@ -0,0 +1,27 @@
+use serde::Serialize;
+
+/// Code that was found unused by tree shaking. Modules are referred to by their stable ids.
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnusedCodeReport {
+  /// Exports that are never used by any entry, grouped by module.
+  pub unused_exports: Vec<UnusedExports>,
+  /// Modules that were loaded but contributed no statements to the output.
+  pub unused_modules: Vec<String>,
+  /// Source files under `source_root` that are never reached from any entry.
+  pub unreachable_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnusedExports {
+  pub module: String,
+  pub names: Vec<String>,
+}
+
+impl UnusedCodeReport {
+  pub fn is_empty(&self) -> bool {
+    self.unused_exports.is_empty()
+      && self.unused_modules.is_empty()
+      && self.unreachable_files.is_empty()
+  }
+}
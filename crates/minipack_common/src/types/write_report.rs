@@ -0,0 +1,10 @@
+/// Files touched when the output of a build was written, relative to the output directory.
+#[derive(Debug, Default, Clone)]
+pub struct WriteReport {
+  /// Files that were created or whose content changed.
+  pub written: Vec<String>,
+  /// Files that already had the same content, left untouched.
+  pub skipped: Vec<String>,
+  /// Stale files removed by `empty_out_dir`.
+  pub deleted: Vec<String>,
+}
@@ -0,0 +1,68 @@
+// Client runtime of the dev server. The registry of `import.meta.hot` lives in the runtime module of
+// minipack, both sides share it through `globalThis.__minipack_hmr__`.
+const hmr = (globalThis.__minipack_hmr__ ??= { modules: new Map(), data: new Map() });
+hmr.reload = () => location.reload();
+
+const protocol = location.protocol === 'https:' ? 'wss' : 'ws';
+const socket = new WebSocket(`${protocol}://${location.host}/@minipack/ws`);
+
+socket.addEventListener('message', async ({ data }) => {
+  const payload = JSON.parse(data);
+  switch (payload.type) {
+    case 'connected':
+      console.debug('[minipack] connected.');
+      break;
+    case 'update':
+      try {
+        for (const update of payload.updates) {
+          await applyUpdate(update);
+        }
+      } catch (err) {
+        console.error('[minipack] failed to apply the update, reloading the page.', err);
+        location.reload();
+      }
+      break;
+    case 'full-reload':
+      location.reload();
+      break;
+    case 'error':
+      console.error(`[minipack] ${payload.message}`);
+      break;
+  }
+});
+
+socket.addEventListener('close', () => {
+  console.log('[minipack] server connection lost.');
+});
+
+async function applyUpdate({ boundary, accepted, dep, url, modules }) {
+  const callbacks = (hmr.modules.get(boundary)?.acceptCallbacks ?? []).filter(
+    (accept) => (accept.self ? boundary === accepted : accept.deps.includes(dep)),
+  );
+  // The boundary hasn't been executed yet, e.g. it's imported dynamically but not loaded.
+  if (callbacks.length === 0) {
+    return;
+  }
+
+  for (const id of modules) {
+    const hot = hmr.modules.get(id);
+    if (hot) {
+      for (const dispose of hot.disposeCallbacks) {
+        await dispose(hot.data);
+      }
+      hmr.modules.delete(id);
+    }
+  }
+
+  const mod = await import(url);
+  for (const accept of callbacks) {
+    if (accept.self) {
+      accept.callback?.(mod);
+    } else if (accept.isArray) {
+      accept.callback?.(accept.deps.map((d) => (d === dep ? mod : undefined)));
+    } else {
+      accept.callback?.(mod);
+    }
+  }
+  console.debug(`[minipack] hot updated: ${accepted}`);
+}
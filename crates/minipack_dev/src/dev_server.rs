@@ -0,0 +1,533 @@
+use std::{
+  path::{Component, Path, PathBuf},
+  sync::{Arc, RwLock},
+  time::{Duration, Instant},
+};
+
+use futures::{SinkExt, StreamExt};
+use minipack::{Bundler, BundlerOptions, InputItem, ModuleInfo, OutputFormat};
+use minipack_utils::{
+  concat_string,
+  html::{HtmlToken, tokenize},
+};
+use notify::{EventKind, RecursiveMode, Watcher, event::ModifyKind};
+use rustc_hash::{FxHashMap, FxHashSet};
+use sugar_path::SugarPath;
+use tokio::{
+  io::AsyncWriteExt,
+  net::{TcpListener, TcpStream},
+  sync::{broadcast, mpsc},
+};
+use tokio_tungstenite::{
+  WebSocketStream,
+  tungstenite::{Message, handshake::derive_accept_key, protocol::Role},
+};
+
+use crate::{
+  hmr::{HmrBoundary, HmrUpdate, compute_hmr_update},
+  http::{Request, content_type_of, write_response},
+};
+
+const CLIENT_PATH: &str = "/@minipack/client.js";
+const WEBSOCKET_PATH: &str = "/@minipack/ws";
+const HMR_UPDATE_DIR: &str = "@hmr";
+/// The manifest is used to find the entry chunks when there is no html entry.
+const DEV_MANIFEST: &str = ".minipack/manifest.json";
+
+pub struct DevServerOptions {
+  pub host: String,
+  pub port: u16,
+  /// Called with what happens in the server, it's up to the caller to report it.
+  pub on_event: Arc<dyn Fn(DevServerEvent) + Send + Sync>,
+}
+
+impl Default for DevServerOptions {
+  fn default() -> Self {
+    Self { host: "localhost".to_string(), port: 3000, on_event: Arc::new(|_| {}) }
+  }
+}
+
+pub enum DevServerEvent {
+  /// The server accepts connections at `url`.
+  Listening { url: String },
+  /// All modules are bundled.
+  Built { elapsed: Duration, warnings: Vec<anyhow::Error> },
+  /// The page is reloaded since the change can't be hot updated.
+  FullReload,
+  /// The module `accepted` is re-executed with its changed dependencies.
+  Update { accepted: String },
+  /// A build or a connection failed, the output of the last successful build is still served.
+  Error(anyhow::Error),
+}
+
+/// A development server which bundles in memory, watches the sources and pushes hot updates to the
+/// browser over a WebSocket.
+pub struct DevServer {
+  bundler_options: BundlerOptions,
+  options: DevServerOptions,
+}
+
+impl DevServer {
+  pub fn new(mut bundler_options: BundlerOptions, options: DevServerOptions) -> Self {
+    bundler_options.hmr = Some(true);
+    bundler_options.format = Some(OutputFormat::Esm);
+    bundler_options.manifest.get_or_insert_with(|| DEV_MANIFEST.to_string());
+    Self { bundler_options, options }
+  }
+
+  pub async fn serve(self) -> anyhow::Result<()> {
+    let cwd = match &self.bundler_options.cwd {
+      Some(cwd) => cwd.clone(),
+      None => std::env::current_dir()?,
+    };
+    let state = Arc::new(DevState::new(
+      self.bundler_options,
+      cwd.clone(),
+      Arc::clone(&self.options.on_event),
+    ));
+    state.build_all().await;
+
+    let listener = TcpListener::bind((self.options.host.as_str(), self.options.port)).await?;
+    let url = format!("http://{}:{}/", self.options.host, self.options.port);
+    (self.options.on_event)(DevServerEvent::Listening { url });
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+      if let Ok(event) = event {
+        // Files are usually added by creating them, or by renaming temporary files.
+        let is_added =
+          matches!(event.kind, EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_)));
+        if is_added || matches!(event.kind, EventKind::Modify(_) | EventKind::Remove(_)) {
+          let _ = tx.send((event.paths, is_added));
+        }
+      }
+    })?;
+    watcher.watch(&cwd, RecursiveMode::Recursive)?;
+
+    let watch_state = Arc::clone(&state);
+    tokio::spawn(async move {
+      while let Some(event) = rx.recv().await {
+        let mut changed = FxHashSet::default();
+        let mut added = FxHashSet::default();
+        // Editors usually emit several events for a single save.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        for (paths, is_added) in
+          std::iter::once(event).chain(std::iter::from_fn(|| rx.try_recv().ok()))
+        {
+          if is_added {
+            added.extend(paths.iter().cloned());
+          }
+          changed.extend(paths);
+        }
+        watch_state.on_change(changed, added).await;
+      }
+    });
+
+    loop {
+      let (stream, _) = listener.accept().await?;
+      let state = Arc::clone(&state);
+      tokio::spawn(async move {
+        if let Err(err) = state.handle_connection(stream).await {
+          (state.on_event)(DevServerEvent::Error(err));
+        }
+      });
+    }
+  }
+}
+
+#[derive(Default)]
+struct BuildState {
+  assets: FxHashMap<String, String>,
+  modules: Vec<ModuleInfo>,
+  /// Filenames of the entry chunks.
+  entries: Vec<String>,
+  /// Update chunks requested by the client, keyed by their path.
+  updates: FxHashMap<String, String>,
+  version: usize,
+}
+
+struct DevState {
+  cwd: PathBuf,
+  bundler_options: BundlerOptions,
+  build: RwLock<BuildState>,
+  hmr_tx: broadcast::Sender<String>,
+  on_event: Arc<dyn Fn(DevServerEvent) + Send + Sync>,
+}
+
+impl DevState {
+  fn new(
+    bundler_options: BundlerOptions,
+    cwd: PathBuf,
+    on_event: Arc<dyn Fn(DevServerEvent) + Send + Sync>,
+  ) -> Self {
+    let (hmr_tx, _) = broadcast::channel(16);
+    Self { cwd, bundler_options, build: RwLock::new(BuildState::default()), hmr_tx, on_event }
+  }
+
+  fn html_inputs(&self) -> Vec<PathBuf> {
+    let inputs = self.bundler_options.input.as_deref().unwrap_or_default();
+    inputs
+      .iter()
+      .filter(|item| is_html_file(&item.import))
+      .map(|item| self.cwd.join(&item.import).normalize())
+      .collect()
+  }
+
+  /// Bundle all modules. Return `false` if the build failed, the previous output is kept in this case.
+  async fn build_all(&self) -> bool {
+    let start = Instant::now();
+    let output = match Bundler::new(self.bundler_options.clone()) {
+      Ok(mut bundler) => bundler.build(false).await,
+      Err(errors) => Err(errors),
+    };
+    match output {
+      Ok(output) => {
+        let manifest = self.bundler_options.manifest.as_deref().unwrap_or(DEV_MANIFEST);
+        let entries = output
+          .assets
+          .iter()
+          .find(|asset| asset.filename == manifest)
+          .map(|asset| entry_files_from_manifest(&asset.content))
+          .unwrap_or_default();
+
+        let mut build = self.build.write().unwrap();
+        build.assets =
+          output.assets.into_iter().map(|asset| (asset.filename, asset.content)).collect();
+        build.modules = output.modules;
+        build.entries = entries;
+        drop(build);
+
+        (self.on_event)(DevServerEvent::Built {
+          elapsed: start.elapsed(),
+          warnings: output.warnings,
+        });
+        true
+      }
+      Err(errors) => {
+        let message = errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n");
+        self.send(&serde_json::json!({ "type": "error", "message": message }));
+        errors.0.into_iter().for_each(|error| (self.on_event)(DevServerEvent::Error(error)));
+        false
+      }
+    }
+  }
+
+  async fn on_change(&self, paths: FxHashSet<PathBuf>, added: FxHashSet<PathBuf>) {
+    let html_inputs = self.html_inputs();
+    let html_changed = paths.iter().any(|path| html_inputs.contains(path));
+    let previous_ids = self
+      .build
+      .read()
+      .unwrap()
+      .modules
+      .iter()
+      .map(|module| module.id.clone())
+      .collect::<FxHashSet<_>>();
+    let mut changed_ids = paths
+      .iter()
+      .map(|path| path.to_string_lossy().into_owned())
+      .filter(|id| previous_ids.contains(id))
+      .collect::<Vec<_>>();
+    // A new file may fix an import which failed to resolve, or be matched by `import.meta.glob`.
+    let has_new_sources = added.iter().any(|path| {
+      !previous_ids.contains(path.to_string_lossy().as_ref())
+        && is_source_file(path.strip_prefix(&self.cwd).unwrap_or(path))
+    });
+
+    if !html_changed && changed_ids.is_empty() && !has_new_sources {
+      return;
+    }
+
+    if !self.build_all().await {
+      return;
+    }
+
+    let update = if html_changed {
+      HmrUpdate::FullReload
+    } else {
+      let build = self.build.read().unwrap();
+      // New modules are propagated to their importers like changed ones.
+      changed_ids.extend(
+        build
+          .modules
+          .iter()
+          .filter(|module| !previous_ids.contains(&module.id))
+          .map(|module| module.id.clone()),
+      );
+      compute_hmr_update(&build.modules, &changed_ids)
+    };
+
+    match update {
+      HmrUpdate::FullReload => {
+        (self.on_event)(DevServerEvent::FullReload);
+        self.send(&serde_json::json!({ "type": "full-reload" }));
+      }
+      HmrUpdate::Update(mut boundaries) => {
+        if boundaries.is_empty() {
+          return;
+        }
+        let version = {
+          let mut build = self.build.write().unwrap();
+          build.version += 1;
+          build.updates.clear();
+          build.version
+        };
+        for (index, boundary) in boundaries.iter_mut().enumerate() {
+          let Some(content) = self.build_update_chunk(boundary).await else {
+            (self.on_event)(DevServerEvent::FullReload);
+            self.send(&serde_json::json!({ "type": "full-reload" }));
+            return;
+          };
+          let path = format!("{HMR_UPDATE_DIR}/{version}-{index}.js");
+          boundary.url = concat_string!("/", path);
+          self.build.write().unwrap().updates.insert(path, content);
+          (self.on_event)(DevServerEvent::Update { accepted: boundary.accepted.clone() });
+        }
+        self.send(&serde_json::json!({ "type": "update", "updates": boundaries }));
+      }
+    }
+  }
+
+  /// Bundle the accepted module and the modules it re-executes into a standalone chunk, which is
+  /// imported by the client. The other dependencies are read from the running build.
+  async fn build_update_chunk(&self, boundary: &HmrBoundary) -> Option<String> {
+    let output = match Bundler::new(BundlerOptions {
+      input: Some(vec![InputItem {
+        name: Some("update".to_string()),
+        import: boundary.accepted_id.clone(),
+      }]),
+      entry_filenames: Some("[name].js".to_string()),
+      inline_dynamic_imports: Some(true),
+      manifest: None,
+      integrity: None,
+      hmr_reused_modules: Some(boundary.reused.clone()),
+      ..self.bundler_options.clone()
+    }) {
+      Ok(mut bundler) => bundler.build(false).await,
+      Err(errors) => Err(errors),
+    };
+    match output {
+      Ok(output) => {
+        output.assets.into_iter().find(|asset| asset.filename == "update.js").map(|a| a.content)
+      }
+      Err(errors) => {
+        errors.0.into_iter().for_each(|error| (self.on_event)(DevServerEvent::Error(error)));
+        None
+      }
+    }
+  }
+
+  fn send(&self, payload: &serde_json::Value) {
+    // It's fine that there is no client connected.
+    let _ = self.hmr_tx.send(payload.to_string());
+  }
+
+  async fn handle_connection(&self, mut stream: TcpStream) -> anyhow::Result<()> {
+    let request = Request::read(&mut stream).await?;
+
+    if request.pathname() == WEBSOCKET_PATH
+      && request.header("upgrade").is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+    {
+      return self.handle_websocket(stream, &request).await;
+    }
+
+    if request.method != "GET" && request.method != "HEAD" {
+      return write_response(&mut stream, "405 Method Not Allowed", "text/plain", b"").await;
+    }
+
+    match self.load(request.pathname()) {
+      Some((content_type, body)) => {
+        let body = if request.method == "HEAD" { b"" } else { body.as_bytes() };
+        write_response(&mut stream, "200 OK", content_type, body).await
+      }
+      None => write_response(&mut stream, "404 Not Found", "text/plain", b"Not Found").await,
+    }
+  }
+
+  fn load(&self, pathname: &str) -> Option<(&'static str, String)> {
+    if pathname == CLIENT_PATH {
+      return Some((content_type_of(CLIENT_PATH), include_str!("./client.js").to_string()));
+    }
+
+    let mut filename = pathname.trim_start_matches('/').to_string();
+    if filename.is_empty() || filename.ends_with('/') {
+      filename.push_str("index.html");
+    }
+
+    let content = {
+      let build = self.build.read().unwrap();
+      match build.updates.get(&filename).or_else(|| build.assets.get(&filename)) {
+        Some(content) => content.clone(),
+        None if filename == "index.html" => default_index_html(&build.entries),
+        None => return None,
+      }
+    };
+
+    let content_type = content_type_of(&filename);
+    if is_html_file(&filename) {
+      Some((content_type, inject_client(&content)))
+    } else {
+      Some((content_type, content))
+    }
+  }
+
+  async fn handle_websocket(&self, mut stream: TcpStream, request: &Request) -> anyhow::Result<()> {
+    let Some(key) = request.header("sec-websocket-key") else {
+      return write_response(&mut stream, "400 Bad Request", "text/plain", b"").await;
+    };
+    let head = concat_string!(
+      "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: ",
+      derive_accept_key(key.as_bytes()),
+      "\r\n\r\n"
+    );
+    stream.write_all(head.as_bytes()).await?;
+
+    let (mut sink, mut source) =
+      WebSocketStream::from_raw_socket(stream, Role::Server, None).await.split();
+    let mut rx = self.hmr_tx.subscribe();
+    sink.send(Message::text(serde_json::json!({ "type": "connected" }).to_string())).await?;
+
+    loop {
+      tokio::select! {
+        payload = rx.recv() => match payload {
+          Ok(payload) => sink.send(Message::text(payload)).await?,
+          Err(broadcast::error::RecvError::Lagged(_)) => {}
+          Err(broadcast::error::RecvError::Closed) => break,
+        },
+        message = source.next() => match message {
+          Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+          Some(Ok(_)) => {}
+        },
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Whether a file, relative to `cwd`, could be a module of the build. Dependencies and hidden
+/// directories like the cache directory are ignored.
+fn is_source_file(path: &Path) -> bool {
+  let is_ignored = path.components().any(|component| match component {
+    Component::Normal(name) => name == "node_modules" || name.to_string_lossy().starts_with('.'),
+    _ => false,
+  });
+  let is_source = path.extension().is_some_and(|ext| {
+    matches!(
+      ext.to_string_lossy().as_ref(),
+      "js" | "mjs" | "cjs" | "jsx" | "ts" | "mts" | "cts" | "tsx" | "json" | "html" | "htm"
+    )
+  });
+  !is_ignored && is_source
+}
+
+fn is_html_file(filename: &str) -> bool {
+  Path::new(filename)
+    .extension()
+    .is_some_and(|ext| ext.eq_ignore_ascii_case("html") || ext.eq_ignore_ascii_case("htm"))
+}
+
+fn entry_files_from_manifest(manifest: &str) -> Vec<String> {
+  let Ok(serde_json::Value::Object(records)) = serde_json::from_str(manifest) else {
+    return vec![];
+  };
+  records
+    .values()
+    .filter(|record| record["isEntry"].as_bool().unwrap_or_default())
+    .filter_map(|record| record["file"].as_str().map(ToString::to_string))
+    .collect()
+}
+
+/// The page for projects without a html entry, which just loads the entry chunks.
+fn default_index_html(entries: &[String]) -> String {
+  let scripts = entries
+    .iter()
+    .map(|file| concat_string!("  <script type=\"module\" src=\"/", file, "\"></script>\n"))
+    .collect::<String>();
+  concat_string!(
+    "<!doctype html>\n<html>\n<head>\n  <meta charset=\"utf-8\">\n</head>\n<body>\n",
+    scripts,
+    "</body>\n</html>\n"
+  )
+}
+
+/// Insert the client at the start of `<head>`, or after the doctype if there is no `<head>`, so that
+/// it's loaded before the entries.
+fn inject_client(html: &str) -> String {
+  let tokens = tokenize(html);
+  let offset = tokens
+    .iter()
+    .find_map(|token| match token {
+      HtmlToken::StartTag(tag) if tag.is("head") => Some(tag.range.end),
+      _ => None,
+    })
+    .or_else(|| {
+      tokens.iter().find_map(|token| match token {
+        HtmlToken::Doctype(range) => Some(range.end),
+        _ => None,
+      })
+    })
+    .unwrap_or_default();
+  concat_string!(
+    &html[..offset],
+    "<script type=\"module\" src=\"",
+    CLIENT_PATH,
+    "\"></script>\n",
+    &html[offset..]
+  )
+}
+
+#[test]
+fn test_inject_client() {
+  let client = concat_string!("<script type=\"module\" src=\"", CLIENT_PATH, "\"></script>\n");
+  assert_eq!(
+    inject_client("<!doctype html>\n<html><head><title>App</title></head></html>"),
+    concat_string!("<!doctype html>\n<html><head>", client, "<title>App</title></head></html>")
+  );
+  assert_eq!(
+    inject_client("<!DOCTYPE html><body></body>"),
+    concat_string!("<!DOCTYPE html>", client, "<body></body>")
+  );
+  assert_eq!(inject_client("<body></body>"), concat_string!(client, "<body></body>"));
+}
+
+#[test]
+fn test_is_source_file() {
+  assert!(is_source_file(Path::new("src/new.ts")));
+  assert!(!is_source_file(Path::new("src/new.txt")));
+  assert!(!is_source_file(Path::new("node_modules/dep/index.js")));
+  assert!(!is_source_file(Path::new(".minipack/cache/new.js")));
+}
+
+#[tokio::test]
+async fn test_update_chunk_reuses_unchanged_modules() {
+  let cwd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/hmr");
+  let server = DevServer::new(
+    BundlerOptions {
+      cwd: Some(cwd.clone()),
+      input: Some(vec![InputItem { name: None, import: "./main.js".to_string() }]),
+      ..BundlerOptions::default()
+    },
+    DevServerOptions::default(),
+  );
+  let state = DevState::new(server.bundler_options, cwd.clone(), server.options.on_event);
+  assert!(state.build_all().await);
+
+  // The running build registers the modules that updates may reuse.
+  let main = state.build.read().unwrap().assets["main.js"].clone();
+  assert!(main.contains("__hmrRegister(\"icon.js\""));
+
+  let changed_ids = [cwd.join("label.js").to_string_lossy().into_owned()];
+  let update = compute_hmr_update(&state.build.read().unwrap().modules, &changed_ids);
+  let HmrUpdate::Update(boundaries) = update else {
+    panic!("`app.js` should accept the update of `button.js`");
+  };
+  assert_eq!(boundaries[0].modules, ["button.js", "label.js"]);
+  assert_eq!(boundaries[0].reused.len(), 1);
+  assert_eq!(boundaries[0].reused[0].exports, ["icon"]);
+
+  let content = state.build_update_chunk(&boundaries[0]).await.unwrap();
+  assert!(content.contains("const label = \"Click\";"));
+  assert!(content.contains("globalThis.__minipack_hmr__.exports.get(\"icon.js\")"));
+  assert!(!content.contains("const icon = \"*\";"));
+}
@@ -0,0 +1,221 @@
+use minipack::{HmrReusedModule, ModuleInfo};
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Serialize;
+
+/// A module which accepts the update of a changed module, either itself or one of its dependencies.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct HmrBoundary {
+  /// Stable id of the module which calls `import.meta.hot.accept`.
+  pub boundary: String,
+  /// Stable id of the module which will be re-executed.
+  pub accepted: String,
+  /// Specifier of `import.meta.hot.accept(dep, cb)`, `None` for self-accepting modules.
+  pub dep: Option<String>,
+  /// Stable ids of the modules re-executed along with `accepted`, their `dispose` callbacks will be called.
+  pub modules: Vec<String>,
+  /// Url of the update chunk, filled by the dev server.
+  pub url: String,
+  #[serde(skip)]
+  pub accepted_id: String,
+  /// Dependencies of the re-executed modules which don't depend on the changed modules, the update
+  /// chunk reuses their running instances.
+  #[serde(skip)]
+  pub reused: Vec<HmrReusedModule>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HmrUpdate {
+  FullReload,
+  Update(Vec<HmrBoundary>),
+}
+
+/// Walk up the importers of the changed modules until modules accepting the change are found. If the
+/// change reaches a module without importers, which is an entry, the page has to be fully reloaded.
+pub fn compute_hmr_update(modules: &[ModuleInfo], changed_ids: &[String]) -> HmrUpdate {
+  let module_by_id = modules.iter().map(|m| (m.id.as_str(), m)).collect::<FxHashMap<_, _>>();
+  let mut importers = FxHashMap::<&str, Vec<&ModuleInfo>>::default();
+  for module in modules {
+    for imported_id in module.imported_ids.iter().chain(&module.dynamically_imported_ids) {
+      importers.entry(imported_id.as_str()).or_default().push(module);
+    }
+  }
+
+  let changed = changed_ids.iter().map(String::as_str).collect::<FxHashSet<_>>();
+  let ctx = PropagateContext { module_by_id: &module_by_id, importers: &importers };
+  let mut boundaries = vec![];
+  let mut visited = FxHashSet::default();
+  for id in changed_ids {
+    if module_by_id.contains_key(id.as_str()) && !ctx.propagate(id, &mut boundaries, &mut visited) {
+      return HmrUpdate::FullReload;
+    }
+  }
+
+  let mut seen = FxHashSet::default();
+  boundaries
+    .retain(|(boundary, accepted, _)| seen.insert((boundary.id.as_str(), accepted.id.as_str())));
+
+  HmrUpdate::Update(
+    boundaries
+      .into_iter()
+      .map(|(boundary, accepted, dep)| {
+        let (modules, reused) = ctx.collect_reexecuted_modules(accepted, &changed);
+        HmrBoundary {
+          boundary: boundary.stable_id.clone(),
+          accepted: accepted.stable_id.clone(),
+          dep: dep.map(ToString::to_string),
+          modules,
+          url: String::new(),
+          accepted_id: accepted.id.clone(),
+          reused,
+        }
+      })
+      .collect(),
+  )
+}
+
+type Boundary<'a> = (&'a ModuleInfo, &'a ModuleInfo, Option<&'a str>);
+
+struct PropagateContext<'a> {
+  module_by_id: &'a FxHashMap<&'a str, &'a ModuleInfo>,
+  importers: &'a FxHashMap<&'a str, Vec<&'a ModuleInfo>>,
+}
+
+impl<'a> PropagateContext<'a> {
+  /// Return `false` if the change of `id` can't be accepted.
+  fn propagate(
+    &self,
+    id: &str,
+    boundaries: &mut Vec<Boundary<'a>>,
+    visited: &mut FxHashSet<&'a str>,
+  ) -> bool {
+    let module = self.module_by_id[id];
+    if module.hmr_self_accepting {
+      boundaries.push((module, module, None));
+      return true;
+    }
+
+    let Some(importers) = self.importers.get(id) else {
+      return false;
+    };
+
+    for importer in importers {
+      if let Some((dep, _)) = importer.hmr_accepted_deps.iter().find(|(_, dep_id)| dep_id == id) {
+        boundaries.push((importer, module, Some(dep.as_str())));
+        continue;
+      }
+      if visited.insert(importer.id.as_str()) && !self.propagate(&importer.id, boundaries, visited)
+      {
+        return false;
+      }
+    }
+
+    true
+  }
+
+  /// The update chunk is bundled from the accepted module. Only the modules between it and the
+  /// changed modules are re-executed, the other modules they import statically are reused.
+  fn collect_reexecuted_modules(
+    &self,
+    accepted: &'a ModuleInfo,
+    changed: &FxHashSet<&str>,
+  ) -> (Vec<String>, Vec<HmrReusedModule>) {
+    let imports_of = |module: &'a ModuleInfo| {
+      module
+        .imported_ids
+        .iter()
+        .chain(&module.dynamically_imported_ids)
+        .filter_map(|id| self.module_by_id.get(id.as_str()).copied())
+    };
+
+    let mut reachable = FxHashSet::from_iter([accepted.id.as_str()]);
+    let mut stack = vec![accepted];
+    while let Some(module) = stack.pop() {
+      for imported in imports_of(module) {
+        if reachable.insert(imported.id.as_str()) {
+          stack.push(imported);
+        }
+      }
+    }
+
+    // The changed modules and the modules depending on them, up to the accepted module.
+    let mut stack = reachable.iter().copied().filter(|id| changed.contains(id)).collect::<Vec<_>>();
+    let mut affected = stack.iter().copied().collect::<FxHashSet<_>>();
+    while let Some(id) = stack.pop() {
+      for importer in self.importers.get(id).into_iter().flatten() {
+        let importer_id = importer.id.as_str();
+        if reachable.contains(importer_id) && affected.insert(importer_id) {
+          stack.push(importer_id);
+        }
+      }
+    }
+    affected.insert(accepted.id.as_str());
+
+    // Modules imported by `import()` may not be loaded yet, so only static imports are reused.
+    let mut reused = vec![];
+    let mut reused_ids = FxHashSet::default();
+    for id in &affected {
+      for imported_id in &self.module_by_id[id].imported_ids {
+        if let Some(imported) = self.module_by_id.get(imported_id.as_str()) {
+          if !affected.contains(imported_id.as_str()) && reused_ids.insert(imported_id.as_str()) {
+            reused
+              .push(HmrReusedModule { id: imported.id.clone(), exports: imported.exports.clone() });
+          }
+        }
+      }
+    }
+    reused.sort_unstable_by(|a, b| a.id.cmp(&b.id));
+
+    let mut visited = FxHashSet::from_iter([accepted.id.as_str()]);
+    let mut stack = vec![accepted];
+    let mut modules = vec![];
+    while let Some(module) = stack.pop() {
+      modules.push(module.stable_id.clone());
+      for imported in imports_of(module) {
+        if !reused_ids.contains(imported.id.as_str()) && visited.insert(imported.id.as_str()) {
+          stack.push(imported);
+        }
+      }
+    }
+    (modules, reused)
+  }
+}
+
+#[test]
+fn test_compute_hmr_update() {
+  let module = |id: &str, imported_ids: &[&str]| ModuleInfo {
+    id: id.to_string(),
+    stable_id: id.to_string(),
+    is_entry: false,
+    exports: vec![],
+    imported_ids: imported_ids.iter().map(ToString::to_string).collect(),
+    dynamically_imported_ids: vec![],
+    hmr_self_accepting: false,
+    hmr_accepted_deps: vec![],
+  };
+
+  // main -> app -> button -> utils
+  let mut modules = vec![
+    module("main", &["app"]),
+    module("app", &["button"]),
+    module("button", &["utils"]),
+    module("utils", &[]),
+  ];
+  assert_eq!(compute_hmr_update(&modules, &["utils".into()]), HmrUpdate::FullReload);
+
+  modules[1].hmr_accepted_deps.push(("./button".into(), "button".into()));
+  let HmrUpdate::Update(boundaries) = compute_hmr_update(&modules, &["utils".into()]) else {
+    panic!("`app` should accept the update of `button`");
+  };
+  assert_eq!(boundaries.len(), 1);
+  assert_eq!(boundaries[0].boundary, "app");
+  assert_eq!(boundaries[0].accepted, "button");
+  assert_eq!(boundaries[0].dep.as_deref(), Some("./button"));
+  assert_eq!(boundaries[0].modules, vec!["button", "utils"]);
+
+  modules[3].hmr_self_accepting = true;
+  let HmrUpdate::Update(boundaries) = compute_hmr_update(&modules, &["utils".into()]) else {
+    panic!("`utils` should accept its own update");
+  };
+  assert_eq!(boundaries[0].boundary, "utils");
+  assert_eq!(boundaries[0].dep, None);
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use rustc_hash::FxHashMap;
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::TcpStream,
+};
+
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+
+/// The head of a HTTP/1.1 request. The dev server only serves `GET` requests, so the body is ignored.
+pub struct Request {
+  pub method: String,
+  pub path: String,
+  /// Header names are lowercased.
+  pub headers: FxHashMap<String, String>,
+}
+
+impl Request {
+  pub async fn read(stream: &mut TcpStream) -> anyhow::Result<Self> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0; 1024];
+    let head_end = loop {
+      let n = stream.read(&mut chunk).await?;
+      if n == 0 {
+        anyhow::bail!("Connection closed before the request head is received");
+      }
+      buf.extend_from_slice(&chunk[..n]);
+      if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+        break pos;
+      }
+      if buf.len() > MAX_HEAD_SIZE {
+        anyhow::bail!("Request head is too large");
+      }
+    };
+
+    let head = std::str::from_utf8(&buf[..head_end])?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or("/").to_string();
+    let headers = lines
+      .filter_map(|line| line.split_once(':'))
+      .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+      .collect();
+
+    Ok(Self { method, path, headers })
+  }
+
+  pub fn header(&self, name: &str) -> Option<&str> {
+    self.headers.get(name).map(String::as_str)
+  }
+
+  /// The request path without query and hash, e.g. `/assets/index.js`.
+  pub fn pathname(&self) -> &str {
+    self.path.split(['?', '#']).next().unwrap_or_default()
+  }
+}
+
+pub async fn write_response(
+  stream: &mut TcpStream,
+  status: &str,
+  content_type: &str,
+  body: &[u8],
+) -> anyhow::Result<()> {
+  let head = format!(
+    "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+    body.len()
+  );
+  stream.write_all(head.as_bytes()).await?;
+  stream.write_all(body).await?;
+  stream.flush().await?;
+  Ok(())
+}
+
+pub fn content_type_of(filename: &str) -> &'static str {
+  match Path::new(filename).extension().and_then(|ext| ext.to_str()) {
+    Some("js" | "mjs" | "cjs") => "text/javascript; charset=utf-8",
+    Some("html" | "htm") => "text/html; charset=utf-8",
+    Some("css") => "text/css; charset=utf-8",
+    Some("json" | "map") => "application/json; charset=utf-8",
+    Some("txt") => "text/plain; charset=utf-8",
+    _ => "application/octet-stream",
+  }
+}
@@ -0,0 +1,5 @@
+mod dev_server;
+mod hmr;
+mod http;
+
+pub use crate::dev_server::{DevServer, DevServerEvent, DevServerOptions};
@@ -0,0 +1,7 @@
+import { button } from './button.js';
+
+export function render() {
+  console.log(button());
+}
+
+import.meta.hot.accept('./button.js', () => render());
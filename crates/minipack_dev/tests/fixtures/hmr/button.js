@@ -0,0 +1,4 @@
+import { icon } from './icon.js';
+import { label } from './label.js';
+
+export const button = () => `${icon} ${label}`;
@@ -1,12 +1,12 @@
 use arcstr::ArcStr;
 use minipack_error::BuildResult;
 use oxc::{
-  allocator::Allocator,
-  codegen::{Codegen, CodegenOptions, CodegenReturn},
-  minifier::{CompressOptions, CompressOptionsKeepNames, MangleOptions, Minifier, MinifierOptions},
-  parser::Parser,
+  ast::ast::Program,
+  codegen::{Codegen, CodegenOptions, CodegenReturn, LegalComment},
+  minifier::{Minifier, MinifierOptions},
+  parser::{ParseOptions, Parser},
+  semantic::Scoping,
   span::SourceType,
-  transformer::ESTarget,
 };
 
 use crate::ecma_ast::{
@@ -32,35 +32,87 @@ impl EcmaCompiler {
     Ok(EcmaAst { program, source_type })
   }
 
+  /// Parse `code` printed from the ast of `source`. The program keeps `source` as its source text,
+  /// so that the spans and comments of the printed ast can be put back. Parentheses are dropped,
+  /// like in an ast normalized by the minifier.
+  pub fn parse_printed(
+    source: ArcStr,
+    code: &str,
+    source_type: SourceType,
+  ) -> BuildResult<EcmaAst> {
+    let allocator = oxc::allocator::Allocator::default();
+    let owner = ProgramCellOwner { source, allocator };
+    let program = ProgramCell::try_new(owner, |owner| {
+      let code = owner.allocator.alloc_str(code);
+      let options = ParseOptions { preserve_parens: false, ..ParseOptions::default() };
+      let ret = Parser::new(&owner.allocator, code, source_type).with_options(options).parse();
+      if ret.errors.is_empty() {
+        let mut program = ret.program;
+        program.source_text = &owner.source;
+        Ok(ProgramCellDependent { program })
+      } else {
+        Err(anyhow::anyhow!("{:?}", ret.errors))
+      }
+    })?;
+
+    Ok(EcmaAst { program, source_type })
+  }
+
   pub fn print(ast: &EcmaAst) -> CodegenReturn {
     Codegen::new().build(ast.program())
   }
 
-  pub fn minify(source_text: &str) -> String {
-    let allocator = Allocator::default();
-    let source_type = SourceType::default();
-
-    let program = Parser::new(&allocator, source_text, source_type).parse().program;
-    let program = allocator.alloc(program);
-
-    let ret = Minifier::new(MinifierOptions {
-      mangle: Some(MangleOptions::default()),
-      compress: Some(CompressOptions {
-        target: ESTarget::ESNext,
-        drop_debugger: false,
-        drop_console: false,
-        keep_names: CompressOptionsKeepNames { function: true, class: true },
-      }),
+  /// Legal comments moved out of the code by `LegalComment::External` are returned in
+  /// `CodegenReturn::legal_comments`.
+  pub fn print_with_legal_comments(
+    ast: &mut EcmaAst,
+    legal_comments: LegalComment,
+  ) -> CodegenReturn {
+    ast.program.with_mut(|fields| {
+      let options = CodegenOptions { legal_comments, ..CodegenOptions::default() };
+      print_program(fields.program, options, None)
     })
-    .build(&allocator, program);
+  }
 
-    let ret = Codegen::new()
-      .with_options(CodegenOptions { minify: true, ..CodegenOptions::default() })
-      .with_scoping(ret.scoping)
-      .build(program);
+  /// Minifies the AST of a module in place and prints it. Without `remove_whitespace`, the code is
+  /// printed as usual, e.g. to only drop `console.*` calls or mangle names.
+  pub fn minify(
+    ast: &mut EcmaAst,
+    options: MinifierOptions,
+    remove_whitespace: bool,
+    legal_comments: LegalComment,
+  ) -> CodegenReturn {
+    ast.program.with_mut(|fields| {
+      let ret = Minifier::new(options).build(fields.allocator, fields.program);
+      let options = if remove_whitespace {
+        CodegenOptions { legal_comments, ..CodegenOptions::minify() }
+      } else {
+        CodegenOptions { legal_comments, ..CodegenOptions::default() }
+      };
+      print_program(fields.program, options, ret.scoping)
+    })
+  }
+}
 
-    ret.code
+/// Legal comments which aren't kept in place are taken out of the program before it's printed.
+/// The codegen only leaves them out as legal comments, `/** @license */` would still be printed as
+/// a JSDoc comment. With `LegalComment::External`, they're returned as the codegen would.
+fn print_program(
+  program: &mut Program,
+  options: CodegenOptions,
+  scoping: Option<Scoping>,
+) -> CodegenReturn {
+  if options.legal_comments.is_inline() {
+    return Codegen::new().with_options(options).with_scoping(scoping).build(program);
   }
+  let extracted = matches!(options.legal_comments, LegalComment::External).then(|| {
+    program.comments.iter().filter(|comment| comment.is_legal()).copied().collect::<Vec<_>>()
+  });
+  program.comments.retain(|comment| !comment.is_legal());
+  let options = CodegenOptions { legal_comments: LegalComment::None, ..options };
+  let mut ret = Codegen::new().with_options(options).with_scoping(scoping).build(program);
+  ret.legal_comments = extracted.unwrap_or_default();
+  ret
 }
 
 #[test]
@@ -1,8 +1,8 @@
 use oxc::{
-  allocator::{self, Allocator, Box, IntoIn},
+  allocator::{Allocator, Box, IntoIn},
   ast::{
     AstBuilder, NONE,
-    ast::{self, Argument, BindingIdentifier, ImportOrExportKind, Statement},
+    ast::{self, Argument, BindingIdentifier, ImportOrExportKind},
   },
   span::{Atom, CompactStr, SPAN, Span},
 };
@@ -240,6 +240,31 @@ impl<'ast> AstSnippet<'ast> {
     ))
   }
 
+  /// ```js
+  /// function() { return xx; }
+  /// ```
+  pub fn only_return_fn_expr(&self, expr: ast::Expression<'ast>) -> ast::Expression<'ast> {
+    let statements = self.builder.vec1(self.builder.statement_return(SPAN, Some(expr)));
+    ast::Expression::FunctionExpression(self.builder.alloc_function(
+      SPAN,
+      ast::FunctionType::FunctionExpression,
+      None::<BindingIdentifier>,
+      false,
+      false,
+      false,
+      NONE,
+      NONE,
+      self.builder.formal_parameters(
+        SPAN,
+        ast::FormalParameterKind::FormalParameter,
+        self.builder.vec(),
+        NONE,
+      ),
+      NONE,
+      Some(self.builder.function_body(SPAN, self.builder.vec(), statements)),
+    ))
+  }
+
   pub fn alloc_string_literal(
     &self,
     value: PassedStr,
@@ -266,51 +291,39 @@ impl<'ast> AstSnippet<'ast> {
     ))
   }
 
-  /// Promise.resolve().then(function() {})
-  pub fn promise_resolve_then_call_expr(
+  /// Promise.resolve().then(callback)
+  pub fn promise_resolve_then_expr(
     &self,
     span: Span,
-    statements: allocator::Vec<'ast, Statement<'ast>>,
+    callback: ast::Expression<'ast>,
   ) -> ast::Expression<'ast> {
-    let arguments = self.builder.vec1(Argument::FunctionExpression(self.builder.alloc_function(
-      SPAN,
-      ast::FunctionType::FunctionExpression,
-      None::<BindingIdentifier>,
-      false,
-      false,
-      false,
+    ast::Expression::CallExpression(self.builder.alloc_call_expression(
+      span,
+      self.promise_resolve_then_callee(),
       NONE,
-      NONE,
-      self.builder.formal_parameters(
-        SPAN,
-        ast::FormalParameterKind::Signature,
-        self.builder.vec_with_capacity(2),
-        NONE,
-      ),
-      NONE,
-      Some(self.builder.function_body(SPAN, self.builder.vec(), statements)),
-    )));
+      self.builder.vec1(Argument::from(callback)),
+      false,
+    ))
+  }
 
-    let callee =
-      ast::Expression::StaticMemberExpression(self.builder.alloc_static_member_expression(
+  /// Promise.resolve().then
+  fn promise_resolve_then_callee(&self) -> ast::Expression<'ast> {
+    ast::Expression::StaticMemberExpression(self.builder.alloc_static_member_expression(
+      SPAN,
+      ast::Expression::CallExpression(self.builder.alloc_call_expression(
         SPAN,
-        ast::Expression::CallExpression(self.builder.alloc_call_expression(
+        ast::Expression::StaticMemberExpression(self.builder.alloc_static_member_expression(
           SPAN,
-          ast::Expression::StaticMemberExpression(self.builder.alloc_static_member_expression(
-            SPAN,
-            self.id_ref_expr("Promise", SPAN),
-            self.id_name("resolve", SPAN),
-            false,
-          )),
-          NONE,
-          self.builder.vec(),
+          self.id_ref_expr("Promise", SPAN),
+          self.id_name("resolve", SPAN),
           false,
         )),
-        self.id_name("then", SPAN),
+        NONE,
+        self.builder.vec(),
         false,
-      ));
-    ast::Expression::CallExpression(
-      self.builder.alloc_call_expression(span, callee, NONE, arguments, false),
-    )
+      )),
+      self.id_name("then", SPAN),
+      false,
+    ))
   }
 }
@@ -1,4 +1,7 @@
-use std::{io, path::Path};
+use std::{
+  io,
+  path::{Path, PathBuf},
+};
 
 use oxc_resolver::FileSystem as OxcResolverFileSystem;
 
@@ -13,6 +16,33 @@ pub trait FileSystem: Send + Sync + OxcResolverFileSystem {
   /// * See [std::fs::write]
   fn write(&self, path: &Path, content: &[u8]) -> io::Result<()>;
 
+  /// # Errors
+  ///
+  /// * See [std::fs::read]
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+
+  /// # Errors
+  ///
+  /// * See [std::fs::rename]
+  fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+
+  /// # Errors
+  ///
+  /// * See [std::fs::remove_file]
+  fn remove_file(&self, path: &Path) -> io::Result<()>;
+
+  /// # Errors
+  ///
+  /// * See [std::fs::remove_dir]
+  fn remove_dir(&self, path: &Path) -> io::Result<()>;
+
+  /// Paths of the entries in the directory, in no particular order.
+  ///
+  /// # Errors
+  ///
+  /// * See [std::fs::read_dir]
+  fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
   /// # Errors
   ///
   /// * See [std::path::Path::exists]
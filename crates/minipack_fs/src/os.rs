@@ -20,6 +20,26 @@ impl FileSystem for OsFileSystem {
     std::fs::write(path, content)
   }
 
+  fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+    std::fs::read(path)
+  }
+
+  fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+    std::fs::rename(from, to)
+  }
+
+  fn remove_file(&self, path: &Path) -> io::Result<()> {
+    std::fs::remove_file(path)
+  }
+
+  fn remove_dir(&self, path: &Path) -> io::Result<()> {
+    std::fs::remove_dir(path)
+  }
+
+  fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+    std::fs::read_dir(path)?.map(|entry| entry.map(|entry| entry.path())).collect()
+  }
+
   fn exists(&self, path: &Path) -> bool {
     path.exists()
   }
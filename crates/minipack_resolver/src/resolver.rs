@@ -100,7 +100,7 @@ impl<F: FileSystem + Default> Resolver<F> {
           },
           is_external: true,
         }),
-        _ => Err(anyhow::anyhow!("{:?}", err))?,
+        _ => Err(anyhow::anyhow!("{err:?}"))?,
       },
     }
   }
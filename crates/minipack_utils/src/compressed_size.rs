@@ -0,0 +1,28 @@
+use std::io::Write;
+
+use flate2::{Compression, write::GzEncoder};
+
+/// Size of `content` after gzip compression with the default level.
+pub fn gzip_size(content: &[u8]) -> usize {
+  let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+  encoder.write_all(content).expect("Writing to a Vec can't fail");
+  encoder.finish().expect("Writing to a Vec can't fail").len()
+}
+
+/// Size of `content` after brotli compression with the highest quality, as served by most CDNs
+/// for static files.
+pub fn brotli_size(content: &[u8]) -> usize {
+  let mut compressed = Vec::new();
+  let params = brotli::enc::BrotliEncoderParams { quality: 11, ..Default::default() };
+  brotli::BrotliCompress(&mut &content[..], &mut compressed, &params)
+    .expect("Reading from a slice and writing to a Vec can't fail");
+  compressed.len()
+}
+
+#[test]
+fn test_compressed_size() {
+  let content = "export const value = 1;\n".repeat(100);
+  assert!(gzip_size(content.as_bytes()) < content.len());
+  assert!(brotli_size(content.as_bytes()) < content.len());
+  assert!(gzip_size(b"") > 0);
+}
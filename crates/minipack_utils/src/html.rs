@@ -0,0 +1,279 @@
+use std::ops::Range;
+
+/// Elements whose content is raw text, where tags aren't recognized until the matching end tag.
+const RAW_TEXT_ELEMENTS: [&str; 9] =
+  ["script", "style", "textarea", "title", "xmp", "iframe", "noembed", "noframes", "plaintext"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HtmlToken<'a> {
+  StartTag(HtmlTag<'a>),
+  EndTag { name: &'a str, range: Range<usize> },
+  Doctype(Range<usize>),
+  Comment(Range<usize>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct HtmlTag<'a> {
+  pub name: &'a str,
+  pub attrs: Vec<HtmlAttr<'a>>,
+  /// The range of the start tag, or of the whole element for raw text elements like `<script>`.
+  pub range: Range<usize>,
+  /// The content of raw text elements.
+  pub text: Option<Range<usize>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct HtmlAttr<'a> {
+  pub name: &'a str,
+  /// The value without quotes. Character references aren't decoded.
+  pub value: Option<&'a str>,
+  /// The range of the value without quotes, which is empty at the end of the name if there is no value.
+  pub value_range: Range<usize>,
+}
+
+impl<'a> HtmlTag<'a> {
+  pub fn is(&self, name: &str) -> bool {
+    self.name.eq_ignore_ascii_case(name)
+  }
+
+  pub fn attr(&self, name: &str) -> Option<&HtmlAttr<'a>> {
+    self.attrs.iter().find(|attr| attr.name.eq_ignore_ascii_case(name))
+  }
+
+  pub fn attr_value(&self, name: &str) -> Option<&'a str> {
+    self.attr(name).and_then(|attr| attr.value)
+  }
+}
+
+/// Split a html document into its tags, doctype and comments, skipping text. This follows the
+/// tokenization of the html spec closely enough to find elements, but it doesn't build a tree.
+pub fn tokenize(html: &str) -> Vec<HtmlToken<'_>> {
+  let bytes = html.as_bytes();
+  let mut tokens = vec![];
+  let mut pos = 0;
+
+  while let Some(offset) = html[pos..].find('<') {
+    let start = pos + offset;
+    let rest = &bytes[start + 1..];
+    if rest.starts_with(b"!--") {
+      let end = html[start + 4..].find("-->").map_or(html.len(), |end| start + 4 + end + 3);
+      tokens.push(HtmlToken::Comment(start..end));
+      pos = end;
+    } else if rest.first().is_some_and(|b| *b == b'!' || *b == b'?') {
+      let end = html[start..].find('>').map_or(html.len(), |end| start + end + 1);
+      if rest.len() >= 8 && rest[1..8].eq_ignore_ascii_case(b"doctype") {
+        tokens.push(HtmlToken::Doctype(start..end));
+      } else {
+        tokens.push(HtmlToken::Comment(start..end));
+      }
+      pos = end;
+    } else if rest.first() == Some(&b'/') && rest.get(1).is_some_and(u8::is_ascii_alphabetic) {
+      let name_end = tag_name_end(bytes, start + 2);
+      let (_, end) = parse_attrs(html, name_end);
+      tokens.push(HtmlToken::EndTag { name: &html[start + 2..name_end], range: start..end });
+      pos = end;
+    } else if rest.first().is_some_and(u8::is_ascii_alphabetic) {
+      let name_end = tag_name_end(bytes, start + 1);
+      let name = &html[start + 1..name_end];
+      let (attrs, mut end) = parse_attrs(html, name_end);
+      let mut text = None;
+      if RAW_TEXT_ELEMENTS.iter().any(|raw| name.eq_ignore_ascii_case(raw)) {
+        let text_end = find_end_tag(html, end, name).unwrap_or(html.len());
+        text = Some(end..text_end);
+        end = if text_end == html.len() {
+          text_end
+        } else {
+          parse_attrs(html, text_end + 2 + name.len()).1
+        };
+      }
+      tokens.push(HtmlToken::StartTag(HtmlTag { name, attrs, range: start..end, text }));
+      pos = end;
+    } else {
+      pos = start + 1;
+    }
+  }
+
+  tokens
+}
+
+fn is_tag_delimiter(b: u8) -> bool {
+  b.is_ascii_whitespace() || b == b'/' || b == b'>'
+}
+
+fn tag_name_end(bytes: &[u8], start: usize) -> usize {
+  bytes[start..].iter().position(|b| is_tag_delimiter(*b)).map_or(bytes.len(), |len| start + len)
+}
+
+/// Parse the attributes of a tag from `pos` to its `>`, returning them and the end of the tag.
+fn parse_attrs(html: &str, mut pos: usize) -> (Vec<HtmlAttr<'_>>, usize) {
+  let bytes = html.as_bytes();
+  let mut attrs = vec![];
+  loop {
+    while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'/') {
+      pos += 1;
+    }
+    if pos >= bytes.len() {
+      return (attrs, bytes.len());
+    }
+    if bytes[pos] == b'>' {
+      return (attrs, pos + 1);
+    }
+
+    // A leading `=` is part of the name.
+    let name_start = pos;
+    pos += 1;
+    while pos < bytes.len() && !is_tag_delimiter(bytes[pos]) && bytes[pos] != b'=' {
+      pos += 1;
+    }
+    let name = &html[name_start..pos];
+
+    let mut after_name = pos;
+    while after_name < bytes.len() && bytes[after_name].is_ascii_whitespace() {
+      after_name += 1;
+    }
+    if bytes.get(after_name) != Some(&b'=') {
+      attrs.push(HtmlAttr { name, value: None, value_range: pos..pos });
+      continue;
+    }
+    pos = after_name + 1;
+    while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+      pos += 1;
+    }
+
+    let value_range = match bytes.get(pos) {
+      Some(quote @ (b'"' | b'\'')) => {
+        let value_start = pos + 1;
+        let value_end = bytes[value_start..]
+          .iter()
+          .position(|b| b == quote)
+          .map_or(bytes.len(), |len| value_start + len);
+        pos = (value_end + 1).min(bytes.len());
+        value_start..value_end
+      }
+      _ => {
+        let value_start = pos;
+        while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() && bytes[pos] != b'>' {
+          pos += 1;
+        }
+        value_start..pos
+      }
+    };
+    attrs.push(HtmlAttr { name, value: Some(&html[value_range.clone()]), value_range });
+  }
+}
+
+/// Find the start of the end tag of a raw text element, e.g. `</script>`.
+fn find_end_tag(html: &str, mut pos: usize, name: &str) -> Option<usize> {
+  let bytes = html.as_bytes();
+  while let Some(offset) = html[pos..].find("</") {
+    let start = pos + offset;
+    let name_end = start + 2 + name.len();
+    if bytes.get(start + 2..name_end).is_some_and(|tag| tag.eq_ignore_ascii_case(name.as_bytes()))
+      && bytes.get(name_end).is_none_or(|b| is_tag_delimiter(*b))
+    {
+      return Some(start);
+    }
+    pos = start + 2;
+  }
+  None
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum HtmlModuleScript<'a> {
+  /// `<script type="module" src="./main.js"></script>`
+  External(&'a str),
+  /// `<script type="module">console.log('inline')</script>`
+  Inline(&'a str),
+}
+
+/// Find all `<script type="module">` tags in a html document, along with the byte range of the whole tag.
+/// Classic scripts and scripts inside comments are skipped.
+pub fn find_module_scripts(html: &str) -> Vec<(Range<usize>, HtmlModuleScript<'_>)> {
+  tokenize(html)
+    .into_iter()
+    .filter_map(|token| match token {
+      HtmlToken::StartTag(tag)
+        if tag.is("script")
+          && tag.attr_value("type").is_some_and(|ty| ty.trim().eq_ignore_ascii_case("module")) =>
+      {
+        let script = match tag.attr_value("src") {
+          Some(src) => HtmlModuleScript::External(src.trim()),
+          None => HtmlModuleScript::Inline(&html[tag.text.clone()?]),
+        };
+        Some((tag.range, script))
+      }
+      _ => None,
+    })
+    .collect()
+}
+
+/// Find all `<link rel="stylesheet">` tags in a html document, along with the byte range of their
+/// `href` value.
+pub fn find_stylesheets(html: &str) -> Vec<(Range<usize>, &str)> {
+  tokenize(html)
+    .into_iter()
+    .filter_map(|token| match token {
+      HtmlToken::StartTag(tag)
+        if tag.is("link")
+          && tag.attr_value("rel").is_some_and(|rel| {
+            rel.split_ascii_whitespace().any(|rel| rel.eq_ignore_ascii_case("stylesheet"))
+          }) =>
+      {
+        let href = tag.attrs.into_iter().find(|attr| attr.name.eq_ignore_ascii_case("href"))?;
+        Some((href.value_range, href.value?))
+      }
+      _ => None,
+    })
+    .collect()
+}
+
+/// Return the offset of `</head>`, which is where elements like `<link rel="modulepreload">` should be injected.
+pub fn find_head_end(html: &str) -> Option<usize> {
+  tokenize(html).into_iter().find_map(|token| match token {
+    HtmlToken::EndTag { name, range } if name.eq_ignore_ascii_case("head") => Some(range.start),
+    _ => None,
+  })
+}
+
+#[test]
+fn test_find_module_scripts() {
+  let html = r#"<html>
+<head>
+  <script src="./classic.js"></script>
+  <!-- <script type="module" src="./commented.js"></script> -->
+  <script type="module" src="./main.js"></script>
+  <script type=module src='/app.ts'></script>
+  <script>const tag = '<script type="module" src="./string.js">'</script>
+</head>
+<body>
+  <textarea><script type="module" src="./textarea.js"></script></textarea>
+  <script type="module">
+    import './inline.js';
+  </script>
+</body>
+</html>"#;
+
+  let scripts = find_module_scripts(html).into_iter().map(|(_, script)| script).collect::<Vec<_>>();
+  assert_eq!(
+    scripts,
+    vec![
+      HtmlModuleScript::External("./main.js"),
+      HtmlModuleScript::External("/app.ts"),
+      HtmlModuleScript::Inline("\n    import './inline.js';\n  "),
+    ]
+  );
+  assert_eq!(&html[find_head_end(html).unwrap()..][..7], "</head>");
+}
+
+#[test]
+fn test_find_stylesheets() {
+  let html = r#"<link rel="stylesheet" href="./main.css"><link rel=icon href=/favicon.ico>
+<LINK REL="alternate Stylesheet" HREF=./dark.css>"#;
+
+  let stylesheets = find_stylesheets(html);
+  assert_eq!(
+    stylesheets.iter().map(|(_, href)| *href).collect::<Vec<_>>(),
+    vec!["./main.css", "./dark.css"]
+  );
+  assert_eq!(&html[stylesheets[0].0.clone()], "./main.css");
+}
@@ -1,8 +1,10 @@
 pub mod bitset;
+pub mod compressed_size;
 pub mod concat_string;
 pub mod ecmascript;
 pub mod global_reference;
 pub mod hash_placeholder;
+pub mod html;
 pub mod indexmap;
 pub mod option_ext;
 pub mod path_ext;
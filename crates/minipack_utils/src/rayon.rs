@@ -2,6 +2,7 @@ pub use rayon::iter::{
   IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator,
   IntoParallelRefMutIterator, ParallelBridge, ParallelIterator,
 };
+pub use rayon::spawn;
 
 fn _usages() {
   let mut demo = vec![1, 2, 3, 4, 5];